@@ -11,18 +11,43 @@
 use std::{env, path::PathBuf};
 
 fn main() {
+    // The simulator is a regular host executable and uses the default linker setup.
+    if cfg!(feature = "sim") {
+        return;
+    }
+
     println!("cargo:rustc-link-arg-bins=--nmagic");
     println!("cargo:rustc-link-arg-bins=-Tlink.x");
     println!("cargo:rustc-link-arg-bins=-Tdefmt.x");
 
     if cfg!(feature = "rp-pico") {
-        println!("cargo:rerun-if-changed=rp_memory.x");
-
         //println!("cargo:rustc-link-arg-bins=-Tlink-rp.x");
 
-        let out_dir = &PathBuf::from(env::var("OUT_DIR").unwrap());
-        println!("out dir: {out_dir:?}");
-        std::fs::copy("./rp_memory.x", out_dir.join("memory.x")).unwrap();
-        println!("cargo:rustc-link-search={}", out_dir.display());
+        copy_memory_x("rp_memory.x");
     }
+
+    if cfg!(feature = "rp2040") {
+        // Places the second stage bootloader provided by embassy-rp
+        println!("cargo:rustc-link-arg-bins=-Tlink-rp.x");
+
+        copy_memory_x("rp2040_memory.x");
+    }
+
+    if cfg!(feature = "nrf52840") {
+        copy_memory_x("nrf_memory.x");
+    }
+
+    if cfg!(feature = "qemu") {
+        copy_memory_x("qemu_memory.x");
+    }
+}
+
+/// Provide `file` as the `memory.x` used by the linker.
+fn copy_memory_x(file: &str) {
+    println!("cargo:rerun-if-changed={file}");
+
+    let out_dir = &PathBuf::from(env::var("OUT_DIR").unwrap());
+    println!("out dir: {out_dir:?}");
+    std::fs::copy(file, out_dir.join("memory.x")).unwrap();
+    println!("cargo:rustc-link-search={}", out_dir.display());
 }
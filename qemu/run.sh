@@ -0,0 +1,7 @@
+#!/usr/bin/env bash
+# Cargo runner for the QEMU platform: runs the firmware on an emulated LM3S6965EVB and decodes its
+# defmt log output. Requires `qemu-system-arm` and `defmt-print` (`cargo install defmt-print`).
+set -euo pipefail
+
+qemu-system-arm -cpu cortex-m3 -machine lm3s6965evb -nographic \
+    -semihosting-config enable=on,target=native -kernel "$1" | defmt-print -e "$1"
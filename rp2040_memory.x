@@ -0,0 +1,14 @@
+MEMORY {
+    /*
+     * The first 256 bytes of flash hold the second stage bootloader, which sets up the external
+     * flash for execute-in-place.
+     */
+    BOOT2 : ORIGIN = 0x10000000, LENGTH = 0x100
+    /* 2 MiB of flash on the Pico */
+    FLASH : ORIGIN = 0x10000100, LENGTH = 2048K - 0x100
+    /*
+     * 256 KiB of striped SRAM (banks 0-3). Banks 4 and 5 with 4 KiB each are left out for
+     * simplicity.
+     */
+    RAM   : ORIGIN = 0x20000000, LENGTH = 256K
+}
@@ -0,0 +1,117 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use async_trait::async_trait;
+use embassy_time::Duration;
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+use u8g2_fonts::U8g2TextStyle;
+
+use crate::peripherals::{ButtonEvent, PeripheralError};
+
+/// Marks an object that can update its internal state.
+#[async_trait]
+pub trait Update {
+    /// Update internal state. Using the `#[async_trait]` macro, this function's signature is simply
+    /// `async fn update(&mut self)`.
+    ///
+    /// The application cancels updates still running when a button event arrives (see
+    /// [`ModeManager::update_until`]), so the state should remain consistent at every `.await`.
+    ///
+    /// [`ModeManager::update_until`]: crate::manager::ModeManager::update_until
+    async fn update(&mut self);
+}
+
+/// Marks an object that can be drawn to a target as part of an application.
+pub trait Draw<D>
+where
+    D: DrawTarget,
+{
+    /// Draw the object into a specific area following the provided style.
+    fn draw_with_style(
+        &self,
+        style: &AppStyle<D::Color>,
+        draw_area: Rectangle,
+        target: &mut D,
+    ) -> Result<(), D::Error>;
+}
+
+/// Styling information for application elements.
+pub struct AppStyle<C> {
+    /// Style used for titles.
+    pub title_style: U8g2TextStyle<C>,
+    /// Style used for general text elements.
+    pub text_style: U8g2TextStyle<C>,
+    /// Default color for elements.
+    pub default_color: C,
+}
+
+impl<C> AppStyle<C> {
+    pub fn new(
+        title_style: U8g2TextStyle<C>,
+        text_style: U8g2TextStyle<C>,
+        default_color: C,
+    ) -> Self {
+        Self {
+            title_style,
+            text_style,
+            default_color,
+        }
+    }
+}
+
+/// Outcome of handing an event to a mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventResult {
+    /// The mode acted on the event.
+    Consumed,
+    /// The mode has no use for the event, leaving it to the application, e.g. for switching modes.
+    Ignored,
+}
+
+#[async_trait]
+/// An Application mode that can perform an update->draw loop.
+pub trait AppMode<D>: Update + Draw<D> + Send
+where
+    D: DrawTarget,
+{
+    /// Returns the title that should be displayed.
+    fn title(&self) -> String;
+
+    /// Preferred interval between updates of the mode.
+    fn update_interval(&self) -> Duration {
+        Duration::from_millis(100)
+    }
+
+    /// Preferred interval between redraws of the mode. By default, the mode is redrawn as often as
+    /// it is updated.
+    fn redraw_interval(&self) -> Duration {
+        self.update_interval()
+    }
+
+    async fn enter(&mut self) -> Result<(), PeripheralError> {
+        Ok(())
+    }
+
+    async fn exit(&mut self) -> Result<(), PeripheralError> {
+        Ok(())
+    }
+
+    /// Handle a button event while the mode is active. Events that aren't consumed fall through to
+    /// the application. Long presses are reserved for the application and never handed to modes,
+    /// see [`ModeManager`](crate::manager::ModeManager).
+    async fn handle_event(&mut self, _event: ButtonEvent) -> EventResult {
+        EventResult::Ignored
+    }
+
+    /// Labels of the actions the mode offers in the menu, e.g. resetting statistics. Labels may
+    /// change with the mode's state, e.g. to show the current value of a setting.
+    fn actions(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Perform the action at `index` of [`Self::actions`]. Indices past the last action are
+    /// ignored.
+    async fn perform_action(&mut self, _index: usize) -> Result<(), PeripheralError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+//! Histories of measured quantities, e.g. for plotting trends.
+
+use alloc::vec::Vec;
+use embassy_time::{Duration, Instant};
+
+/// Fixed-capacity history of timestamped values of a measured quantity, covering a span of time.
+///
+/// Values are kept at most once per `span / N`, values arriving sooner after the previous one are
+/// dropped. Once full, the oldest value is replaced by the newest.
+pub struct History<const N: usize = 60> {
+    /// Ring buffer of entries, the oldest at `start`.
+    entries: [(Instant, f32); N],
+    start: usize,
+    len: usize,
+    /// Minimum time between entries.
+    period: Duration,
+}
+
+impl<const N: usize> History<N> {
+    /// Create an empty history covering `span`.
+    pub const fn new(span: Duration) -> Self {
+        Self {
+            entries: [(Instant::MIN, 0.0); N],
+            start: 0,
+            len: 0,
+            period: Duration::from_ticks(span.as_ticks() / N as u64),
+        }
+    }
+
+    /// Span of time covered once the history is full.
+    pub fn span(&self) -> Duration {
+        self.period * N as u32
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Forget all entries.
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.len = 0;
+    }
+
+    /// Add `value` measured at `timestamp`, unless it follows the newest entry too closely.
+    pub fn push(&mut self, timestamp: Instant, value: f32) {
+        if let Some((newest, _)) = self.latest() {
+            if timestamp < newest + self.period {
+                return;
+            }
+        }
+
+        if self.len < N {
+            self.entries[(self.start + self.len) % N] = (timestamp, value);
+            self.len += 1;
+        } else {
+            self.entries[self.start] = (timestamp, value);
+            self.start = (self.start + 1) % N;
+        }
+    }
+
+    /// The newest entry, if any.
+    pub fn latest(&self) -> Option<(Instant, f32)> {
+        self.iter().last()
+    }
+
+    /// All entries, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = (Instant, f32)> + '_ {
+        (0..self.len).map(|i| self.entries[(self.start + i) % N])
+    }
+
+    /// Entries within the span before `now`, with their time in seconds relative to `now`. These
+    /// are the points of a [`crate::ui::LineChart`] scrolling with the time.
+    pub fn points(&self, now: Instant) -> Vec<(f32, f32)> {
+        let span = self.span();
+        self.iter()
+            .filter(|&(timestamp, _)| timestamp <= now && now - timestamp <= span)
+            .map(|(timestamp, value)| (-((now - timestamp).as_millis() as f32) / 1000.0, value))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: u64) -> Instant {
+        Instant::from_secs(secs)
+    }
+
+    #[test]
+    fn entries_are_spaced_by_period() {
+        let mut history = History::<10>::new(Duration::from_secs(10));
+        for secs in [0, 0, 1, 2] {
+            history.push(at(secs), secs as f32);
+        }
+        history.push(at(2) + Duration::from_millis(500), 2.5);
+        history.push(at(3), 3.0);
+
+        let values: Vec<_> = history.iter().map(|(_, value)| value).collect();
+        assert_eq!(values, [0.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn newest_entries_replace_oldest() {
+        let mut history = History::<3>::new(Duration::from_secs(3));
+        for secs in 0..5 {
+            history.push(at(secs), secs as f32);
+        }
+
+        assert_eq!(history.len(), 3);
+        let values: Vec<_> = history.iter().map(|(_, value)| value).collect();
+        assert_eq!(values, [2.0, 3.0, 4.0]);
+        assert_eq!(history.latest(), Some((at(4), 4.0)));
+
+        history.clear();
+        assert!(history.is_empty());
+        assert_eq!(history.latest(), None);
+    }
+
+    #[test]
+    fn points_are_relative_to_now() {
+        let mut history = History::<4>::new(Duration::from_secs(4));
+        for secs in 0..4 {
+            history.push(at(secs * 2), secs as f32);
+        }
+
+        // The first entry is older than the span, the last one lies in the future
+        let points = history.points(at(5));
+        assert_eq!(points, [(-3.0, 1.0), (-1.0, 2.0)]);
+    }
+}
@@ -0,0 +1,40 @@
+//! # Arduino Sensor Kit Core
+//!
+//! Platform independent parts of the Arduino Sensor Kit firmware. This crate has no hardware
+//! dependencies and builds for both the embedded targets and the host, where its behaviour can be
+//! unit-tested.
+//!
+//! The crate is structured into 2 layers: The application layer describing the various modes in
+//! [`mode`], and the peripheral layer in [`peripherals`] defining the abstract peripheral
+//! interfaces used by the application layer. Platform specific implementations of those interfaces
+//! are provided by the firmware.
+//!
+//! A mode is anything that implements [`app::AppMode`], which apart from some bookkeeping requires
+//! implementing both [`app::Update`] and [`app::Draw`]. The [`app::Update`] implementation defines
+//! how a mode updates its internal state (e.g. based on sensor data), and [`app::Draw`] determines
+//! what should be shown on the display during this mode, depending on the mode's internal state.
+//! Defining a new mode is as simple as freely defining these two operations. Modes can also react
+//! to button gestures through [`app::AppMode::handle_event`]. Reusable drawing elements used by the
+//! modes can be found in [`ui`], and [`manager::ModeManager`] switches between the modes.
+//!
+//! Float methods missing from `core`, such as `sqrt`, come from [`micromath::F32Ext`]. On the host,
+//! dependencies of the tests and the simulator link `std`, whose own methods take precedence and
+//! leave those imports unused. Unused imports are therefore only reported on bare-metal targets.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(test)]
+extern crate std;
+
+pub mod app;
+pub mod history;
+pub mod manager;
+pub mod mode;
+pub mod peripherals;
+pub mod statistics;
+pub mod trend;
+pub mod ui;
+
+#[cfg(test)]
+mod test_utils;
@@ -0,0 +1,463 @@
+//! Navigation between the modes of the application.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use async_trait::async_trait;
+use core::future::Future;
+use embassy_futures::select::{select, Either};
+use embassy_time::Duration;
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+
+use crate::app::{AppMode, AppStyle, Draw, EventResult, Update};
+use crate::peripherals::{AnalogInput, ButtonEvent, PeripheralError};
+use crate::ui::ListMenu;
+
+/// Keeps track of the active mode and switches between modes.
+///
+/// The gestures are mapped the same way in every mode:
+///
+/// | Gesture      | Action                                                          |
+/// | ------------ | --------------------------------------------------------------- |
+/// | Press        | Handed to the mode, switches to the next mode if ignored        |
+/// | Double click | Handed to the mode, switches to the previous mode if ignored    |
+/// | Long press   | Opens the menu, never handed to the mode                        |
+///
+/// Modes showing several views switch between them with a double click. The menu lists the
+/// actions of the active mode (see [`AppMode::actions`]), followed by all modes. An analog input
+/// (e.g. the potentiometer) selects an entry and a press confirms it, performing the action or
+/// switching to the mode. A double click closes the menu without doing either.
+pub struct ModeManager<'a, D>
+where
+    D: DrawTarget,
+{
+    /// All modes, in the order they are cycled through.
+    modes: Vec<Box<dyn AppMode<D> + 'a>>,
+    /// Index of the active mode.
+    current: usize,
+    /// Input selecting an entry of the menu.
+    menu_input: Box<dyn AnalogInput + 'a>,
+    /// Index of the selected menu entry, if the menu is open. Counts the active mode's actions
+    /// first, then the modes.
+    menu: Option<usize>,
+}
+
+impl<'a, D> ModeManager<'a, D>
+where
+    D: DrawTarget,
+{
+    /// Interval between updates and redraws of the menu, short enough to follow the input
+    /// smoothly.
+    const MENU_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// Create a new manager, starting with the first of `modes`. Call [`Self::start`] to enter it.
+    pub fn new(modes: Vec<Box<dyn AppMode<D> + 'a>>, menu_input: impl AnalogInput + 'a) -> Self {
+        Self {
+            modes,
+            current: 0,
+            menu_input: Box::new(menu_input),
+            menu: None,
+        }
+    }
+
+    /// Enter the active mode.
+    pub async fn start(&mut self) -> Result<(), PeripheralError> {
+        match self.modes.get_mut(self.current) {
+            Some(mode) => mode.enter().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Index of the active mode.
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Titles of all modes.
+    pub fn titles(&self) -> Vec<String> {
+        self.modes.iter().map(|mode| mode.title()).collect()
+    }
+
+    /// Preferred interval between updates of the active mode, or of the menu if it is open.
+    pub fn update_interval(&self) -> Duration {
+        match (self.menu, self.modes.get(self.current)) {
+            (None, Some(mode)) => mode.update_interval(),
+            _ => Self::MENU_INTERVAL,
+        }
+    }
+
+    /// Preferred interval between redraws of the active mode, or of the menu if it is open.
+    pub fn redraw_interval(&self) -> Duration {
+        match (self.menu, self.modes.get(self.current)) {
+            (None, Some(mode)) => mode.redraw_interval(),
+            _ => Self::MENU_INTERVAL,
+        }
+    }
+
+    /// Update like [`Update::update`], unless `interrupt` completes first, e.g. on a button event.
+    /// The update is then cancelled at the `.await` it is waiting at, and the output of
+    /// `interrupt` returned.
+    pub async fn update_until<T>(&mut self, interrupt: impl Future<Output = T>) -> Option<T> {
+        match select(self.update(), interrupt).await {
+            Either::First(()) => None,
+            Either::Second(output) => Some(output),
+        }
+    }
+
+    /// Title of the active mode, or of the menu if it is open.
+    pub fn title(&self) -> String {
+        match (self.menu, self.modes.get(self.current)) {
+            (None, Some(mode)) => mode.title(),
+            _ => String::from("Menu"),
+        }
+    }
+
+    /// Actions offered by the active mode.
+    fn actions(&self) -> Vec<String> {
+        match self.modes.get(self.current) {
+            Some(mode) => mode.actions(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Entries of the menu: the actions of the active mode, followed by the titles of all modes.
+    pub fn menu_entries(&self) -> Vec<String> {
+        let mut entries = self.actions();
+        entries.extend(self.titles());
+        entries
+    }
+
+    /// Switch to the mode at `index`, exiting the active mode and entering the new one. Indices
+    /// past the last mode are ignored.
+    pub async fn select(&mut self, index: usize) -> Result<(), PeripheralError> {
+        if index >= self.modes.len() || index == self.current {
+            return Ok(());
+        }
+        // Switch even if exiting fails, a mode shouldn't be able to trap the user
+        let exited = self.modes[self.current].exit().await;
+        self.current = index;
+        let entered = self.modes[self.current].enter().await;
+        exited.and(entered)
+    }
+
+    /// Switch to the next mode, wrapping around after the last one.
+    pub async fn next(&mut self) -> Result<(), PeripheralError> {
+        let count = self.modes.len().max(1);
+        self.select((self.current + 1) % count).await
+    }
+
+    /// Switch to the previous mode, wrapping around before the first one.
+    pub async fn previous(&mut self) -> Result<(), PeripheralError> {
+        let count = self.modes.len().max(1);
+        self.select((self.current + count - 1) % count).await
+    }
+
+    /// Open the menu, initially selecting the active mode.
+    pub fn open_menu(&mut self) {
+        self.menu = Some(self.actions().len() + self.current);
+    }
+
+    /// Close the menu without switching modes.
+    pub fn close_menu(&mut self) {
+        self.menu = None;
+    }
+
+    /// Whether the menu is open.
+    pub fn is_menu_open(&self) -> bool {
+        self.menu.is_some()
+    }
+
+    /// Handle a button event, see [`ModeManager`] for the gestures used.
+    pub async fn handle_event(&mut self, event: ButtonEvent) -> Result<(), PeripheralError> {
+        if let Some(selected) = self.menu {
+            match event {
+                ButtonEvent::Press => {
+                    self.close_menu();
+                    let actions = self.actions().len();
+                    if selected < actions {
+                        return self.modes[self.current].perform_action(selected).await;
+                    }
+                    return self.select(selected - actions).await;
+                }
+                ButtonEvent::DoubleClick => self.close_menu(),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        if event == ButtonEvent::LongPress {
+            self.open_menu();
+            return Ok(());
+        }
+
+        let Some(mode) = self.modes.get_mut(self.current) else {
+            return Ok(());
+        };
+        if mode.handle_event(event).await == EventResult::Consumed {
+            return Ok(());
+        }
+
+        match event {
+            ButtonEvent::Press => self.next().await,
+            ButtonEvent::DoubleClick => self.previous().await,
+            ButtonEvent::LongPress | ButtonEvent::Hold => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl<D> Update for ModeManager<'_, D>
+where
+    D: DrawTarget,
+{
+    /// Update the menu selection while the menu is open, or the active mode otherwise.
+    async fn update(&mut self) {
+        if self.menu.is_some() {
+            if let Ok(pct) = self.menu_input.input_pct().await {
+                let count = self.actions().len() + self.modes.len();
+                let index = (pct.clamp(0.0, 100.0) / 100.0 * count as f32) as usize;
+                self.menu = Some(index.min(count.saturating_sub(1)));
+            }
+        } else if let Some(mode) = self.modes.get_mut(self.current) {
+            mode.update().await;
+        }
+    }
+}
+
+impl<D> Draw<D> for ModeManager<'_, D>
+where
+    D: DrawTarget,
+{
+    fn draw_with_style(
+        &self,
+        style: &AppStyle<D::Color>,
+        draw_area: Rectangle,
+        target: &mut D,
+    ) -> Result<(), D::Error> {
+        match (self.menu, self.modes.get(self.current)) {
+            (Some(selected), _) => {
+                let entries = self.menu_entries();
+                ListMenu::new(
+                    &entries,
+                    selected,
+                    style.text_style.clone(),
+                    style.default_color,
+                    draw_area,
+                )
+                .draw(target)
+            }
+            (None, Some(mode)) => mode.draw_with_style(style, draw_area, target),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use core::future::{pending, ready};
+    use embassy_futures::block_on;
+    use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    use super::*;
+    use crate::mode::{AccelerationMode, LedMode, PotentiometerMode};
+    use crate::peripherals::mock::{MockAccelerometer, MockAnalogInput, MockPwm};
+    use crate::peripherals::{AccelerationReading, LatestReading, ReadingChannel};
+    use crate::test_utils::{draw, trend, Frame};
+
+    /// A manager with three modes and the menu input at `menu_pct` percent. Returns the LED of the
+    /// second mode, which is only enabled while that mode is active.
+    fn manager<D: DrawTarget>(menu_pct: u16) -> (ModeManager<'static, D>, MockPwm) {
+        let led = MockPwm::new();
+        let modes: Vec<Box<dyn AppMode<D>>> = vec![
+            Box::new(PotentiometerMode::new(
+                MockAnalogInput::constant(100, 50),
+                trend(PotentiometerMode::HISTORY_SPAN),
+            )),
+            Box::new(LedMode::new(
+                led.clone(),
+                MockAnalogInput::constant(100, 50),
+            )),
+            Box::new(AccelerationMode::new(
+                MockAccelerometer::failing(),
+                trend(AccelerationMode::HISTORY_SPAN),
+            )),
+        ];
+        let manager = ModeManager::new(modes, MockAnalogInput::constant(100, menu_pct));
+        (manager, led)
+    }
+
+    #[test]
+    fn next_and_previous_wrap_around() {
+        let (mut manager, _) = manager::<Frame>(0);
+        block_on(manager.previous()).unwrap();
+        assert_eq!(manager.current_index(), 2);
+        assert_eq!(manager.title(), "Acceleration");
+
+        block_on(manager.next()).unwrap();
+        assert_eq!(manager.current_index(), 0);
+    }
+
+    #[test]
+    fn switching_exits_and_enters_modes() {
+        let (mut manager, led) = manager::<Frame>(0);
+        block_on(manager.select(1)).unwrap();
+        assert!(led.is_enabled());
+
+        block_on(manager.select(2)).unwrap();
+        assert!(!led.is_enabled());
+
+        block_on(manager.select(5)).unwrap();
+        assert_eq!(manager.current_index(), 2);
+    }
+
+    #[test]
+    fn ignored_events_navigate() {
+        let (mut manager, _) = manager::<Frame>(0);
+        block_on(manager.handle_event(ButtonEvent::Press)).unwrap();
+        assert_eq!(manager.current_index(), 1);
+
+        block_on(manager.handle_event(ButtonEvent::DoubleClick)).unwrap();
+        assert_eq!(manager.current_index(), 0);
+
+        block_on(manager.handle_event(ButtonEvent::Hold)).unwrap();
+        assert_eq!(manager.current_index(), 0);
+        assert!(!manager.is_menu_open());
+    }
+
+    #[test]
+    fn consumed_events_stay_with_mode() {
+        let (mut manager, _) = manager::<Frame>(0);
+        block_on(manager.select(2)).unwrap();
+
+        // The acceleration mode shows its statistics on a double click
+        block_on(manager.handle_event(ButtonEvent::DoubleClick)).unwrap();
+        assert_eq!(manager.current_index(), 2);
+    }
+
+    #[test]
+    fn long_press_opens_menu_in_every_mode() {
+        let (mut manager, _) = manager::<Frame>(0);
+        for index in 0..manager.titles().len() {
+            block_on(manager.select(index)).unwrap();
+            block_on(manager.handle_event(ButtonEvent::LongPress)).unwrap();
+            assert!(
+                manager.is_menu_open(),
+                "{} kept the long press",
+                manager.title()
+            );
+
+            // The menu opens on the active mode, following its actions
+            let entries = manager.menu_entries();
+            assert_eq!(entries[manager.menu.unwrap()], manager.titles()[index]);
+            manager.close_menu();
+        }
+    }
+
+    #[test]
+    fn menu_lists_actions_of_active_mode() {
+        let (mut manager, _) = manager::<Frame>(0);
+        block_on(manager.select(2)).unwrap();
+        let entries = manager.menu_entries();
+        assert_eq!(entries[..2], ["Recenter ball", "Reset statistics"]);
+        assert_eq!(entries[2..], manager.titles());
+
+        block_on(manager.select(1)).unwrap();
+        assert_eq!(manager.menu_entries(), manager.titles());
+    }
+
+    #[test]
+    fn menu_performs_actions() {
+        // The first entry is the first action of the active mode
+        let (mut manager, _) = manager::<Frame>(0);
+        block_on(manager.select(2)).unwrap();
+        block_on(manager.handle_event(ButtonEvent::LongPress)).unwrap();
+        block_on(manager.update());
+        assert_eq!(manager.menu, Some(0));
+
+        block_on(manager.handle_event(ButtonEvent::Press)).unwrap();
+        assert!(!manager.is_menu_open());
+        assert_eq!(manager.current_index(), 2);
+    }
+
+    #[test]
+    fn menu_selects_mode_by_input() {
+        let (mut manager, led) = manager::<Frame>(99);
+        block_on(manager.handle_event(ButtonEvent::LongPress)).unwrap();
+        assert!(manager.is_menu_open());
+        assert_eq!(manager.title(), "Menu");
+
+        // The potentiometer mode offers a single action ahead of the modes
+        block_on(manager.update());
+        assert_eq!(manager.menu, Some(3));
+
+        block_on(manager.handle_event(ButtonEvent::Press)).unwrap();
+        assert!(!manager.is_menu_open());
+        assert_eq!(manager.current_index(), 2);
+        assert!(!led.is_enabled());
+    }
+
+    #[test]
+    fn menu_closes_without_switching() {
+        let (mut manager, _) = manager::<Frame>(50);
+        manager.open_menu();
+        block_on(manager.update());
+        assert_eq!(manager.menu, Some(2));
+
+        block_on(manager.handle_event(ButtonEvent::DoubleClick)).unwrap();
+        assert!(!manager.is_menu_open());
+        assert_eq!(manager.current_index(), 0);
+    }
+
+    #[test]
+    fn intervals_follow_active_mode() {
+        let (mut manager, _) = manager::<Frame>(0);
+        assert_eq!(manager.redraw_interval(), manager.update_interval());
+
+        // The acceleration mode draws less often than it updates
+        block_on(manager.select(2)).unwrap();
+        assert_eq!(manager.update_interval(), Duration::from_millis(20));
+        assert_eq!(manager.redraw_interval(), Duration::from_millis(50));
+
+        manager.open_menu();
+        assert_eq!(
+            manager.update_interval(),
+            ModeManager::<Frame>::MENU_INTERVAL
+        );
+        assert_eq!(
+            manager.redraw_interval(),
+            ModeManager::<Frame>::MENU_INTERVAL
+        );
+    }
+
+    #[test]
+    fn pending_update_is_interrupted() {
+        // Without readings, the acceleration mode keeps waiting for the first one
+        let channel = ReadingChannel::<CriticalSectionRawMutex, AccelerationReading, 1, 1>::new();
+        let accelerometer = LatestReading::new(&channel).unwrap();
+        let trend = trend(AccelerationMode::HISTORY_SPAN);
+        let modes: Vec<Box<dyn AppMode<Frame>>> =
+            vec![Box::new(AccelerationMode::new(accelerometer, trend))];
+        let mut waiting = ModeManager::new(modes, MockAnalogInput::constant(100, 0));
+
+        let interrupted = block_on(waiting.update_until(ready(ButtonEvent::Press)));
+        assert_eq!(interrupted, Some(ButtonEvent::Press));
+
+        // Updates completing first aren't interrupted
+        let (mut manager, _) = manager::<Frame>(0);
+        let interrupted = block_on(manager.update_until(pending::<ButtonEvent>()));
+        assert_eq!(interrupted, None);
+    }
+
+    #[test]
+    fn menu_replaces_mode_on_display() {
+        let (mut manager, _) = manager::<MockDisplay<BinaryColor>>(0);
+        block_on(manager.update());
+        let mode = draw(&manager);
+
+        manager.open_menu();
+        assert_ne!(draw(&manager), mode);
+    }
+}
@@ -0,0 +1,511 @@
+use accelerometer::vector::{F32x3, VectorExt};
+use alloc::vec::Vec;
+use alloc::{boxed::Box, string::String, vec};
+use async_trait::async_trait;
+use core::ops::Mul;
+use embassy_time::{Duration, Instant};
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{
+    Circle, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, StrokeAlignment, Styled,
+};
+use embedded_layout::align::Align;
+use embedded_layout::layout::linear::{FixedMargin, LinearLayout};
+use embedded_layout::prelude::*;
+use micromath::vector::{F32x2, Vector};
+#[cfg_attr(not(target_os = "none"), allow(unused_imports))]
+use micromath::F32Ext;
+
+use crate::app::{AppMode, EventResult};
+use crate::mode::{QuantityView, RESET_STATISTICS};
+use crate::trend::Trend;
+use crate::ui::{LineChart, StatisticsRow, StatisticsTable, VectorBox};
+use crate::{
+    app::{Draw, Update},
+    peripherals::{AccelerationReading, ButtonEvent, PeripheralError, Reading},
+};
+
+struct Area {
+    pub x_max: f32,
+    pub x_min: f32,
+    pub y_max: f32,
+    pub y_min: f32,
+}
+
+#[allow(dead_code)]
+impl Area {
+    pub fn width(&self) -> f32 {
+        self.x_max - self.x_min
+    }
+
+    pub fn height(&self) -> f32 {
+        self.y_max - self.y_min
+    }
+}
+
+/// Label of the menu action putting the ball back into the center.
+const RECENTER_BALL: &str = "Recenter ball";
+
+/// Struct defining the 'Acceleration' mode. Rolls a ball around a frame following the tilt of the
+/// accelerometer, next to an indicator of the acceleration. A double click switches to a chart of
+/// the acceleration's magnitude over the last minute and to its statistics, which an action of the
+/// menu resets.
+pub struct AccelerationMode<'a> {
+    input: Box<dyn AccelerationInput + 'a>,
+    acceleration: Option<F32x3>,
+    ball_position: F32x2,
+    ball_velocity: F32x2,
+    last_update: Instant,
+    /// Trend and statistics of the magnitude of the acceleration, in g.
+    trend: &'a Trend,
+    /// View shown.
+    view: QuantityView,
+}
+
+impl<'a> AccelerationMode<'a> {
+    const BALL_DIAMETER: u32 = 5;
+    const AREA: Area = Area {
+        x_max: 1.0,
+        x_min: -1.0,
+        y_max: 1.0,
+        y_min: -1.0,
+    };
+    /// Span of time covered by the chart.
+    pub const HISTORY_SPAN: Duration = Duration::from_secs(60);
+
+    /// Create the mode, showing the acceleration read from `input` and the `trend` of its
+    /// magnitude.
+    pub fn new(input: impl AccelerationInput + 'a, trend: &'a Trend) -> Self {
+        Self {
+            input: Box::new(input),
+            acceleration: None,
+            ball_position: F32x2 { x: 0.0, y: 0.0 },
+            ball_velocity: F32x2 { x: 0.0, y: 0.0 },
+            last_update: Instant::now(),
+            trend,
+            view: QuantityView::Live,
+        }
+    }
+
+    /// Record the magnitude of an accelerometer reading into the mode's `trend`.
+    pub fn record(trend: &Trend, reading: &Reading<AccelerationReading>) {
+        if let Ok(acceleration) = reading.value {
+            trend.push(reading.timestamp, acceleration.magnitude());
+        }
+    }
+}
+
+#[async_trait]
+pub trait AccelerationInput: Send {
+    async fn accel_norm(&mut self) -> Result<F32x3, PeripheralError>;
+}
+
+#[async_trait]
+impl Update for AccelerationMode<'_> {
+    async fn update(&mut self) {
+        self.acceleration = self.input.accel_norm().await.ok();
+
+        let now = Instant::now();
+        if let Some(acc) = self.acceleration {
+            // Get time delta in seconds
+            let delta = now - self.last_update;
+            let delta = (delta.as_millis() as f32) / 200.0;
+
+            // Flipped due to physical orientation of display and sensor
+            let mut acc = F32x2 {
+                x: -acc.y,
+                y: -acc.x,
+            };
+
+            // Apply friction
+            let vel_mag = self.ball_velocity.magnitude();
+            let friction = self.ball_velocity.mul(vel_mag.powi(2)).mul(0.01);
+            acc -= friction;
+
+            // Update state
+            self.ball_velocity += acc.mul(delta);
+            self.ball_position += self.ball_velocity.mul(delta);
+            self.last_update = now;
+
+            // Collision
+
+            if self.ball_position.x > Self::AREA.x_max {
+                self.ball_position.x = Self::AREA.x_max;
+                self.ball_velocity.x = 0.0;
+            }
+
+            if self.ball_position.x < Self::AREA.x_min {
+                self.ball_position.x = Self::AREA.x_min;
+                self.ball_velocity.x = 0.0;
+            }
+
+            if self.ball_position.y > Self::AREA.y_max {
+                self.ball_position.y = Self::AREA.y_max;
+                self.ball_velocity.y = 0.0;
+            }
+
+            if self.ball_position.y < Self::AREA.y_min {
+                self.ball_position.y = Self::AREA.y_min;
+                self.ball_velocity.y = 0.0;
+            }
+        }
+    }
+}
+
+impl<D> Draw<D> for AccelerationMode<'_>
+where
+    D: DrawTarget,
+{
+    fn draw_with_style(
+        &self,
+        style: &crate::app::AppStyle<<D as embedded_graphics::prelude::DrawTarget>::Color>,
+        draw_area: embedded_graphics::primitives::Rectangle,
+        target: &mut D,
+    ) -> Result<(), <D as embedded_graphics::prelude::DrawTarget>::Error> {
+        if self.view == QuantityView::Statistics {
+            let rows = [StatisticsRow::new("g", 2, self.trend.statistics())];
+            return StatisticsTable::new(&rows, style.text_style.clone(), draw_area).draw(target);
+        }
+        if self.view == QuantityView::Chart {
+            let points = self.trend.points(Instant::now());
+            return LineChart::new(
+                &points,
+                self.trend.span(),
+                " g",
+                style.text_style.clone(),
+                style.default_color,
+                draw_area,
+            )
+            .draw(target);
+        }
+
+        if self.acceleration.is_none() {
+            return Ok(());
+        }
+
+        let smallest_dimension = u32::min(draw_area.size.width, draw_area.size.height) as f32 * 0.9;
+
+        let acc_2d = match self.acceleration {
+            Some(acc) => F32x2 {
+                x: -acc.y,
+                y: -acc.x,
+            },
+            None => F32x2 { x: 0.0, y: 0.0 },
+        };
+
+        let indicator_style = PrimitiveStyleBuilder::new()
+            .stroke_width(1)
+            .stroke_color(style.default_color)
+            .stroke_alignment(StrokeAlignment::Center)
+            .build();
+
+        let indicator = VectorBox::new(
+            Rectangle::new(
+                Point::zero(),
+                Size::new(smallest_dimension as u32, smallest_dimension as u32),
+            ),
+            acc_2d,
+            1.0,
+        )
+        .into_styled(indicator_style);
+
+        // Frame with ball
+        let frame_height = draw_area.size.height as f32 * 0.9;
+
+        let scale = (frame_height - Self::BALL_DIAMETER as f32) / Self::AREA.height();
+        let scaled_position = self.ball_position.mul(scale);
+        let offset = Point::new(scaled_position.x as i32, scaled_position.y as i32);
+
+        let frame_style = PrimitiveStyleBuilder::new()
+            .stroke_color(style.default_color)
+            .stroke_width(1)
+            .stroke_alignment(StrokeAlignment::Center)
+            .build();
+
+        let frame = Rectangle::new(
+            Point::zero(),
+            Size::new(frame_height as u32, frame_height as u32),
+        )
+        .into_styled(frame_style);
+
+        let ball_style = PrimitiveStyleBuilder::new()
+            .fill_color(style.default_color)
+            .build();
+
+        let ball = Circle::new(Point::zero(), Self::BALL_DIAMETER).into_styled(ball_style);
+
+        let ball_in_frame = BallInFrame::new(frame, ball, offset);
+
+        let layout = LinearLayout::horizontal(Chain::new(indicator).append(ball_in_frame))
+            .with_spacing(FixedMargin(5))
+            .with_alignment(vertical::Top)
+            .arrange()
+            .align_to(&draw_area, horizontal::Center, vertical::Center);
+
+        layout.draw(target)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<D> AppMode<D> for AccelerationMode<'_>
+where
+    D: DrawTarget,
+{
+    fn title(&self) -> String {
+        String::from("Acceleration")
+    }
+
+    /// Frequent updates keep the ball rolling smoothly.
+    fn update_interval(&self) -> Duration {
+        Duration::from_millis(20)
+    }
+
+    /// Sending a frame to the display takes longer than an update, so the ball is simulated in
+    /// smaller steps than it is drawn.
+    fn redraw_interval(&self) -> Duration {
+        Duration::from_millis(50)
+    }
+
+    /// A double click switches between the ball, the chart and the statistics.
+    async fn handle_event(&mut self, event: ButtonEvent) -> EventResult {
+        match event {
+            ButtonEvent::DoubleClick => {
+                self.view = self.view.next();
+                EventResult::Consumed
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn actions(&self) -> Vec<String> {
+        vec![String::from(RECENTER_BALL), String::from(RESET_STATISTICS)]
+    }
+
+    async fn perform_action(&mut self, index: usize) -> Result<(), PeripheralError> {
+        match index {
+            0 => {
+                self.ball_position = F32x2 { x: 0.0, y: 0.0 };
+                self.ball_velocity = F32x2 { x: 0.0, y: 0.0 };
+            }
+            1 => self.trend.reset_statistics(),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+struct BallInFrame<C>
+where
+    C: PixelColor,
+{
+    frame: Styled<Rectangle, PrimitiveStyle<C>>,
+    ball: Styled<Circle, PrimitiveStyle<C>>,
+    offset_from_center: Point,
+}
+
+impl<C> BallInFrame<C>
+where
+    C: PixelColor,
+{
+    pub fn new(
+        frame: Styled<Rectangle, PrimitiveStyle<C>>,
+        ball: Styled<Circle, PrimitiveStyle<C>>,
+        offset_from_center: Point,
+    ) -> Self {
+        Self {
+            frame,
+            ball,
+            offset_from_center,
+        }
+    }
+}
+
+impl<C> Transform for BallInFrame<C>
+where
+    C: PixelColor,
+{
+    fn translate(&self, by: Point) -> Self {
+        Self {
+            frame: self.frame.translate(by),
+            ..*self
+        }
+    }
+
+    fn translate_mut(&mut self, by: Point) -> &mut Self {
+        Transform::translate_mut(&mut self.frame, by);
+        self
+    }
+}
+
+impl<C> Dimensions for BallInFrame<C>
+where
+    C: PixelColor,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.frame.bounding_box()
+    }
+}
+
+impl<C> Drawable for BallInFrame<C>
+where
+    C: PixelColor,
+{
+    type Color = C;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let ball = self
+            .ball
+            .align_to(&self.frame, horizontal::Center, vertical::Center)
+            .translate(self.offset_from_center);
+
+        self.frame.draw(target)?;
+        ball.draw(target)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use embassy_futures::block_on;
+    use embassy_time::Timer;
+
+    use super::*;
+    use crate::peripherals::mock::MockAccelerometer;
+    use crate::test_utils::{assert_snapshot, draw, handle_event, perform_action, trend};
+
+    /// Acceleration mode reading `accelerometer`.
+    fn acceleration_mode(accelerometer: MockAccelerometer) -> AccelerationMode<'static> {
+        AccelerationMode::new(accelerometer, trend(AccelerationMode::HISTORY_SPAN))
+    }
+
+    fn tilted() -> F32x3 {
+        F32x3::new(0.0, -1.0, 0.0)
+    }
+
+    #[test]
+    fn ball_rolls_downhill() {
+        let mut mode = acceleration_mode(MockAccelerometer::constant(tilted()));
+        block_on(Timer::after_millis(50));
+        block_on(mode.update());
+
+        assert!(mode.ball_position.x > 0.0);
+        assert_eq!(mode.ball_position.y, 0.0);
+    }
+
+    #[test]
+    fn ball_stays_in_area() {
+        let mut mode = acceleration_mode(MockAccelerometer::constant(tilted()));
+        for _ in 0..10 {
+            block_on(Timer::after_millis(50));
+            block_on(mode.update());
+        }
+
+        assert_eq!(mode.ball_position.x, AccelerationMode::AREA.x_max);
+        assert_eq!(mode.ball_velocity.x, 0.0);
+    }
+
+    #[test]
+    fn action_recenters_ball() {
+        let mut mode = acceleration_mode(MockAccelerometer::constant(tilted()));
+        block_on(Timer::after_millis(50));
+        block_on(mode.update());
+
+        assert_eq!(
+            handle_event(&mut mode, ButtonEvent::Press),
+            EventResult::Ignored
+        );
+        assert!(mode.ball_position.x > 0.0);
+
+        perform_action(&mut mode, RECENTER_BALL);
+        assert_eq!((mode.ball_position.x, mode.ball_position.y), (0.0, 0.0));
+        assert_eq!((mode.ball_velocity.x, mode.ball_velocity.y), (0.0, 0.0));
+    }
+
+    #[test]
+    fn statistics_track_magnitude() {
+        let mut mode = acceleration_mode(MockAccelerometer::failing());
+        for reading in [
+            Ok(F32x3::new(0.0, 0.0, 1.0)),
+            Ok(F32x3::new(0.0, 3.0, 4.0)),
+            Err(PeripheralError::I2c),
+        ] {
+            AccelerationMode::record(mode.trend, &Reading::now(reading));
+        }
+        let statistics = mode.trend.statistics();
+        assert_eq!(statistics.count(), 2);
+        // `sqrt` is only approximated
+        assert!((statistics.max().unwrap() - 5.0).abs() < 0.2);
+
+        mode.view = QuantityView::Statistics;
+        let table = draw(&mode);
+        perform_action(&mut mode, RESET_STATISTICS);
+        assert_eq!(mode.trend.statistics().count(), 0);
+        assert_ne!(draw(&mode), table);
+    }
+
+    #[test]
+    fn double_click_cycles_views() {
+        let mut mode = acceleration_mode(MockAccelerometer::constant(tilted()));
+        block_on(mode.update());
+        let ball = draw(&mode);
+
+        assert_eq!(
+            handle_event(&mut mode, ButtonEvent::DoubleClick),
+            EventResult::Consumed
+        );
+        assert_eq!(mode.view, QuantityView::Chart);
+        let empty_chart = draw(&mode);
+        assert_ne!(empty_chart, ball);
+        mode.trend.push(Instant::now(), 1.0);
+        assert_ne!(draw(&mode), empty_chart);
+
+        handle_event(&mut mode, ButtonEvent::DoubleClick);
+        assert_eq!(mode.view, QuantityView::Statistics);
+        handle_event(&mut mode, ButtonEvent::DoubleClick);
+        assert_eq!(mode.view, QuantityView::Live);
+    }
+
+    #[test]
+    fn nothing_drawn_without_data() {
+        let mut mode = acceleration_mode(MockAccelerometer::failing());
+        block_on(mode.update());
+
+        assert!(draw(&mode).affected_area().is_zero_sized());
+    }
+
+    #[test]
+    fn drawn_with_data() {
+        let mut mode = acceleration_mode(MockAccelerometer::constant(tilted()));
+        block_on(mode.update());
+
+        assert!(!draw(&mode).affected_area().is_zero_sized());
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut mode = acceleration_mode(MockAccelerometer::failing());
+        assert_snapshot("acceleration_none", &mode);
+
+        mode.acceleration = Some(F32x3::new(0.0, 0.0, 1.0));
+        assert_snapshot("acceleration_flat", &mode);
+
+        // Ball resting in each corner, pushed there by a matching tilt
+        for (name, x, y) in [
+            ("top_left", -1.0, -1.0),
+            ("top_right", 1.0, -1.0),
+            ("bottom_left", -1.0, 1.0),
+            ("bottom_right", 1.0, 1.0),
+        ] {
+            mode.acceleration = Some(F32x3::new(-0.7 * y, -0.7 * x, 0.7));
+            mode.ball_position = F32x2 { x, y };
+            assert_snapshot(&format!("acceleration_{name}"), &mode);
+        }
+    }
+}
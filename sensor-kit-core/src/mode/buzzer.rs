@@ -0,0 +1,247 @@
+use alloc::format;
+use alloc::{boxed::Box, string::String};
+use async_trait::async_trait;
+use embedded_graphics::{prelude::*, text::Text};
+use embedded_layout::prelude::*;
+use fugit::HertzU32;
+#[cfg_attr(not(target_os = "none"), allow(unused_imports))]
+use micromath::F32Ext;
+
+use crate::app::AppMode;
+use crate::{
+    app::{Draw, Update},
+    peripherals::{AnalogInput, PeripheralError},
+};
+
+/// Struct defining the 'Buzzer Mode'. Samples from an analog input and adjusts buzzer frequency
+/// according to a logarithmic scale.
+pub struct BuzzerMode<'a> {
+    /// Input determining the frequency.
+    input: Box<dyn AnalogInput + 'a>,
+    /// Buzzer output.
+    output: Box<dyn BuzzerOutput + 'a>,
+    /// Frequency currently produced by the buzzer, if any.
+    frequency: Option<HertzU32>,
+}
+
+impl<'a> BuzzerMode<'a> {
+    const FREQ_MIN: HertzU32 = HertzU32::Hz(10);
+    const FREQ_MAX: HertzU32 = HertzU32::Hz(500);
+
+    pub fn new(input: impl AnalogInput + 'a, output: impl BuzzerOutput + 'a) -> Self {
+        Self {
+            input: Box::new(input),
+            output: Box::new(output),
+            frequency: None,
+        }
+    }
+}
+
+fn interp_log(pct: f32, min: f32, max: f32) -> f32 {
+    let min_log = min.log10();
+    let max_log = max.log10();
+    let d = max_log - min_log;
+    let x = min_log + (pct / 100.0) * d;
+    10.0f32.powf(x)
+}
+
+#[async_trait]
+/// A buzzer output with variable frequency.
+pub trait BuzzerOutput: Send {
+    /// Enable buzzer output.
+    async fn enable(&mut self) -> Result<(), PeripheralError>;
+
+    /// Disable buzzer output.
+    async fn disable(&mut self) -> Result<(), PeripheralError>;
+
+    /// Set buzzer frequency. Returns the frequency actually produced, which may deviate from
+    /// `freq`.
+    async fn set_frequency(&mut self, freq: HertzU32) -> Result<HertzU32, PeripheralError>;
+}
+
+#[async_trait]
+impl Update for BuzzerMode<'_> {
+    async fn update(&mut self) {
+        let input = self.input.input_pct().await;
+        if let Ok(pct) = input {
+            // Set set_frequency
+            let freq = interp_log(
+                pct,
+                Self::FREQ_MIN.to_Hz() as f32,
+                Self::FREQ_MAX.to_Hz() as f32,
+            );
+            let freq = HertzU32::Hz(freq as u32);
+            // Show the true pitch, or nothing if the output can't produce it
+            self.frequency = self.output.set_frequency(freq).await.ok();
+        }
+    }
+}
+
+impl<D> Draw<D> for BuzzerMode<'_>
+where
+    D: DrawTarget,
+{
+    fn draw_with_style(
+        &self,
+        style: &crate::app::AppStyle<<D as DrawTarget>::Color>,
+        draw_area: embedded_graphics::primitives::Rectangle,
+        target: &mut D,
+    ) -> Result<(), <D as DrawTarget>::Error> {
+        let string = match self.frequency {
+            Some(freq) => format!("{:.2}Hz", freq.to_Hz()),
+            None => String::from("???"),
+        };
+
+        let text = Text::new(&string, Point::zero(), style.text_style.clone());
+
+        text.align_to(&draw_area, horizontal::Center, vertical::Center)
+            .draw(target)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<D> AppMode<D> for BuzzerMode<'_>
+where
+    D: DrawTarget,
+{
+    fn title(&self) -> String {
+        String::from("Buzzer")
+    }
+
+    async fn enter(&mut self) -> Result<(), PeripheralError> {
+        self.output.enable().await?;
+        if let Some(freq) = self.frequency {
+            self.frequency = Some(self.output.set_frequency(freq).await?);
+        }
+        Ok(())
+    }
+
+    async fn exit(&mut self) -> Result<(), PeripheralError> {
+        self.output.disable().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::block_on;
+
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    use super::*;
+    use crate::peripherals::mock::{MockAnalogInput, MockPwm};
+    use crate::test_utils::{assert_snapshot, draw};
+
+    /// Assert that `freq` is within 1% of `expected`, as the logarithm is approximated.
+    fn assert_close(freq: Option<HertzU32>, expected: u32) {
+        let freq = freq.expect("no frequency").to_Hz();
+        assert!(
+            freq.abs_diff(expected) * 100 <= expected,
+            "{freq}Hz isn't {expected}Hz"
+        );
+    }
+
+    #[test]
+    fn interp_log_covers_range() {
+        assert!((interp_log(0.0, 10.0, 500.0) - 10.0).abs() < 0.5);
+        assert!((interp_log(100.0, 10.0, 500.0) - 500.0).abs() < 5.0);
+        // Halfway on a logarithmic scale is the geometric mean
+        assert!((interp_log(50.0, 10.0, 1000.0) - 100.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn update_sets_frequency_in_range() {
+        for raw in [0, 1024, 2048, 3072, 4096] {
+            let mut mode = BuzzerMode::new(MockAnalogInput::constant(4096, raw), MockPwm::new());
+            block_on(mode.update());
+
+            let freq = mode.frequency.unwrap();
+            assert!((9..=500).contains(&freq.to_Hz()), "{freq} out of range");
+        }
+    }
+
+    #[test]
+    fn full_input_sets_maximum_frequency() {
+        let pwm = MockPwm::new();
+        let mut mode = BuzzerMode::new(MockAnalogInput::constant(4096, 4096), pwm.clone());
+        block_on(mode.update());
+
+        assert_close(pwm.frequency(), 500);
+    }
+
+    #[test]
+    fn empty_input_sets_minimum_frequency() {
+        let pwm = MockPwm::new();
+        let mut mode = BuzzerMode::new(MockAnalogInput::constant(4096, 0), pwm.clone());
+        block_on(mode.update());
+
+        assert_close(pwm.frequency(), 10);
+    }
+
+    #[test]
+    fn enter_restores_frequency_and_exit_silences() {
+        let pwm = MockPwm::new();
+        let mut mode = BuzzerMode::new(MockAnalogInput::constant(4096, 4096), pwm.clone());
+        block_on(mode.update());
+        pwm.clear();
+
+        block_on(AppMode::<MockDisplay<BinaryColor>>::enter(&mut mode)).unwrap();
+        assert!(pwm.is_enabled());
+        assert_eq!(pwm.duty_cycle_percent(), Some(50));
+        assert_close(pwm.frequency(), 500);
+
+        block_on(AppMode::<MockDisplay<BinaryColor>>::exit(&mut mode)).unwrap();
+        assert!(!pwm.is_enabled());
+    }
+
+    #[test]
+    fn update_shows_achieved_frequency() {
+        let pwm = MockPwm::quantizing(HertzU32::Hz(30), 100);
+        let mut mode = BuzzerMode::new(MockAnalogInput::constant(4096, 4096), pwm.clone());
+        block_on(mode.update());
+
+        assert_close(pwm.frequency(), 500);
+        assert_eq!(mode.frequency, Some(HertzU32::Hz(480)));
+    }
+
+    #[test]
+    fn update_clears_frequency_on_output_error() {
+        let pwm = MockPwm::quantizing(HertzU32::Hz(1000), 100);
+        let mut mode = BuzzerMode::new(MockAnalogInput::constant(4096, 4096), pwm);
+        mode.frequency = Some(HertzU32::Hz(100));
+        block_on(mode.update());
+
+        assert_eq!(mode.frequency, None);
+    }
+
+    #[test]
+    fn update_keeps_frequency_on_error() {
+        let mut mode = BuzzerMode::new(MockAnalogInput::failing(4096), MockPwm::new());
+        block_on(mode.update());
+        assert_eq!(mode.frequency, None);
+    }
+
+    #[test]
+    fn drawing_depends_on_value() {
+        let mut working = BuzzerMode::new(MockAnalogInput::constant(4096, 1024), MockPwm::new());
+        let broken = BuzzerMode::new(MockAnalogInput::failing(4096), MockPwm::new());
+        block_on(working.update());
+
+        assert_ne!(draw(&working), draw(&broken));
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut mode = BuzzerMode::new(MockAnalogInput::failing(4096), MockPwm::new());
+        for (name, frequency) in [
+            ("none", None),
+            ("min", Some(BuzzerMode::FREQ_MIN)),
+            ("max", Some(BuzzerMode::FREQ_MAX)),
+        ] {
+            mode.frequency = frequency;
+            assert_snapshot(&format!("buzzer_{name}"), &mode);
+        }
+    }
+}
@@ -0,0 +1,419 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use async_trait::async_trait;
+use core::fmt::Write;
+use embassy_time::{Duration, Instant};
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::text::Text;
+use embedded_layout::layout::linear::spacing::DistributeFill;
+use embedded_layout::layout::linear::{FixedMargin, LinearLayout};
+use embedded_layout::prelude::*;
+
+use crate::app::{AppMode, AppStyle, EventResult};
+use crate::app::{Draw, Update};
+use crate::mode::RESET_STATISTICS;
+use crate::peripherals::{ButtonEvent, EnvironmentReading, PeripheralError, Reading};
+use crate::trend::Trend;
+use crate::ui::{LineChart, StatisticsRow, StatisticsTable};
+
+/// View shown by the [`EnvironmentMode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EnvironmentView {
+    /// Table of the latest values.
+    Table,
+    /// Chart of the temperature.
+    Temperature,
+    /// Chart of the humidity.
+    Humidity,
+    /// Chart of the pressure.
+    Pressure,
+    /// Statistics of all quantities.
+    Statistics,
+}
+
+impl EnvironmentView {
+    /// The view following this one, wrapping around after the last one.
+    fn next(self) -> Self {
+        match self {
+            Self::Table => Self::Temperature,
+            Self::Temperature => Self::Humidity,
+            Self::Humidity => Self::Pressure,
+            Self::Pressure => Self::Statistics,
+            Self::Statistics => Self::Table,
+        }
+    }
+}
+
+/// Trends of the quantities shown by the [`EnvironmentMode`], over the last hour.
+pub struct EnvironmentTrends {
+    /// Trend of the temperature in °C.
+    pub temperature: Trend,
+    /// Trend of the humidity in %.
+    pub humidity: Trend,
+    /// Trend of the pressure in kPa.
+    pub pressure: Trend,
+}
+
+impl EnvironmentTrends {
+    /// Span of time covered by the trends.
+    const SPAN: Duration = Duration::from_secs(60 * 60);
+
+    /// Create empty trends.
+    pub const fn new() -> Self {
+        Self {
+            temperature: Trend::new(Self::SPAN),
+            humidity: Trend::new(Self::SPAN),
+            pressure: Trend::new(Self::SPAN),
+        }
+    }
+
+    /// Record a reading of the environment sensors, skipping the quantities that failed.
+    pub fn push(&self, reading: &Reading<EnvironmentReading>) {
+        let values = &reading.value;
+        for (trend, value) in [
+            (&self.temperature, values.temperature_c),
+            (&self.humidity, values.humidity_pct),
+            (&self.pressure, values.pressure_kpa),
+        ] {
+            if let Ok(value) = value {
+                trend.push(reading.timestamp, value);
+            }
+        }
+    }
+}
+
+impl Default for EnvironmentTrends {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Struct defining the 'Environment' mode. Samples data from a set of sensors and displays it in a
+/// table. A double click switches between the table, charts of each quantity over the last hour and
+/// their statistics, which an action of the menu resets.
+pub struct EnvironmentMode<'a> {
+    /// Sensors used as data source.
+    sensors: Box<dyn EnvironmentSensors + 'a>,
+    /// Temperature in °C.
+    temperature_c: Option<f32>,
+    /// Humidity in %.
+    humidity_pct: Option<f32>,
+    /// Pressure in kPa.
+    pressure_kpa: Option<f32>,
+    /// Trends and statistics of all quantities.
+    trends: &'a EnvironmentTrends,
+    /// View shown.
+    view: EnvironmentView,
+}
+
+impl<'a> EnvironmentMode<'a> {
+    /// Create the mode, showing the latest values read from `sensors` and their `trends`.
+    pub fn new(sensors: impl EnvironmentSensors + 'a, trends: &'a EnvironmentTrends) -> Self {
+        let sensors = Box::new(sensors);
+
+        Self {
+            sensors,
+            temperature_c: None,
+            humidity_pct: None,
+            pressure_kpa: None,
+            trends,
+            view: EnvironmentView::Table,
+        }
+    }
+
+    /// Draw the latest values as a table.
+    fn draw_table<D>(
+        &self,
+        style: &AppStyle<D::Color>,
+        draw_area: Rectangle,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+    {
+        let mut temp_str = String::new();
+        if let Some(temperature) = self.temperature_c {
+            _ = write!(&mut temp_str, "{:.2}°C", temperature);
+        } else {
+            _ = write!(&mut temp_str, "???");
+        }
+
+        let mut hum_str = String::new();
+        if let Some(humidity) = self.humidity_pct {
+            _ = write!(&mut hum_str, "{:.2}%", humidity);
+        } else {
+            _ = write!(&mut hum_str, "???");
+        }
+
+        let mut pres_str = String::new();
+        if let Some(pressure) = self.pressure_kpa {
+            _ = write!(&mut pres_str, "{:.1}kPa", pressure);
+        } else {
+            _ = write!(&mut pres_str, "???");
+        }
+
+        let temp_label = Text::new("Temp.:", Point::zero(), style.text_style.clone());
+        let temp_value = Text::new(&temp_str, Point::zero(), style.text_style.clone());
+        let hum_label = Text::new("Humidity:", Point::zero(), style.text_style.clone());
+        let hum_value = Text::new(&hum_str, Point::zero(), style.text_style.clone());
+        let pres_label = Text::new("Pressure:", Point::zero(), style.text_style.clone());
+        let pres_value = Text::new(&pres_str, Point::zero(), style.text_style.clone());
+
+        let temp_line = LinearLayout::horizontal(Chain::new(temp_label).append(temp_value))
+            .with_spacing(DistributeFill(draw_area.size.width))
+            .arrange();
+
+        let hum_line = LinearLayout::horizontal(Chain::new(hum_label).append(hum_value))
+            .with_spacing(DistributeFill(draw_area.size.width))
+            .arrange();
+
+        let pres_line = LinearLayout::horizontal(Chain::new(pres_label).append(pres_value))
+            .with_spacing(DistributeFill(draw_area.size.width))
+            .arrange();
+
+        let all_lines =
+            LinearLayout::vertical(Chain::new(temp_line).append(hum_line).append(pres_line))
+                .with_spacing(FixedMargin(2))
+                .arrange()
+                .align_to(&draw_area, horizontal::Center, vertical::Center);
+
+        all_lines.draw(target)
+    }
+}
+
+#[async_trait]
+impl Update for EnvironmentMode<'_> {
+    async fn update(&mut self) {
+        let reading = self.sensors.read().await;
+        self.temperature_c = reading.temperature_c.ok();
+        self.humidity_pct = reading.humidity_pct.ok();
+        self.pressure_kpa = reading.pressure_kpa.ok();
+    }
+}
+
+impl<D> Draw<D> for EnvironmentMode<'_>
+where
+    D: DrawTarget,
+{
+    fn draw_with_style(
+        &self,
+        style: &AppStyle<D::Color>,
+        draw_area: Rectangle,
+        target: &mut D,
+    ) -> Result<(), D::Error> {
+        let (trend, unit) = match self.view {
+            EnvironmentView::Table => return self.draw_table(style, draw_area, target),
+            EnvironmentView::Temperature => (&self.trends.temperature, "°C"),
+            EnvironmentView::Humidity => (&self.trends.humidity, "%"),
+            EnvironmentView::Pressure => (&self.trends.pressure, "kPa"),
+            EnvironmentView::Statistics => {
+                let rows = [
+                    StatisticsRow::new("°C", 1, self.trends.temperature.statistics()),
+                    StatisticsRow::new("%", 0, self.trends.humidity.statistics()),
+                    StatisticsRow::new("kPa", 1, self.trends.pressure.statistics()),
+                ];
+                return StatisticsTable::new(&rows, style.text_style.clone(), draw_area)
+                    .draw(target);
+            }
+        };
+
+        let points = trend.points(Instant::now());
+        LineChart::new(
+            &points,
+            trend.span(),
+            unit,
+            style.text_style.clone(),
+            style.default_color,
+            draw_area,
+        )
+        .draw(target)
+    }
+}
+
+#[async_trait]
+impl<D> AppMode<D> for EnvironmentMode<'_>
+where
+    D: DrawTarget,
+{
+    fn title(&self) -> String {
+        String::from("Environment")
+    }
+
+    /// Temperature, humidity and pressure change slowly, and the DHT20 takes a while to measure.
+    fn update_interval(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+
+    /// A double click switches to the next view.
+    async fn handle_event(&mut self, event: ButtonEvent) -> EventResult {
+        match event {
+            ButtonEvent::DoubleClick => {
+                self.view = self.view.next();
+                EventResult::Consumed
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn actions(&self) -> Vec<String> {
+        vec![String::from(RESET_STATISTICS)]
+    }
+
+    async fn perform_action(&mut self, index: usize) -> Result<(), PeripheralError> {
+        if index == 0 {
+            self.trends.temperature.reset_statistics();
+            self.trends.humidity.reset_statistics();
+            self.trends.pressure.reset_statistics();
+        }
+        Ok(())
+    }
+}
+
+/// Defines interface for environment sensors that can be used by the [`EnvironmentMode`].
+#[async_trait]
+pub trait EnvironmentSensors: Send {
+    /// Measure temperature, humidity and air pressure, each sensor once.
+    async fn read(&mut self) -> EnvironmentReading;
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+    use embassy_futures::block_on;
+
+    use super::*;
+    use crate::peripherals::mock::MockEnvironmentSensors;
+    use crate::test_utils::{assert_snapshot, draw, handle_event, perform_action};
+
+    /// Environment mode reading `sensors`.
+    fn environment_mode(sensors: MockEnvironmentSensors) -> EnvironmentMode<'static> {
+        EnvironmentMode::new(sensors, Box::leak(Box::new(EnvironmentTrends::new())))
+    }
+
+    #[test]
+    fn update_reads_all_sensors() {
+        let mut mode = environment_mode(MockEnvironmentSensors::constant(21.5, 40.0, 101.3));
+        block_on(mode.update());
+
+        assert_eq!(mode.temperature_c, Some(21.5));
+        assert_eq!(mode.humidity_pct, Some(40.0));
+        assert_eq!(mode.pressure_kpa, Some(101.3));
+    }
+
+    #[test]
+    fn update_discards_values_on_error() {
+        let mut mode = environment_mode(MockEnvironmentSensors::failing());
+        block_on(mode.update());
+
+        assert_eq!(mode.temperature_c, None);
+        assert_eq!(mode.humidity_pct, None);
+        assert_eq!(mode.pressure_kpa, None);
+    }
+
+    #[test]
+    fn trends_skip_failed_quantities() {
+        let trends = EnvironmentTrends::new();
+        let reading = EnvironmentReading {
+            temperature_c: Ok(21.5),
+            humidity_pct: Err(PeripheralError::I2c),
+            pressure_kpa: Ok(101.3),
+        };
+        trends.push(&Reading::now(reading));
+
+        assert_eq!(trends.temperature.latest().unwrap().1, 21.5);
+        assert_eq!(trends.humidity.latest(), None);
+        assert_eq!(trends.pressure.latest().unwrap().1, 101.3);
+    }
+
+    #[test]
+    fn chart_shows_trend() {
+        let mut mode = environment_mode(MockEnvironmentSensors::failing());
+        mode.view = EnvironmentView::Pressure;
+        let empty = draw(&mode);
+
+        mode.trends.pressure.push(Instant::now(), 101.3);
+        assert_ne!(draw(&mode), empty);
+    }
+
+    #[test]
+    fn statistics_follow_recorded_readings() {
+        let mut mode = environment_mode(MockEnvironmentSensors::constant(21.5, 40.0, 101.3));
+        // Readings shown by the mode aren't recorded, only those from the channel are
+        block_on(mode.update());
+        assert_eq!(mode.trends.temperature.statistics().count(), 0);
+
+        for temperature_c in [18.0, 24.0] {
+            let reading = EnvironmentReading {
+                temperature_c: Ok(temperature_c),
+                humidity_pct: Ok(40.0),
+                pressure_kpa: Err(PeripheralError::I2c),
+            };
+            mode.trends.push(&Reading::now(reading));
+        }
+        let temperature = mode.trends.temperature.statistics();
+        assert_eq!(temperature.min(), Some(18.0));
+        assert_eq!(temperature.max(), Some(24.0));
+        assert_eq!(temperature.mean(), Some(21.0));
+        assert_eq!(mode.trends.humidity.statistics().count(), 2);
+        assert_eq!(mode.trends.pressure.statistics().count(), 0);
+
+        mode.view = EnvironmentView::Statistics;
+        let statistics = draw(&mode);
+        perform_action(&mut mode, RESET_STATISTICS);
+        assert_eq!(mode.trends.temperature.statistics().count(), 0);
+        assert_eq!(mode.trends.humidity.statistics().count(), 0);
+        assert_ne!(draw(&mode), statistics);
+    }
+
+    #[test]
+    fn double_click_cycles_views() {
+        let mut mode = environment_mode(MockEnvironmentSensors::constant(21.5, 40.0, 101.3));
+        block_on(mode.update());
+        let table = draw(&mode);
+
+        assert_eq!(
+            handle_event(&mut mode, ButtonEvent::DoubleClick),
+            EventResult::Consumed
+        );
+        assert_eq!(mode.view, EnvironmentView::Temperature);
+        assert_ne!(draw(&mode), table);
+
+        for _ in 0..4 {
+            handle_event(&mut mode, ButtonEvent::DoubleClick);
+        }
+        assert_eq!(mode.view, EnvironmentView::Table);
+        assert_eq!(
+            handle_event(&mut mode, ButtonEvent::Press),
+            EventResult::Ignored
+        );
+    }
+
+    #[test]
+    fn drawing_depends_on_values() {
+        let mut working = environment_mode(MockEnvironmentSensors::constant(21.5, 40.0, 101.3));
+        let mut broken = environment_mode(MockEnvironmentSensors::failing());
+        block_on(working.update());
+        block_on(broken.update());
+
+        assert_ne!(draw(&working), draw(&broken));
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut mode = environment_mode(MockEnvironmentSensors::failing());
+        assert_snapshot("environment_none", &mode);
+
+        mode.temperature_c = Some(21.5);
+        mode.humidity_pct = Some(40.25);
+        mode.pressure_kpa = Some(101.3);
+        assert_snapshot("environment_typical", &mode);
+
+        mode.temperature_c = Some(-10.75);
+        mode.humidity_pct = Some(100.0);
+        mode.pressure_kpa = Some(95.0);
+        assert_snapshot("environment_extreme", &mode);
+    }
+}
@@ -0,0 +1,196 @@
+use alloc::{boxed::Box, format, string::String};
+use async_trait::async_trait;
+use embedded_graphics::{prelude::*, text::Text};
+use embedded_layout::prelude::*;
+
+use crate::{
+    app::{AppMode, Draw, Update},
+    peripherals::{AnalogInput, PeripheralError},
+};
+
+/// Struct defining the 'LED Mode'. Sampled from an analog input and sets LED brightness
+/// accordingly.
+pub struct LedMode<'a> {
+    /// LED output.
+    led: Box<dyn LedOutput + 'a>,
+    /// Input determining the brightness.
+    input: Box<dyn AnalogInput + Send + 'a>,
+    /// Brightness currently produced by the LED, if any.
+    brightness_pct: Option<f32>,
+}
+
+impl<'a> LedMode<'a> {
+    pub fn new(led: impl LedOutput + 'a, input: impl AnalogInput + 'a) -> Self {
+        Self {
+            led: Box::new(led),
+            input: Box::new(input),
+            brightness_pct: None,
+        }
+    }
+}
+
+#[async_trait]
+/// An LED output with variable brightness.
+pub trait LedOutput: Send {
+    /// Set brightness in percent. Returns the brightness actually produced, which may deviate from
+    /// `brightness_pct` depending on the resolution of the output.
+    async fn set_brightness(&mut self, brightness_pct: f32) -> Result<f32, PeripheralError>;
+
+    /// Enable LED output.
+    async fn enable(&mut self) -> Result<(), PeripheralError> {
+        Ok(())
+    }
+
+    /// Disable LED output.
+    async fn disable(&mut self) -> Result<(), PeripheralError> {
+        self.set_brightness(0.0).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Update for LedMode<'_> {
+    async fn update(&mut self) {
+        let input = self.input.input_pct().await;
+        let brightness = self.led.set_brightness(input.unwrap_or(0.0)).await;
+        // Turn the LED off on input errors, but only show the brightness for valid input
+        self.brightness_pct = input.and(brightness).ok();
+    }
+}
+
+impl<D> Draw<D> for LedMode<'_>
+where
+    D: DrawTarget,
+{
+    fn draw_with_style(
+        &self,
+        style: &crate::app::AppStyle<<D as DrawTarget>::Color>,
+        draw_area: embedded_graphics::primitives::Rectangle,
+        target: &mut D,
+    ) -> Result<(), <D as DrawTarget>::Error> {
+        let string = match self.brightness_pct {
+            Some(b) => format!("{b:.2}%"),
+            None => String::from("???"),
+        };
+
+        let text = Text::new(&string, Point::zero(), style.text_style.clone());
+
+        text.align_to(&draw_area, horizontal::Center, vertical::Center)
+            .draw(target)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<D> AppMode<D> for LedMode<'_>
+where
+    D: DrawTarget,
+{
+    fn title(&self) -> String {
+        String::from("Led")
+    }
+
+    async fn enter(&mut self) -> Result<(), PeripheralError> {
+        self.led.enable().await
+    }
+
+    async fn exit(&mut self) -> Result<(), PeripheralError> {
+        self.led.disable().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::block_on;
+
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    use super::*;
+    use fugit::HertzU32;
+
+    use crate::peripherals::mock::{MockAnalogInput, MockPwm, PwmCall};
+    use crate::test_utils::{assert_snapshot, draw};
+
+    #[test]
+    fn update_samples_input() {
+        let mut mode = LedMode::new(MockPwm::new(), MockAnalogInput::constant(4096, 1024));
+        block_on(mode.update());
+        assert_eq!(mode.brightness_pct, Some(25.0));
+    }
+
+    #[test]
+    fn update_sets_brightness() {
+        let pwm = MockPwm::new();
+        let mut mode = LedMode::new(pwm.clone(), MockAnalogInput::constant(4096, 3072));
+        block_on(mode.update());
+
+        assert_eq!(pwm.duty_cycle_percent(), Some(75));
+    }
+
+    #[test]
+    fn update_shows_achieved_brightness() {
+        let pwm = MockPwm::quantizing(HertzU32::Hz(1), 8);
+        let mut mode = LedMode::new(pwm.clone(), MockAnalogInput::constant(4096, 1229));
+        block_on(mode.update());
+
+        // 30% is requested, but the closest step below is 2 of 8
+        assert_eq!(pwm.duty_cycle_percent(), Some(30));
+        assert_eq!(mode.brightness_pct, Some(25.0));
+    }
+
+    #[test]
+    fn update_turns_led_off_on_error() {
+        let pwm = MockPwm::new();
+        let mut mode = LedMode::new(pwm.clone(), MockAnalogInput::failing(4096));
+        block_on(mode.update());
+
+        assert_eq!(mode.brightness_pct, None);
+        assert_eq!(pwm.duty_cycle_percent(), Some(0));
+    }
+
+    #[test]
+    fn enter_enables_led() {
+        let pwm = MockPwm::new();
+        let mut mode = LedMode::new(pwm.clone(), MockAnalogInput::constant(4096, 0));
+        block_on(AppMode::<MockDisplay<BinaryColor>>::enter(&mut mode)).unwrap();
+
+        assert!(pwm.is_enabled());
+    }
+
+    #[test]
+    fn exit_disables_led() {
+        let pwm = MockPwm::new();
+        let mut mode = LedMode::new(pwm.clone(), MockAnalogInput::constant(4096, 4096));
+        block_on(AppMode::<MockDisplay<BinaryColor>>::enter(&mut mode)).unwrap();
+        block_on(mode.update());
+        block_on(AppMode::<MockDisplay<BinaryColor>>::exit(&mut mode)).unwrap();
+
+        assert_eq!(pwm.calls().last(), Some(&PwmCall::Disable));
+    }
+
+    #[test]
+    fn drawing_depends_on_value() {
+        let mut working = LedMode::new(MockPwm::new(), MockAnalogInput::constant(4096, 1024));
+        let mut broken = LedMode::new(MockPwm::new(), MockAnalogInput::failing(4096));
+        block_on(working.update());
+        block_on(broken.update());
+
+        assert_ne!(draw(&working), draw(&broken));
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut mode = LedMode::new(MockPwm::new(), MockAnalogInput::failing(4096));
+        for (name, value) in [
+            ("none", None),
+            ("0", Some(0.0)),
+            ("50", Some(50.0)),
+            ("100", Some(100.0)),
+        ] {
+            mode.brightness_pct = value;
+            assert_snapshot(&format!("led_{name}"), &mode);
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use alloc::format;
+use alloc::vec::Vec;
+use alloc::{boxed::Box, string::String, vec};
+use async_trait::async_trait;
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{prelude::*, primitives::Circle, text::Text};
+use embedded_layout::layout::linear::{FixedMargin, LinearLayout};
+use embedded_layout::prelude::*;
+#[cfg_attr(not(target_os = "none"), allow(unused_imports))]
+use micromath::F32Ext;
+
+use crate::app::{AppMode, Draw, EventResult, Update};
+use crate::mode::{QuantityView, RESET_STATISTICS};
+use crate::peripherals::{
+    AnalogInput, AnalogReading, ButtonEvent, CalibratedAnalogInput, Calibration, PeripheralError,
+    Reading,
+};
+use crate::trend::Trend;
+use crate::ui::{FilledCircle, LineChart, StatisticsRow, StatisticsTable};
+
+/// Struct defining the 'Light Sensor' mode. Estimates the illuminance from a photoresistor through
+/// its calibration, see [`Photoresistor::calibration`], and displays it as a number and as a
+/// partially filled circle on a logarithmic scale. A double click switches to a chart of the
+/// light level over the last minute and to its statistics, which an action of the menu resets.
+/// Both track the light level in % of the circle's scale.
+///
+/// [`Photoresistor::calibration`]: crate::peripherals::Photoresistor::calibration
+pub struct LightSensorMode<'a> {
+    /// Input connected to the photoresistor's voltage divider, calibrated in lux.
+    input: CalibratedAnalogInput<Box<dyn AnalogInput + Sync + 'a>>,
+    /// Estimated illuminance in lux.
+    lux: Option<f32>,
+    /// Trend and statistics of the light level in %.
+    trend: &'a Trend,
+    /// View shown.
+    view: QuantityView,
+}
+
+impl<'a> LightSensorMode<'a> {
+    /// Illuminance shown as an empty circle, about that of a dark room.
+    const LUX_MIN: f32 = 1.0;
+    /// Illuminance shown as a full circle, about that of direct sunlight.
+    const LUX_MAX: f32 = 100_000.0;
+    /// Span of time covered by the chart.
+    pub const HISTORY_SPAN: Duration = Duration::from_secs(60);
+
+    /// Create the mode, showing the illuminance read from `input` and its `trend`.
+    pub fn new(
+        input: impl AnalogInput + Sync + 'a,
+        calibration: Calibration,
+        trend: &'a Trend,
+    ) -> Self {
+        Self {
+            input: CalibratedAnalogInput::new(Box::new(input), calibration, "lx"),
+            lux: None,
+            trend,
+            view: QuantityView::Live,
+        }
+    }
+
+    /// Record a reading of the light sensor into the mode's `trend`, as the light level in % of
+    /// the circle's scale, estimated through the sensor's `calibration`.
+    pub fn record(
+        trend: &Trend,
+        calibration: &Calibration,
+        reading: &Reading<Result<AnalogReading, PeripheralError>>,
+    ) {
+        if let Ok(value) = reading.value {
+            trend.push(
+                reading.timestamp,
+                Self::scale_pct(calibration.apply(value.raw)),
+            );
+        }
+    }
+
+    /// Position of `lux` on the logarithmic scale of the circle, in %.
+    fn scale_pct(lux: f32) -> f32 {
+        let min_log = Self::LUX_MIN.log10();
+        let max_log = Self::LUX_MAX.log10();
+        let pct = (lux.log10() - min_log) / (max_log - min_log) * 100.0;
+        pct.clamp(0.0, 100.0)
+    }
+}
+
+/// Format an illuminance with a precision fitting its magnitude.
+fn format_lux(lux: f32) -> String {
+    if lux < 10.0 {
+        format!("{lux:.1} lx")
+    } else if lux < 10_000.0 {
+        format!("{lux:.0} lx")
+    } else {
+        format!("{:.0}k lx", lux / 1000.0)
+    }
+}
+
+#[async_trait]
+impl Update for LightSensorMode<'_> {
+    async fn update(&mut self) {
+        self.lux = self.input.value().await.ok();
+    }
+}
+
+impl<D> Draw<D> for LightSensorMode<'_>
+where
+    D: DrawTarget,
+{
+    fn draw_with_style(
+        &self,
+        style: &crate::app::AppStyle<<D as embedded_graphics::prelude::DrawTarget>::Color>,
+        draw_area: embedded_graphics::primitives::Rectangle,
+        target: &mut D,
+    ) -> Result<(), <D as embedded_graphics::prelude::DrawTarget>::Error> {
+        if self.view == QuantityView::Statistics {
+            let rows = [StatisticsRow::new("%", 0, self.trend.statistics())];
+            return StatisticsTable::new(&rows, style.text_style.clone(), draw_area).draw(target);
+        }
+        if self.view == QuantityView::Chart {
+            let points = self.trend.points(Instant::now());
+            return LineChart::new(
+                &points,
+                self.trend.span(),
+                "%",
+                style.text_style.clone(),
+                style.default_color,
+                draw_area,
+            )
+            .draw(target);
+        }
+
+        let string = match self.lux {
+            Some(lux) => format_lux(lux),
+            None => String::from("???"),
+        };
+        let text = Text::new(&string, Point::zero(), style.text_style.clone());
+
+        // The circle takes up the left half, leaving the right half to the readout
+        let max_dimension = u32::min(draw_area.size.width / 2, draw_area.size.height);
+        let diameter = max_dimension as f32 * 0.9;
+
+        let circle = FilledCircle::new(
+            self.lux.map(Self::scale_pct).unwrap_or(0.0),
+            style.default_color,
+            Circle::new(Point::zero(), diameter as u32),
+        );
+
+        LinearLayout::horizontal(Chain::new(circle).append(text))
+            .with_alignment(vertical::Center)
+            .with_spacing(FixedMargin(5))
+            .arrange()
+            .align_to(&draw_area, horizontal::Center, vertical::Center)
+            .draw(target)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<D> AppMode<D> for LightSensorMode<'_>
+where
+    D: DrawTarget,
+{
+    fn title(&self) -> alloc::string::String {
+        String::from("Light Sensor")
+    }
+
+    /// A double click switches between the circle, the chart and the statistics.
+    async fn handle_event(&mut self, event: ButtonEvent) -> EventResult {
+        match event {
+            ButtonEvent::DoubleClick => {
+                self.view = self.view.next();
+                EventResult::Consumed
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn actions(&self) -> Vec<String> {
+        vec![String::from(RESET_STATISTICS)]
+    }
+
+    async fn perform_action(&mut self, index: usize) -> Result<(), PeripheralError> {
+        if index == 0 {
+            self.trend.reset_statistics();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::block_on;
+
+    use super::*;
+    use crate::peripherals::mock::MockAnalogInput;
+    use crate::peripherals::Photoresistor;
+    use crate::test_utils::{assert_snapshot, draw, handle_event, perform_action, trend};
+
+    /// Light sensor mode reading `input` through a photoresistor with its resistance at 10 lx
+    /// matching the fixed resistor.
+    fn light_mode(input: MockAnalogInput) -> LightSensorMode<'static> {
+        let ldr = Photoresistor::new(10_000.0, 10_000.0, 0.5);
+        let trend = trend(LightSensorMode::HISTORY_SPAN);
+        LightSensorMode::new(input, ldr.calibration(4096).unwrap(), trend)
+    }
+
+    #[test]
+    fn update_estimates_lux() {
+        let mut mode = light_mode(MockAnalogInput::constant(4096, 2048));
+        block_on(mode.update());
+        let lux = mode.lux.unwrap();
+        assert!((lux - 10.0).abs() < 0.2, "{lux} is not close to 10 lx");
+    }
+
+    #[test]
+    fn update_keeps_saturated_readings() {
+        let mut dark = light_mode(MockAnalogInput::constant(4096, 0));
+        let mut bright = light_mode(MockAnalogInput::constant(4096, 4096));
+        block_on(dark.update());
+        block_on(bright.update());
+
+        assert!(dark.lux.unwrap() < LightSensorMode::LUX_MIN);
+        assert!(bright.lux.unwrap() > LightSensorMode::LUX_MAX);
+    }
+
+    #[test]
+    fn update_clears_lux_on_error() {
+        let mut mode = light_mode(MockAnalogInput::failing(4096));
+        mode.lux = Some(10.0);
+        block_on(mode.update());
+        assert_eq!(mode.lux, None);
+    }
+
+    #[test]
+    fn readings_are_recorded_in_percent() {
+        let trend = Trend::new(LightSensorMode::HISTORY_SPAN);
+        let calibration = Photoresistor::new(10_000.0, 10_000.0, 0.5)
+            .calibration(4096)
+            .unwrap();
+        let reading = AnalogReading {
+            raw: 2048,
+            max_value: 4096,
+        };
+        LightSensorMode::record(&trend, &calibration, &Reading::now(Ok(reading)));
+        // 10 lx is a fifth up the logarithmic scale from 1 lx to 100 klx
+        let (_, pct) = trend.latest().unwrap();
+        assert!((pct - 20.0).abs() < 0.5, "{pct} is not close to 20 %");
+    }
+
+    #[test]
+    fn chart_shows_trend() {
+        let mut mode = light_mode(MockAnalogInput::constant(4096, 2048));
+        mode.view = QuantityView::Chart;
+        let empty = draw(&mode);
+
+        mode.trend.push(Instant::now(), 50.0);
+        assert_ne!(draw(&mode), empty);
+    }
+
+    #[test]
+    fn action_resets_statistics() {
+        let mut mode = light_mode(MockAnalogInput::constant(4096, 2048));
+        mode.trend.push(Instant::now(), 20.0);
+        mode.trend.push(Instant::now(), 50.0);
+        mode.view = QuantityView::Statistics;
+        let statistics = draw(&mode);
+
+        perform_action(&mut mode, RESET_STATISTICS);
+        assert_eq!(mode.trend.statistics().count(), 0);
+        assert_ne!(draw(&mode), statistics);
+    }
+
+    #[test]
+    fn scale_is_logarithmic() {
+        assert_eq!(LightSensorMode::scale_pct(0.1), 0.0);
+        assert!((LightSensorMode::scale_pct(100.0) - 40.0).abs() < 0.5);
+        assert_eq!(LightSensorMode::scale_pct(1e6), 100.0);
+    }
+
+    #[test]
+    fn lux_is_formatted_by_magnitude() {
+        assert_eq!(format_lux(2.54), "2.5 lx");
+        assert_eq!(format_lux(420.4), "420 lx");
+        assert_eq!(format_lux(32_000.0), "32k lx");
+    }
+
+    #[test]
+    fn drawing_depends_on_value() {
+        let mut dark = light_mode(MockAnalogInput::constant(4096, 100));
+        let mut bright = light_mode(MockAnalogInput::constant(4096, 4000));
+        block_on(dark.update());
+        block_on(bright.update());
+
+        assert_ne!(draw(&dark), draw(&bright));
+    }
+
+    #[test]
+    fn double_click_cycles_views() {
+        let mut mode = light_mode(MockAnalogInput::constant(4096, 2048));
+        block_on(mode.update());
+        let circle = draw(&mode);
+
+        assert_eq!(
+            handle_event(&mut mode, ButtonEvent::DoubleClick),
+            EventResult::Consumed
+        );
+        assert_eq!(mode.view, QuantityView::Chart);
+        assert_ne!(draw(&mode), circle);
+
+        handle_event(&mut mode, ButtonEvent::DoubleClick);
+        assert_eq!(mode.view, QuantityView::Statistics);
+        assert_ne!(draw(&mode), circle);
+
+        handle_event(&mut mode, ButtonEvent::DoubleClick);
+        assert_eq!(draw(&mode), circle);
+        assert_eq!(
+            handle_event(&mut mode, ButtonEvent::Press),
+            EventResult::Ignored
+        );
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut mode = light_mode(MockAnalogInput::failing(4096));
+        for (name, lux) in [
+            ("none", None),
+            ("dark", Some(0.5)),
+            ("room", Some(300.0)),
+            ("sunlight", Some(50_000.0)),
+        ] {
+            mode.lux = lux;
+            assert_snapshot(&format!("light_{name}"), &mode);
+        }
+    }
+}
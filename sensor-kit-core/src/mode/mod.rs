@@ -0,0 +1,52 @@
+/// Acceleration mode
+pub mod acceleration;
+/// Buzzer mode.
+pub mod buzzer;
+/// Environment mode.
+pub mod environment;
+/// LED mode.
+pub mod led;
+/// Light sensor mode.
+pub mod light;
+/// Potentiometer mode.
+pub mod potentiometer;
+/// Oscilloscope mode.
+pub mod scope;
+/// Sound mode.
+pub mod sound;
+/// Spectrum analyzer mode.
+pub mod spectrum;
+
+pub use acceleration::AccelerationMode;
+pub use environment::{EnvironmentMode, EnvironmentTrends};
+pub use led::LedMode;
+pub use light::LightSensorMode;
+pub use potentiometer::PotentiometerMode;
+pub use scope::{ScopeMode, TriggerEdge, TriggerMode};
+pub use sound::{SoundLevelMeter, SoundMode};
+pub use spectrum::SpectrumMode;
+
+/// Label of the menu action resetting a mode's statistics.
+const RESET_STATISTICS: &str = "Reset statistics";
+
+/// Views of modes showing a single measured quantity, switched between with a double click.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QuantityView {
+    /// The latest value.
+    Live,
+    /// Chart of recent values.
+    Chart,
+    /// Statistics of all values.
+    Statistics,
+}
+
+impl QuantityView {
+    /// The view following this one, wrapping around after the last one.
+    fn next(self) -> Self {
+        match self {
+            Self::Live => Self::Chart,
+            Self::Chart => Self::Statistics,
+            Self::Statistics => Self::Live,
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use async_trait::async_trait;
+use embassy_time::{Duration, Instant};
+use embedded_graphics::text::Text;
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+use embedded_layout::align::Align;
+use embedded_layout::layout::linear::{FixedMargin, LinearLayout};
+use embedded_layout::prelude::*;
+
+use crate::app::{AppMode, AppStyle, EventResult};
+use crate::mode::{QuantityView, RESET_STATISTICS};
+use crate::peripherals::{AnalogInput, AnalogReading, ButtonEvent, PeripheralError, Reading};
+use crate::trend::Trend;
+use crate::ui::{LineChart, StatisticsRow, StatisticsTable};
+use crate::{
+    app::{Draw, Update},
+    ui::HorizontalBar,
+};
+
+/// Struct defining the `Potentiometer` mode. Samples value from a potentiometer and displays it as
+/// both a percentage and a bar. A double click switches to a chart of the value over the last
+/// minute and to its statistics, which an action of the menu resets.
+pub struct PotentiometerMode<'a> {
+    /// The potentiometer used as input.
+    input: Box<dyn AnalogInput + 'a>,
+    /// Signal value in %.
+    value_pct: Option<f32>,
+    /// Trend and statistics of the signal value.
+    trend: &'a Trend,
+    /// View shown.
+    view: QuantityView,
+}
+
+impl<'a> PotentiometerMode<'a> {
+    /// Span of time covered by the chart.
+    pub const HISTORY_SPAN: Duration = Duration::from_secs(60);
+
+    /// Create the mode, showing the value read from `input` and its `trend`.
+    pub fn new(input: impl AnalogInput + 'a, trend: &'a Trend) -> Self {
+        Self {
+            input: Box::new(input),
+            value_pct: None,
+            trend,
+            view: QuantityView::Live,
+        }
+    }
+
+    /// Record a reading of the potentiometer into the mode's `trend`, in %.
+    pub fn record(trend: &Trend, reading: &Reading<Result<AnalogReading, PeripheralError>>) {
+        if let Ok(value) = reading.value {
+            trend.push(reading.timestamp, value.pct());
+        }
+    }
+}
+
+#[async_trait]
+impl Update for PotentiometerMode<'_> {
+    async fn update(&mut self) {
+        self.value_pct = self.input.input_pct().await.ok();
+    }
+}
+
+impl<D> Draw<D> for PotentiometerMode<'_>
+where
+    D: DrawTarget,
+{
+    fn draw_with_style(
+        &self,
+        style: &AppStyle<D::Color>,
+        draw_area: Rectangle,
+        target: &mut D,
+    ) -> Result<(), D::Error> {
+        if self.view == QuantityView::Statistics {
+            let rows = [StatisticsRow::new("%", 0, self.trend.statistics())];
+            return StatisticsTable::new(&rows, style.text_style.clone(), draw_area).draw(target);
+        }
+        if self.view == QuantityView::Chart {
+            let points = self.trend.points(Instant::now());
+            return LineChart::new(
+                &points,
+                self.trend.span(),
+                "%",
+                style.text_style.clone(),
+                style.default_color,
+                draw_area,
+            )
+            .draw(target);
+        }
+
+        let string = match self.value_pct {
+            Some(value) => format!("{:.1}%", value),
+            None => String::from("???"),
+        };
+
+        let text = Text::new(&string, Point::zero(), style.text_style.clone());
+        let text_height = text.bounding_box().size.height;
+
+        let bar_width = (0.8 * draw_area.size.width as f32) as u32;
+        let bar_height = u32::min(20, draw_area.size.height - text_height);
+
+        let bar_value = self.value_pct.unwrap_or(0.0);
+        let bar = HorizontalBar::new(
+            bar_value,
+            style.default_color,
+            Rectangle::new(Point::zero(), Size::new(bar_width, bar_height)),
+        );
+
+        LinearLayout::vertical(Chain::new(text).append(bar))
+            .with_alignment(horizontal::Center)
+            .with_spacing(FixedMargin(5))
+            .arrange()
+            .align_to(&draw_area, horizontal::Center, vertical::Center)
+            .draw(target)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<D> AppMode<D> for PotentiometerMode<'_>
+where
+    D: DrawTarget,
+{
+    fn title(&self) -> String {
+        String::from("Potentiometer")
+    }
+
+    /// A double click switches between the bar, the chart and the statistics.
+    async fn handle_event(&mut self, event: ButtonEvent) -> EventResult {
+        match event {
+            ButtonEvent::DoubleClick => {
+                self.view = self.view.next();
+                EventResult::Consumed
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn actions(&self) -> Vec<String> {
+        vec![String::from(RESET_STATISTICS)]
+    }
+
+    async fn perform_action(&mut self, index: usize) -> Result<(), PeripheralError> {
+        if index == 0 {
+            self.trend.reset_statistics();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::block_on;
+
+    use super::*;
+    use crate::peripherals::mock::MockAnalogInput;
+    use crate::test_utils::{assert_snapshot, draw, handle_event, perform_action, trend};
+
+    /// Potentiometer mode reading `input`.
+    fn potentiometer_mode(input: MockAnalogInput) -> PotentiometerMode<'static> {
+        PotentiometerMode::new(input, trend(PotentiometerMode::HISTORY_SPAN))
+    }
+
+    #[test]
+    fn update_samples_input() {
+        let mut mode = potentiometer_mode(MockAnalogInput::constant(4096, 1024));
+        block_on(mode.update());
+        assert_eq!(mode.value_pct, Some(25.0));
+    }
+
+    #[test]
+    fn update_discards_value_on_error() {
+        let mut mode = potentiometer_mode(MockAnalogInput::failing(4096));
+        block_on(mode.update());
+        assert_eq!(mode.value_pct, None);
+    }
+
+    #[test]
+    fn readings_are_recorded_in_percent() {
+        let trend = Trend::new(PotentiometerMode::HISTORY_SPAN);
+        let reading = AnalogReading {
+            raw: 1024,
+            max_value: 4096,
+        };
+        PotentiometerMode::record(&trend, &Reading::now(Ok(reading)));
+        assert_eq!(trend.latest().map(|(_, value)| value), Some(25.0));
+    }
+
+    #[test]
+    fn double_click_cycles_views() {
+        let mut mode = potentiometer_mode(MockAnalogInput::constant(4096, 1024));
+        block_on(mode.update());
+        let bar = draw(&mode);
+
+        assert_eq!(
+            handle_event(&mut mode, ButtonEvent::DoubleClick),
+            EventResult::Consumed
+        );
+        assert_eq!(mode.view, QuantityView::Chart);
+        let empty_chart = draw(&mode);
+        assert_ne!(empty_chart, bar);
+        mode.trend.push(Instant::now(), 25.0);
+        assert_ne!(draw(&mode), empty_chart);
+
+        handle_event(&mut mode, ButtonEvent::DoubleClick);
+        assert_eq!(mode.view, QuantityView::Statistics);
+        handle_event(&mut mode, ButtonEvent::DoubleClick);
+        assert_eq!(draw(&mode), bar);
+    }
+
+    #[test]
+    fn statistics_are_shown_and_reset() {
+        let mut mode = potentiometer_mode(MockAnalogInput::constant(4096, 1024));
+        block_on(mode.update());
+        let bar = draw(&mode);
+        // Only the recorded readings count, not the ones read while the mode is shown
+        assert_eq!(mode.trend.statistics().count(), 0);
+        mode.trend.push(Instant::now(), 25.0);
+        mode.trend.push(Instant::now(), 75.0);
+        assert_eq!(mode.trend.statistics().mean(), Some(50.0));
+
+        mode.view = QuantityView::Statistics;
+        let statistics = draw(&mode);
+        assert_ne!(statistics, bar);
+
+        perform_action(&mut mode, RESET_STATISTICS);
+        assert_eq!(mode.trend.statistics().count(), 0);
+        assert_ne!(draw(&mode), statistics);
+    }
+
+    #[test]
+    fn drawing_depends_on_value() {
+        let mut low = potentiometer_mode(MockAnalogInput::constant(4096, 0));
+        let mut high = potentiometer_mode(MockAnalogInput::constant(4096, 4096));
+        block_on(low.update());
+        block_on(high.update());
+
+        assert_ne!(draw(&low), draw(&high));
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut mode = potentiometer_mode(MockAnalogInput::failing(4096));
+        for (name, value) in [
+            ("none", None),
+            ("0", Some(0.0)),
+            ("50", Some(50.0)),
+            ("100", Some(100.0)),
+        ] {
+            mode.value_pct = value;
+            assert_snapshot(&format!("potentiometer_{name}"), &mode);
+        }
+    }
+}
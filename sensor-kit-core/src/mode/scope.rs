@@ -0,0 +1,638 @@
+use alloc::format;
+use alloc::vec::Vec;
+use alloc::{boxed::Box, string::String};
+use async_trait::async_trait;
+use embedded_graphics::{
+    prelude::*,
+    primitives::{Polyline, PrimitiveStyle, Rectangle},
+    text::Text,
+};
+use embedded_layout::prelude::*;
+use fugit::HertzU32;
+#[cfg_attr(not(target_os = "none"), allow(unused_imports))]
+use micromath::F32Ext;
+
+use crate::app::{AppMode, Draw, EventResult, Update};
+use crate::peripherals::{AnalogInput, ButtonEvent, PeripheralError, SampledInput};
+
+/// Number of samples captured per update, out of which a trace is selected.
+const CAPTURE: usize = 256;
+/// Selectable number of samples per trace. The rest of a capture is used for finding a trigger.
+const SPANS: [usize; 4] = [32, 64, 128, 192];
+
+/// Edge of the signal a trace starts at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerEdge {
+    /// The signal rising to or above the trigger level.
+    Rising,
+    /// The signal falling below the trigger level.
+    Falling,
+}
+
+/// How traces are captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerMode {
+    /// Capture continuously. Signals without a trigger are shown untriggered.
+    Auto,
+    /// Capture a single triggered trace, then hold it until re-armed.
+    Single,
+}
+
+/// Settings of the scope offered as actions in the menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScopeAction {
+    /// Show the next input.
+    Input,
+    /// Switch between rising and falling edges.
+    Edge,
+    /// Raise the trigger level by [`LEVEL_STEP`], wrapping around to the lowest level.
+    Level,
+    /// Switch between auto and single captures.
+    Mode,
+}
+
+/// Actions in the order they are listed in the menu.
+const ACTIONS: [ScopeAction; 4] = [
+    ScopeAction::Input,
+    ScopeAction::Edge,
+    ScopeAction::Level,
+    ScopeAction::Mode,
+];
+/// Step the trigger level is changed by in the menu, in %.
+const LEVEL_STEP: f32 = 10.0;
+
+/// Index of the first sample in `samples` completing a trigger edge at `level`, leaving at least
+/// `span` samples for the trace.
+fn find_trigger(samples: &[u16], span: usize, level: u16, edge: TriggerEdge) -> Option<usize> {
+    let last = samples.len().checked_sub(span)?;
+    (1..=last).find(|&i| {
+        let (previous, current) = (samples[i - 1], samples[i]);
+        match edge {
+            TriggerEdge::Rising => previous < level && current >= level,
+            TriggerEdge::Falling => previous >= level && current < level,
+        }
+    })
+}
+
+/// Format a duration given in microseconds with a precision fitting its magnitude.
+fn format_duration(micros: u32) -> String {
+    match micros {
+        0..1_000 => format!("{micros}us"),
+        1_000..10_000 => format!("{:.1}ms", micros as f32 / 1000.0),
+        10_000..1_000_000 => format!("{}ms", micros / 1000),
+        _ => format!("{:.1}s", micros as f32 / 1_000_000.0),
+    }
+}
+
+/// An input the scope can display.
+struct ScopeInput<'a> {
+    /// Name shown along with the trace.
+    name: &'static str,
+    input: Box<dyn SampledInput + 'a>,
+}
+
+/// A captured trace.
+struct Trace {
+    /// Raw samples of the trace, oldest first.
+    samples: Vec<u16>,
+    /// Maximum raw value of the input.
+    max_value: u16,
+    /// Rate at which the samples were taken.
+    sample_rate: HertzU32,
+}
+
+/// Struct defining the 'Scope' mode. Captures blocks of samples from one of several inputs and
+/// plots them like an oscilloscope, aligning the trace to a trigger edge. The time base is chosen
+/// with an analog input, e.g. the potentiometer. The input, trigger edge, trigger level and
+/// trigger mode are set in the menu. A double click freezes the trace in auto mode, and arms the
+/// next capture in single mode.
+pub struct ScopeMode<'a> {
+    /// Inputs to choose from.
+    inputs: Vec<ScopeInput<'a>>,
+    /// Index of the displayed input.
+    selected: usize,
+    /// Input choosing the time base.
+    time_base_input: Box<dyn AnalogInput + 'a>,
+    /// Index of the number of samples per trace in [`SPANS`].
+    time_base: usize,
+    /// Edge the trace starts at.
+    edge: TriggerEdge,
+    /// Trigger level in % of the input's maximum value.
+    level_pct: f32,
+    /// How traces are captured.
+    mode: TriggerMode,
+    /// Whether a single capture is still waiting for its trigger.
+    armed: bool,
+    /// Whether the trace is frozen, i.e. not updated anymore.
+    frozen: bool,
+    /// Latest trace, if any.
+    trace: Option<Trace>,
+}
+
+impl<'a> ScopeMode<'a> {
+    /// Create a scope without inputs, triggering on rising edges through half of the input's range.
+    pub fn new(time_base_input: impl AnalogInput + 'a) -> Self {
+        Self {
+            inputs: Vec::new(),
+            selected: 0,
+            time_base_input: Box::new(time_base_input),
+            time_base: 0,
+            edge: TriggerEdge::Rising,
+            level_pct: 50.0,
+            mode: TriggerMode::Auto,
+            armed: false,
+            frozen: false,
+            trace: None,
+        }
+    }
+
+    /// Add an input to choose from, shown as `name`.
+    pub fn with_input(mut self, name: &'static str, input: impl SampledInput + 'a) -> Self {
+        self.inputs.push(ScopeInput {
+            name,
+            input: Box::new(input),
+        });
+        self
+    }
+
+    /// Display the next input, wrapping around after the last one.
+    pub fn next_input(&mut self) {
+        if !self.inputs.is_empty() {
+            self.selected = (self.selected + 1) % self.inputs.len();
+            self.trace = None;
+        }
+    }
+
+    /// Set the edge and level, in % of the input's maximum value, to trigger at.
+    pub fn set_trigger(&mut self, edge: TriggerEdge, level_pct: f32) {
+        self.edge = edge;
+        self.level_pct = level_pct.clamp(0.0, 100.0);
+    }
+
+    /// Set how traces are captured. Single captures are armed right away.
+    pub fn set_trigger_mode(&mut self, mode: TriggerMode) {
+        self.mode = mode;
+        self.armed = mode == TriggerMode::Single;
+    }
+
+    /// Wait for the next trigger of a single capture.
+    pub fn arm(&mut self) {
+        self.armed = true;
+    }
+
+    /// Freeze the trace, or resume capturing if it is frozen.
+    pub fn toggle_freeze(&mut self) {
+        self.frozen = !self.frozen;
+    }
+
+    /// Whether the trace is frozen.
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Raise the trigger level to the next multiple of [`LEVEL_STEP`], wrapping around to the
+    /// lowest one above 0%.
+    fn step_level(&mut self) {
+        let next = ((self.level_pct / LEVEL_STEP).floor() + 1.0) * LEVEL_STEP;
+        let level = if next >= 100.0 { LEVEL_STEP } else { next };
+        self.set_trigger(self.edge, level);
+    }
+
+    /// Menu label of `action`, showing the current setting.
+    fn action_label(&self, action: ScopeAction) -> String {
+        match action {
+            ScopeAction::Input => {
+                let name = self
+                    .inputs
+                    .get(self.selected)
+                    .map_or("-", |input| input.name);
+                format!("Input: {name}")
+            }
+            ScopeAction::Edge => match self.edge {
+                TriggerEdge::Rising => String::from("Edge: rising"),
+                TriggerEdge::Falling => String::from("Edge: falling"),
+            },
+            ScopeAction::Level => format!("Level: {:.0}%", self.level_pct),
+            ScopeAction::Mode => match self.mode {
+                TriggerMode::Auto => String::from("Mode: auto"),
+                TriggerMode::Single => String::from("Mode: single"),
+            },
+        }
+    }
+
+    /// Whether new traces are captured.
+    fn capturing(&self) -> bool {
+        !self.frozen && (self.mode == TriggerMode::Auto || self.armed)
+    }
+
+    /// Status shown along with the trace.
+    fn status(&self) -> &'static str {
+        match (self.frozen, self.mode, self.armed) {
+            (true, _, _) => "HOLD",
+            (false, TriggerMode::Auto, _) => "AUTO",
+            (false, TriggerMode::Single, true) => "ARM",
+            (false, TriggerMode::Single, false) => "SGL",
+        }
+    }
+}
+
+#[async_trait]
+impl Update for ScopeMode<'_> {
+    async fn update(&mut self) {
+        if let Ok(pct) = self.time_base_input.input_pct().await {
+            let index = (pct.clamp(0.0, 100.0) / 100.0 * SPANS.len() as f32) as usize;
+            self.time_base = index.min(SPANS.len() - 1);
+        }
+
+        if !self.capturing() {
+            return;
+        }
+        let Some(ScopeInput { input, .. }) = self.inputs.get_mut(self.selected) else {
+            return;
+        };
+
+        let mut samples = [0; CAPTURE];
+        let max_value = match input.read_block(&mut samples).await {
+            Ok(()) => input.max_value().await,
+            Err(error) => Err(error),
+        };
+        let Ok(max_value) = max_value else {
+            // Don't keep showing a stale trace as if it was live
+            if self.mode == TriggerMode::Auto {
+                self.trace = None;
+            }
+            return;
+        };
+
+        let span = SPANS[self.time_base];
+        let level = (self.level_pct / 100.0 * max_value as f32) as u16;
+        let start = match (find_trigger(&samples, span, level, self.edge), self.mode) {
+            (Some(start), _) => start,
+            // Show the latest samples untriggered
+            (None, TriggerMode::Auto) => CAPTURE - span,
+            (None, TriggerMode::Single) => return,
+        };
+
+        self.armed = false;
+        self.trace = Some(Trace {
+            samples: samples[start..start + span].to_vec(),
+            max_value,
+            sample_rate: input.sample_rate(),
+        });
+    }
+}
+
+impl<D> Draw<D> for ScopeMode<'_>
+where
+    D: DrawTarget,
+{
+    fn draw_with_style(
+        &self,
+        style: &crate::app::AppStyle<<D as DrawTarget>::Color>,
+        draw_area: Rectangle,
+        target: &mut D,
+    ) -> Result<(), <D as DrawTarget>::Error> {
+        let name = self
+            .inputs
+            .get(self.selected)
+            .map_or("-", |input| input.name);
+        let edge = match self.edge {
+            TriggerEdge::Rising => "/",
+            TriggerEdge::Falling => "\\",
+        };
+        let span = match &self.trace {
+            Some(trace) => format_duration(
+                (trace.samples.len() as u64 * 1_000_000 / trace.sample_rate.to_Hz().max(1) as u64)
+                    as u32,
+            ),
+            None => String::from("???"),
+        };
+        let string = format!("{name} {edge} {span} {}", self.status());
+
+        let text = Text::new(&string, Point::zero(), style.text_style.clone()).align_to(
+            &draw_area,
+            horizontal::Left,
+            vertical::Top,
+        );
+        text.draw(target)?;
+
+        let Some(trace) = &self.trace else {
+            return Ok(());
+        };
+
+        // The trace takes up the area below the text
+        let text_height = text.bounding_box().size.height + 2;
+        let plot_area = Rectangle::new(
+            draw_area.top_left + Point::new(0, text_height as i32),
+            Size::new(
+                draw_area.size.width,
+                draw_area.size.height.saturating_sub(text_height),
+            ),
+        );
+        let (Some(bottom_right), true) = (plot_area.bottom_right(), trace.samples.len() > 1) else {
+            return Ok(());
+        };
+
+        let width = plot_area.size.width as i32 - 1;
+        let height = plot_area.size.height as i32 - 1;
+        let last = trace.samples.len() as i32 - 1;
+        let points: Vec<_> = trace
+            .samples
+            .iter()
+            .enumerate()
+            .map(|(i, &sample)| {
+                let x = plot_area.top_left.x + i as i32 * width / last;
+                let y = bottom_right.y
+                    - (sample.min(trace.max_value) as i32 * height / trace.max_value.max(1) as i32);
+                Point::new(x, y)
+            })
+            .collect();
+
+        Polyline::new(&points)
+            .into_styled(PrimitiveStyle::with_stroke(style.default_color, 1))
+            .draw(target)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<D> AppMode<D> for ScopeMode<'_>
+where
+    D: DrawTarget,
+{
+    fn title(&self) -> String {
+        String::from("Scope")
+    }
+
+    /// A double click freezes the trace or resumes capturing in auto mode, and arms the next
+    /// capture in single mode.
+    async fn handle_event(&mut self, event: ButtonEvent) -> EventResult {
+        match (event, self.mode) {
+            (ButtonEvent::DoubleClick, TriggerMode::Auto) => self.toggle_freeze(),
+            (ButtonEvent::DoubleClick, TriggerMode::Single) => self.arm(),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed
+    }
+
+    fn actions(&self) -> Vec<String> {
+        ACTIONS
+            .iter()
+            .map(|&action| self.action_label(action))
+            .collect()
+    }
+
+    async fn perform_action(&mut self, index: usize) -> Result<(), PeripheralError> {
+        match ACTIONS.get(index) {
+            Some(ScopeAction::Input) => self.next_input(),
+            Some(ScopeAction::Edge) => {
+                let edge = match self.edge {
+                    TriggerEdge::Rising => TriggerEdge::Falling,
+                    TriggerEdge::Falling => TriggerEdge::Rising,
+                };
+                self.set_trigger(edge, self.level_pct);
+            }
+            Some(ScopeAction::Level) => self.step_level(),
+            Some(ScopeAction::Mode) => {
+                let mode = match self.mode {
+                    TriggerMode::Auto => TriggerMode::Single,
+                    TriggerMode::Single => TriggerMode::Auto,
+                };
+                // A frozen trace would keep a single capture from ever arming
+                self.frozen = false;
+                self.set_trigger_mode(mode);
+            }
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use embassy_futures::block_on;
+
+    use super::*;
+    use crate::manager::ModeManager;
+    use crate::peripherals::mock::MockAnalogInput;
+    use crate::test_utils::{assert_snapshot, draw, handle_event, perform_action, Frame};
+
+    /// A square wave between 0 and 4000 with a period of 20 samples, starting with the last
+    /// `offset` samples of its low phase.
+    fn square_wave(offset: usize) -> MockAnalogInput {
+        let samples: Vec<_> = (0..CAPTURE)
+            .map(|i| if (i + 10 - offset) % 20 < 10 { 0 } else { 4000 })
+            .collect();
+        MockAnalogInput::from_values(4096, samples)
+    }
+
+    /// A scope showing `input` with the shortest time base.
+    fn scope(input: MockAnalogInput) -> ScopeMode<'static> {
+        ScopeMode::new(MockAnalogInput::constant(4096, 0)).with_input("A0", input)
+    }
+
+    #[test]
+    fn trigger_finds_edges() {
+        let samples = [0, 10, 60, 90, 40, 0];
+        assert_eq!(find_trigger(&samples, 2, 50, TriggerEdge::Rising), Some(2));
+        assert_eq!(find_trigger(&samples, 2, 50, TriggerEdge::Falling), Some(4));
+        // No room for the trace after the falling edge
+        assert_eq!(find_trigger(&samples, 3, 50, TriggerEdge::Falling), None);
+        assert_eq!(find_trigger(&samples, 2, 100, TriggerEdge::Rising), None);
+    }
+
+    #[test]
+    fn durations_are_formatted_by_magnitude() {
+        assert_eq!(format_duration(250), "250us");
+        assert_eq!(format_duration(4_000), "4.0ms");
+        assert_eq!(format_duration(32_000), "32ms");
+        assert_eq!(format_duration(1_920_000), "1.9s");
+    }
+
+    #[test]
+    fn auto_trace_starts_at_trigger() {
+        let mut mode = scope(square_wave(3));
+        block_on(mode.update());
+
+        let trace = mode.trace.as_ref().unwrap();
+        assert_eq!(trace.samples.len(), SPANS[0]);
+        assert_eq!(trace.samples[0], 4000);
+        assert_eq!(trace.sample_rate, HertzU32::kHz(1));
+        assert_eq!(mode.status(), "AUTO");
+    }
+
+    #[test]
+    fn falling_edge_starts_trace_low() {
+        let mut mode = scope(square_wave(3));
+        mode.set_trigger(TriggerEdge::Falling, 50.0);
+        block_on(mode.update());
+        assert_eq!(mode.trace.unwrap().samples[..2], [0, 0]);
+    }
+
+    #[test]
+    fn auto_shows_untriggered_signal() {
+        let mut mode = scope(MockAnalogInput::constant(4096, 1000));
+        block_on(mode.update());
+        assert_eq!(mode.trace.unwrap().samples, [1000; SPANS[0]]);
+    }
+
+    #[test]
+    fn single_capture_holds_until_armed() {
+        let mut mode = scope(MockAnalogInput::constant(4096, 1000));
+        mode.set_trigger_mode(TriggerMode::Single);
+        block_on(mode.update());
+        assert!(mode.trace.is_none());
+        assert_eq!(mode.status(), "ARM");
+
+        mode.inputs[0].input = Box::new(square_wave(0));
+        block_on(mode.update());
+        assert!(mode.trace.is_some());
+        assert_eq!(mode.status(), "SGL");
+
+        mode.inputs[0].input = Box::new(MockAnalogInput::failing(4096));
+        block_on(mode.update());
+        assert!(mode.trace.is_some());
+
+        mode.arm();
+        mode.inputs[0].input = Box::new(square_wave(0));
+        mode.set_trigger(TriggerEdge::Falling, 50.0);
+        block_on(mode.update());
+        assert_eq!(mode.trace.unwrap().samples[0], 0);
+    }
+
+    #[test]
+    fn frozen_trace_is_kept() {
+        let mut mode = scope(square_wave(3));
+        block_on(mode.update());
+        mode.toggle_freeze();
+        assert_eq!(mode.status(), "HOLD");
+
+        mode.inputs[0].input = Box::new(MockAnalogInput::failing(4096));
+        block_on(mode.update());
+        assert!(mode.trace.is_some());
+
+        mode.toggle_freeze();
+        block_on(mode.update());
+        assert!(mode.trace.is_none());
+    }
+
+    #[test]
+    fn time_base_follows_input() {
+        let mut mode =
+            ScopeMode::new(MockAnalogInput::constant(4096, 4096)).with_input("A0", square_wave(3));
+        block_on(mode.update());
+        assert_eq!(mode.trace.unwrap().samples.len(), SPANS[SPANS.len() - 1]);
+    }
+
+    #[test]
+    fn inputs_are_selected_in_turn() {
+        let mut mode = scope(square_wave(3)).with_input("A2", MockAnalogInput::failing(4096));
+        block_on(mode.update());
+        mode.next_input();
+        assert_eq!(mode.selected, 1);
+        assert!(mode.trace.is_none());
+
+        mode.next_input();
+        assert_eq!(mode.selected, 0);
+    }
+
+    #[test]
+    fn double_click_freezes_auto_capture() {
+        let mut mode = scope(square_wave(3));
+        assert_eq!(
+            handle_event(&mut mode, ButtonEvent::DoubleClick),
+            EventResult::Consumed
+        );
+        assert!(mode.is_frozen());
+        handle_event(&mut mode, ButtonEvent::DoubleClick);
+        assert!(!mode.is_frozen());
+        assert_eq!(
+            handle_event(&mut mode, ButtonEvent::Press),
+            EventResult::Ignored
+        );
+    }
+
+    #[test]
+    fn double_click_arms_single_capture() {
+        let mut mode = scope(square_wave(0));
+        perform_action(&mut mode, "Mode: auto");
+        assert_eq!(mode.mode, TriggerMode::Single);
+        block_on(mode.update());
+        assert_eq!(mode.status(), "SGL");
+
+        assert_eq!(
+            handle_event(&mut mode, ButtonEvent::DoubleClick),
+            EventResult::Consumed
+        );
+        assert_eq!(mode.status(), "ARM");
+        assert!(!mode.is_frozen());
+    }
+
+    #[test]
+    fn menu_sets_input_and_trigger() {
+        let mut mode = scope(square_wave(3)).with_input("A2", MockAnalogInput::failing(4096));
+        let actions = AppMode::<Frame>::actions(&mode);
+        assert_eq!(
+            actions,
+            ["Input: A0", "Edge: rising", "Level: 50%", "Mode: auto"]
+        );
+
+        perform_action(&mut mode, "Input: A0");
+        assert_eq!(mode.selected, 1);
+        perform_action(&mut mode, "Edge: rising");
+        assert_eq!(mode.edge, TriggerEdge::Falling);
+        perform_action(&mut mode, "Level: 50%");
+        assert_eq!(mode.level_pct, 60.0);
+        for level in ["60", "70", "80", "90"] {
+            perform_action(&mut mode, &format!("Level: {level}%"));
+        }
+        // The level wraps around below 100%
+        assert_eq!(mode.level_pct, 10.0);
+
+        // Frozen traces are resumed when switching to single captures
+        mode.toggle_freeze();
+        perform_action(&mut mode, "Mode: auto");
+        assert_eq!(mode.status(), "ARM");
+        perform_action(&mut mode, "Mode: single");
+        assert_eq!(mode.status(), "AUTO");
+    }
+
+    #[test]
+    fn menu_of_manager_sets_trigger_mode() {
+        // With four actions and a single mode, 60% selects the fourth entry
+        let scope = scope(square_wave(3));
+        let modes: Vec<Box<dyn AppMode<Frame>>> = vec![Box::new(scope)];
+        let mut manager = ModeManager::new(modes, MockAnalogInput::constant(100, 60));
+
+        block_on(manager.handle_event(ButtonEvent::LongPress)).unwrap();
+        block_on(manager.update());
+        block_on(manager.handle_event(ButtonEvent::Press)).unwrap();
+        assert!(!manager.is_menu_open());
+        assert_eq!(manager.menu_entries()[3], "Mode: single");
+    }
+
+    #[test]
+    fn drawing_depends_on_value() {
+        let mut square = scope(square_wave(3));
+        let mut flat = scope(MockAnalogInput::constant(4096, 1000));
+        block_on(square.update());
+        block_on(flat.update());
+
+        assert_ne!(draw(&square), draw(&flat));
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut empty = scope(MockAnalogInput::failing(4096));
+        assert_snapshot("scope_none", &empty);
+
+        empty.inputs[0].input = Box::new(square_wave(3));
+        block_on(empty.update());
+        assert_snapshot("scope_square", &empty);
+
+        empty.toggle_freeze();
+        assert_snapshot("scope_frozen", &empty);
+    }
+}
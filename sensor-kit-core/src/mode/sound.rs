@@ -0,0 +1,480 @@
+use alloc::format;
+use alloc::vec::Vec;
+use alloc::{boxed::Box, string::String, vec};
+use async_trait::async_trait;
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{prelude::*, primitives::Rectangle, text::Text};
+use embedded_layout::layout::linear::{FixedMargin, LinearLayout};
+use embedded_layout::prelude::*;
+use fugit::HertzU32;
+#[cfg_attr(not(target_os = "none"), allow(unused_imports))]
+use micromath::F32Ext;
+use thiserror::Error;
+
+use crate::app::{AppMode, Draw, EventResult, Update};
+use crate::mode::{QuantityView, RESET_STATISTICS};
+use crate::peripherals::{
+    ButtonEvent, Calibration, PeripheralError, Reading, SampledInput, SoundLevelReading,
+};
+use crate::trend::Trend;
+use crate::ui::{HorizontalBar, LineChart, StatisticsRow, StatisticsTable};
+
+/// Sound pressure of the threshold of hearing in Pa, the reference of sound pressure levels.
+const REFERENCE_PRESSURE: f32 = 20e-6;
+
+/// Sound pressure level in dB SPL for an RMS sound pressure in Pa.
+///
+/// The sensor applies no frequency weighting. Its microphone is most sensitive in the mid range
+/// though, so levels roughly resemble A-weighted ones.
+fn level_db(pressure: f32) -> f32 {
+    if pressure <= 0.0 {
+        return f32::NEG_INFINITY;
+    }
+    20.0 * (pressure / REFERENCE_PRESSURE).log10()
+}
+
+/// RMS amplitude of `values` around their mean, which removes the sensor's DC offset.
+fn rms(values: impl Iterator<Item = f32> + Clone) -> f32 {
+    let count = values.clone().count() as f32;
+    let mean = values.clone().sum::<f32>() / count;
+    let square_sum: f32 = values.map(|value| (value - mean).powi(2)).sum();
+    (square_sum / count).sqrt()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+/// Error type for sound level measurements.
+pub enum SoundLevelError {
+    #[error("Sample rate too low to measure the sound level")]
+    /// The input is sampled below [`SoundLevelMeter::MIN_SAMPLE_RATE`].
+    SampleRateTooLow,
+    #[error(transparent)]
+    /// The input failed.
+    Peripheral(#[from] PeripheralError),
+}
+
+/// Defines interface for sound level sources that can be used by the [`SoundMode`].
+#[async_trait]
+pub trait SoundLevelInput: Send {
+    /// Measure the current sound level in dB SPL.
+    async fn level_db(&mut self) -> Result<f32, SoundLevelError>;
+}
+
+/// Sound level meter measuring over a window of samples, converted to sound pressure by a
+/// [`Calibration`] of the sensor in Pa.
+///
+/// Inputs sampled below [`SoundLevelMeter::MIN_SAMPLE_RATE`] aren't measured.
+pub struct SoundLevelMeter<'a> {
+    /// Input used.
+    input: Box<dyn SampledInput + 'a>,
+    /// Calibration of the sensor's raw values in Pa.
+    calibration: Calibration,
+}
+
+impl<'a> SoundLevelMeter<'a> {
+    /// Number of samples the level is measured over. At a few kHz, this covers a few tens of
+    /// milliseconds, enough for all but the lowest frequencies.
+    const WINDOW: usize = 256;
+    /// Lowest sample rate the sound level is measured at. A window of samples at a lower rate takes
+    /// so long that the level would lag far behind the sound.
+    pub const MIN_SAMPLE_RATE: HertzU32 = HertzU32::kHz(1);
+
+    pub fn new(input: impl SampledInput + 'a, calibration: Calibration) -> Self {
+        Self {
+            input: Box::new(input),
+            calibration,
+        }
+    }
+}
+
+#[async_trait]
+impl SoundLevelInput for SoundLevelMeter<'_> {
+    async fn level_db(&mut self) -> Result<f32, SoundLevelError> {
+        if self.input.sample_rate() < Self::MIN_SAMPLE_RATE {
+            return Err(SoundLevelError::SampleRateTooLow);
+        }
+
+        let mut samples = [0; Self::WINDOW];
+        self.input.read_block(&mut samples).await?;
+        let pressures = samples.iter().map(|&sample| self.calibration.apply(sample));
+        Ok(level_db(rms(pressures)))
+    }
+}
+
+/// Struct defining the 'Sound Sensor' mode. Displays the sound level as a bar with a peak-hold
+/// marker and as a number. A double click switches to a chart of the sound level over the last
+/// minute and to its statistics, which an action of the menu resets. Both track the sound level
+/// in % of the bar.
+///
+/// If the sound level can't be measured as the input is sampled too slowly, the mode tells so.
+pub struct SoundMode<'a> {
+    /// Input used.
+    input: Box<dyn SoundLevelInput + 'a>,
+    /// Whether the input is sampled too slowly to measure the sound level.
+    rate_too_low: bool,
+    /// Current sound level in dB.
+    level_db: Option<f32>,
+    /// Highest sound level within the hold time, in dB.
+    peak_db: Option<f32>,
+    /// Time until which the peak is held.
+    peak_until: Instant,
+    /// Trend and statistics of the sound level in %.
+    trend: &'a Trend,
+    /// View shown.
+    view: QuantityView,
+}
+
+impl<'a> SoundMode<'a> {
+    /// Time a peak is held before it follows the level again.
+    const PEAK_HOLD: Duration = Duration::from_millis(1500);
+    /// Level shown as an empty bar, and the lowest level shown.
+    const DB_MIN: f32 = 30.0;
+    /// Level shown as a full bar.
+    const DB_MAX: f32 = 100.0;
+    /// Span of time covered by the chart.
+    pub const HISTORY_SPAN: Duration = Duration::from_secs(60);
+
+    /// Create the mode, showing the sound level measured by `input` and its `trend`.
+    pub fn new(input: impl SoundLevelInput + 'a, trend: &'a Trend) -> Self {
+        Self {
+            input: Box::new(input),
+            rate_too_low: false,
+            level_db: None,
+            peak_db: None,
+            peak_until: Instant::now(),
+            trend,
+            view: QuantityView::Live,
+        }
+    }
+
+    /// Record a sound level reading into the mode's `trend`, in % of the bar.
+    pub fn record(trend: &Trend, reading: &Reading<SoundLevelReading>) {
+        if let Ok(level) = reading.value {
+            trend.push(reading.timestamp, Self::scale_pct(level));
+        }
+    }
+
+    /// Position of `db` on the bar, in %.
+    fn scale_pct(db: f32) -> f32 {
+        ((db - Self::DB_MIN) / (Self::DB_MAX - Self::DB_MIN) * 100.0).clamp(0.0, 100.0)
+    }
+}
+
+#[async_trait]
+impl Update for SoundMode<'_> {
+    async fn update(&mut self) {
+        let level = match self.input.level_db().await {
+            Ok(level) => level.max(Self::DB_MIN),
+            Err(error) => {
+                self.rate_too_low = error == SoundLevelError::SampleRateTooLow;
+                self.level_db = None;
+                return;
+            }
+        };
+
+        let now = Instant::now();
+        match self.peak_db {
+            Some(peak) if peak >= level && now < self.peak_until => {}
+            _ => {
+                self.peak_db = Some(level);
+                self.peak_until = now + Self::PEAK_HOLD;
+            }
+        }
+        self.level_db = Some(level);
+    }
+}
+
+impl<D> Draw<D> for SoundMode<'_>
+where
+    D: DrawTarget,
+{
+    fn draw_with_style(
+        &self,
+        style: &crate::app::AppStyle<<D as DrawTarget>::Color>,
+        draw_area: Rectangle,
+        target: &mut D,
+    ) -> Result<(), <D as DrawTarget>::Error> {
+        if self.view == QuantityView::Statistics {
+            let rows = [StatisticsRow::new("%", 0, self.trend.statistics())];
+            return StatisticsTable::new(&rows, style.text_style.clone(), draw_area).draw(target);
+        }
+        if self.view == QuantityView::Chart {
+            let points = self.trend.points(Instant::now());
+            return LineChart::new(
+                &points,
+                self.trend.span(),
+                "%",
+                style.text_style.clone(),
+                style.default_color,
+                draw_area,
+            )
+            .draw(target);
+        }
+
+        let string = match (self.level_db, self.peak_db) {
+            _ if self.rate_too_low => String::from("Sample rate too low"),
+            (Some(level), Some(peak)) => format!("{level:.0} dB  peak {peak:.0}"),
+            _ => String::from("???"),
+        };
+        let text = Text::new(&string, Point::zero(), style.text_style.clone());
+        let text_height = text.bounding_box().size.height;
+
+        let bar_width = (draw_area.size.width as f32 * 0.9) as u32;
+        let bar_height = u32::min(20, draw_area.size.height.saturating_sub(text_height + 5));
+
+        let mut bar = HorizontalBar::new(
+            self.level_db.map(Self::scale_pct).unwrap_or(0.0),
+            style.default_color,
+            Rectangle::new(Point::zero(), Size::new(bar_width, bar_height)),
+        );
+        if let Some(peak) = self.peak_db {
+            bar = bar.with_marker(Self::scale_pct(peak));
+        }
+
+        LinearLayout::vertical(Chain::new(text).append(bar))
+            .with_alignment(horizontal::Center)
+            .with_spacing(FixedMargin(5))
+            .arrange()
+            .align_to(&draw_area, horizontal::Center, vertical::Center)
+            .draw(target)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<D> AppMode<D> for SoundMode<'_>
+where
+    D: DrawTarget,
+{
+    fn title(&self) -> alloc::string::String {
+        String::from("Sound Sensor")
+    }
+
+    /// Short sounds would be missed between less frequent updates.
+    fn update_interval(&self) -> Duration {
+        Duration::from_millis(50)
+    }
+
+    /// The peak marker keeps short sounds visible, so the display needn't follow every update.
+    fn redraw_interval(&self) -> Duration {
+        Duration::from_millis(100)
+    }
+
+    /// A double click switches between the bar, the chart and the statistics.
+    async fn handle_event(&mut self, event: ButtonEvent) -> EventResult {
+        match event {
+            ButtonEvent::DoubleClick => {
+                self.view = self.view.next();
+                EventResult::Consumed
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn actions(&self) -> Vec<String> {
+        vec![String::from(RESET_STATISTICS)]
+    }
+
+    async fn perform_action(&mut self, index: usize) -> Result<(), PeripheralError> {
+        if index == 0 {
+            self.trend.reset_statistics();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use embassy_futures::block_on;
+
+    use super::*;
+    use crate::peripherals::mock::MockAnalogInput;
+    use crate::test_utils::{assert_snapshot, draw, handle_event, perform_action, trend};
+
+    /// Calibration with an amplitude of 100 counts RMS corresponding to 60 dB, i.e. 0.02 Pa.
+    fn calibration() -> Calibration {
+        Calibration::two_point((0, 0.0), (4096, 4096.0 * 0.0002)).unwrap()
+    }
+
+    /// A square wave around 2048 with an RMS amplitude of `amplitude`, filling a whole window.
+    fn square_wave(amplitude: u16) -> MockAnalogInput {
+        let samples: Vec<_> = (0..SoundLevelMeter::WINDOW)
+            .map(|i| match i % 2 {
+                0 => 2048 - amplitude,
+                _ => 2048 + amplitude,
+            })
+            .collect();
+        MockAnalogInput::from_values(4096, samples)
+    }
+
+    /// Sound level meter measuring `input`.
+    fn meter(input: MockAnalogInput) -> SoundLevelMeter<'static> {
+        SoundLevelMeter::new(input, calibration())
+    }
+
+    /// Sound mode showing the sound level measured on `input`.
+    fn sound_mode(input: MockAnalogInput) -> SoundMode<'static> {
+        SoundMode::new(meter(input), trend(SoundMode::HISTORY_SPAN))
+    }
+
+    fn assert_close(actual: Option<f32>, expected: f32) {
+        let actual = actual.unwrap();
+        assert!(
+            (actual - expected).abs() < 0.5,
+            "{actual} is not close to {expected}"
+        );
+    }
+
+    #[test]
+    fn rms_removes_offset() {
+        // `sqrt` is only approximated
+        let wave = [1000.0, 3000.0, 1000.0, 3000.0];
+        assert!((rms(wave.into_iter()) - 1000.0).abs() < 10.0);
+        assert_eq!(rms([2048.0; 16].into_iter()), 0.0);
+    }
+
+    #[test]
+    fn level_is_logarithmic() {
+        assert_close(Some(level_db(0.02)), 60.0);
+        assert_close(Some(level_db(0.2)), 80.0);
+        assert_eq!(level_db(0.0), f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn update_measures_level() {
+        let mut mode = sound_mode(square_wave(1000));
+        block_on(mode.update());
+        assert_close(mode.level_db, 80.0);
+        assert_close(mode.peak_db, 80.0);
+    }
+
+    #[test]
+    fn silence_is_shown_at_lowest_level() {
+        let mut mode = sound_mode(MockAnalogInput::constant(4096, 2048));
+        block_on(mode.update());
+        assert_eq!(mode.level_db, Some(SoundMode::DB_MIN));
+    }
+
+    #[test]
+    fn peak_is_held_until_it_expires() {
+        let mut mode = sound_mode(square_wave(1000));
+        block_on(mode.update());
+        mode.input = Box::new(meter(square_wave(100)));
+        block_on(mode.update());
+        assert_close(mode.level_db, 60.0);
+        assert_close(mode.peak_db, 80.0);
+
+        mode.peak_until = Instant::now();
+        // The mock repeats its last sample once the wave is used up
+        mode.input = Box::new(meter(square_wave(100)));
+        block_on(mode.update());
+        assert_close(mode.peak_db, 60.0);
+    }
+
+    #[test]
+    fn low_sample_rate_is_refused() {
+        let mut slow = meter(square_wave(1000).with_sample_rate(HertzU32::Hz(100)));
+        assert_eq!(
+            block_on(slow.level_db()),
+            Err(SoundLevelError::SampleRateTooLow)
+        );
+
+        let mut mode = SoundMode::new(slow, trend(SoundMode::HISTORY_SPAN));
+        block_on(mode.update());
+        assert_eq!(mode.level_db, None);
+
+        let mut broken = sound_mode(MockAnalogInput::failing(4096));
+        block_on(broken.update());
+        assert_ne!(draw(&mode), draw(&broken));
+    }
+
+    #[test]
+    fn error_clears_level() {
+        let mut mode = sound_mode(MockAnalogInput::failing(4096));
+        mode.level_db = Some(50.0);
+        block_on(mode.update());
+        assert_eq!(mode.level_db, None);
+    }
+
+    #[test]
+    fn action_resets_statistics() {
+        let mut mode = sound_mode(square_wave(1000));
+        mode.trend.push(Instant::now(), 70.0);
+        mode.view = QuantityView::Statistics;
+        let statistics = draw(&mode);
+
+        perform_action(&mut mode, RESET_STATISTICS);
+        assert_eq!(mode.trend.statistics().count(), 0);
+        assert_ne!(draw(&mode), statistics);
+    }
+
+    #[test]
+    fn levels_are_recorded() {
+        let recorded = |level| {
+            let trend = Trend::new(SoundMode::HISTORY_SPAN);
+            SoundMode::record(&trend, &Reading::now(Ok(level)));
+            trend.latest().map(|(_, pct)| pct)
+        };
+        assert_eq!(recorded(f32::NEG_INFINITY), Some(0.0));
+        assert_eq!(recorded(65.0), Some(50.0));
+    }
+
+    #[test]
+    fn chart_shows_trend() {
+        let mut mode = sound_mode(square_wave(1000));
+        mode.view = QuantityView::Chart;
+        let empty = draw(&mode);
+
+        mode.trend.push(Instant::now(), 70.0);
+        assert_ne!(draw(&mode), empty);
+    }
+
+    #[test]
+    fn double_click_cycles_views() {
+        let mut mode = sound_mode(square_wave(1000));
+        block_on(mode.update());
+        let bar = draw(&mode);
+
+        assert_eq!(
+            handle_event(&mut mode, ButtonEvent::DoubleClick),
+            EventResult::Consumed
+        );
+        assert_eq!(mode.view, QuantityView::Chart);
+        assert_ne!(draw(&mode), bar);
+
+        handle_event(&mut mode, ButtonEvent::DoubleClick);
+        assert_eq!(mode.view, QuantityView::Statistics);
+        assert_ne!(draw(&mode), bar);
+
+        handle_event(&mut mode, ButtonEvent::DoubleClick);
+        assert_eq!(draw(&mode), bar);
+    }
+
+    #[test]
+    fn drawing_depends_on_value() {
+        let mut quiet = sound_mode(square_wave(10));
+        let mut loud = sound_mode(square_wave(1000));
+        let broken = sound_mode(MockAnalogInput::failing(4096));
+        block_on(quiet.update());
+        block_on(loud.update());
+
+        assert_ne!(draw(&quiet), draw(&loud));
+        assert_ne!(draw(&quiet), draw(&broken));
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut mode = sound_mode(MockAnalogInput::failing(4096));
+        for (name, level, peak) in [
+            ("none", None, None),
+            ("quiet", Some(35.0), Some(35.0)),
+            ("peak", Some(55.0), Some(85.0)),
+            ("loud", Some(100.0), Some(100.0)),
+        ] {
+            mode.level_db = level;
+            mode.peak_db = peak;
+            assert_snapshot(&format!("sound_{name}"), &mode);
+        }
+    }
+}
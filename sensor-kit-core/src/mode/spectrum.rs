@@ -0,0 +1,310 @@
+use alloc::{boxed::Box, string::String};
+use async_trait::async_trait;
+use core::f32::consts::PI;
+use embassy_time::Duration;
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+#[cfg_attr(not(target_os = "none"), allow(unused_imports))]
+use micromath::F32Ext;
+
+use crate::app::{AppMode, Draw, Update};
+use crate::peripherals::SampledInput;
+use crate::ui::VerticalBar;
+
+/// Number of samples per FFT. Must be a power of two.
+const FFT_SIZE: usize = 256;
+/// Number of frequency bins of the FFT, from 0 up to half the sample rate.
+const BINS: usize = FFT_SIZE / 2;
+/// Number of bands shown.
+const BANDS: usize = 16;
+
+/// In-place radix-2 FFT of a complex signal given by its real and imaginary parts.
+fn fft(re: &mut [f32; FFT_SIZE], im: &mut [f32; FFT_SIZE]) {
+    // Reorder the samples by bit-reversed index
+    let mut j = 0;
+    for i in 1..FFT_SIZE {
+        let mut bit = FFT_SIZE >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    // Combine pairs of transforms into transforms of twice the length
+    let mut len = 2;
+    while len <= FFT_SIZE {
+        let half = len / 2;
+        for k in 0..half {
+            let angle = -2.0 * PI * k as f32 / len as f32;
+            let (sin, cos) = (angle.sin(), angle.cos());
+            for start in (0..FFT_SIZE).step_by(len) {
+                let (a, b) = (start + k, start + k + half);
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len *= 2;
+    }
+}
+
+/// Magnitude spectrum of `samples`, after removing their mean and applying a Hann window.
+fn spectrum(samples: &[u16; FFT_SIZE]) -> [f32; BINS] {
+    let mean = samples.iter().map(|&sample| sample as f32).sum::<f32>() / FFT_SIZE as f32;
+    let mut re = [0.0; FFT_SIZE];
+    let mut im = [0.0; FFT_SIZE];
+    for (i, (re, &sample)) in re.iter_mut().zip(samples.iter()).enumerate() {
+        let window = 0.5 - 0.5 * (2.0 * PI * i as f32 / (FFT_SIZE - 1) as f32).cos();
+        *re = (sample as f32 - mean) * window;
+    }
+
+    fft(&mut re, &mut im);
+
+    let mut magnitudes = [0.0; BINS];
+    for (bin, magnitude) in magnitudes.iter_mut().enumerate() {
+        *magnitude = (re[bin] * re[bin] + im[bin] * im[bin]).sqrt();
+    }
+    magnitudes
+}
+
+/// First bin of each band, followed by the end of the last band. Bands are spaced logarithmically
+/// like musical octaves, but cover at least one bin each. The DC bin is left out.
+fn band_edges() -> [usize; BANDS + 1] {
+    let mut edges = [0; BANDS + 1];
+    edges[0] = 1;
+    for band in 1..BANDS {
+        let remaining = BANDS - band;
+        let log_edge = (BINS as f32).powf(band as f32 / BANDS as f32) as usize;
+        // Leave at least one bin for each remaining band
+        edges[band] = log_edge.max(edges[band - 1] + 1).min(BINS - remaining);
+    }
+    edges[BANDS] = BINS;
+    edges
+}
+
+/// Struct defining the 'Spectrum' mode. Transforms blocks of samples from the sound sensor into
+/// the frequency domain and displays their spectrum as a bar graph.
+pub struct SpectrumMode<'a> {
+    /// Input used.
+    input: Box<dyn SampledInput + 'a>,
+    /// Level of each band in % of the scale, if a spectrum has been measured.
+    bands: Option<[f32; BANDS]>,
+    /// Magnitude at the top of the scale. Follows the loudest band, so that the spectrum always
+    /// fills the display.
+    scale: f32,
+}
+
+impl<'a> SpectrumMode<'a> {
+    /// Range of levels below the top of the scale that are shown, in dB.
+    const RANGE_DB: f32 = 40.0;
+    /// Factor by which the scale decays per update, letting it adjust to quieter sounds.
+    const SCALE_DECAY: f32 = 0.95;
+    /// Lowest magnitude at the top of the scale, keeping silence from showing amplified noise.
+    const SCALE_MIN: f32 = 200.0;
+
+    pub fn new(input: impl SampledInput + 'a) -> Self {
+        Self {
+            input: Box::new(input),
+            bands: None,
+            scale: Self::SCALE_MIN,
+        }
+    }
+
+    /// Update the scale and compute the band levels from a magnitude spectrum.
+    fn apply_spectrum(&mut self, magnitudes: &[f32; BINS]) {
+        let edges = band_edges();
+        let mut peaks = [0.0f32; BANDS];
+        for (peak, edge) in peaks.iter_mut().zip(edges.windows(2)) {
+            *peak = magnitudes[edge[0]..edge[1]]
+                .iter()
+                .fold(0.0, |peak, &magnitude| f32::max(peak, magnitude));
+        }
+
+        let loudest = peaks
+            .iter()
+            .fold(0.0, |loudest, &peak| f32::max(loudest, peak));
+        self.scale = (self.scale * Self::SCALE_DECAY)
+            .max(loudest)
+            .max(Self::SCALE_MIN);
+
+        self.bands = Some(peaks.map(|peak| {
+            if peak <= 0.0 {
+                return 0.0;
+            }
+            let db = 20.0 * (peak / self.scale).log10();
+            ((db + Self::RANGE_DB) / Self::RANGE_DB * 100.0).clamp(0.0, 100.0)
+        }));
+    }
+}
+
+#[async_trait]
+impl Update for SpectrumMode<'_> {
+    async fn update(&mut self) {
+        let mut samples = [0; FFT_SIZE];
+        match self.input.read_block(&mut samples).await {
+            Ok(()) => self.apply_spectrum(&spectrum(&samples)),
+            Err(_) => self.bands = None,
+        }
+    }
+}
+
+impl<D> Draw<D> for SpectrumMode<'_>
+where
+    D: DrawTarget,
+{
+    fn draw_with_style(
+        &self,
+        style: &crate::app::AppStyle<<D as DrawTarget>::Color>,
+        draw_area: Rectangle,
+        target: &mut D,
+    ) -> Result<(), <D as DrawTarget>::Error> {
+        let Some(bands) = self.bands else {
+            return Ok(());
+        };
+
+        let band_width = draw_area.size.width / BANDS as u32;
+        // Center the bands, which may not fill the whole width
+        let left = draw_area.top_left.x + (draw_area.size.width % BANDS as u32 / 2) as i32;
+        for (i, &level) in bands.iter().enumerate() {
+            let area = Rectangle::new(
+                Point::new(left + (i as u32 * band_width) as i32, draw_area.top_left.y),
+                // Leave a gap between neighbouring bands
+                Size::new(band_width.saturating_sub(1), draw_area.size.height),
+            );
+            VerticalBar::new(level, style.default_color, area).draw(target)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<D> AppMode<D> for SpectrumMode<'_>
+where
+    D: DrawTarget,
+{
+    fn title(&self) -> String {
+        String::from("Spectrum")
+    }
+
+    /// The spectrum changes quickly with the sound, like on a music player's display.
+    fn update_interval(&self) -> Duration {
+        Duration::from_millis(50)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::vec::Vec;
+    use embassy_futures::block_on;
+
+    use super::*;
+    use crate::peripherals::mock::MockAnalogInput;
+    use crate::test_utils::{assert_snapshot, render};
+
+    /// A block of a sine wave around 2048 with `periods` periods per FFT.
+    fn sine(periods: f32, amplitude: f32) -> [u16; FFT_SIZE] {
+        core::array::from_fn(|i| {
+            let phase = 2.0 * PI * periods * i as f32 / FFT_SIZE as f32;
+            (2048.0 + amplitude * phase.sin()) as u16
+        })
+    }
+
+    fn loudest_index(values: &[f32]) -> usize {
+        (0..values.len())
+            .max_by(|&a, &b| values[a].total_cmp(&values[b]))
+            .unwrap()
+    }
+
+    #[test]
+    fn fft_finds_frequency_of_sine() {
+        for bin in [3, 17, 100] {
+            let magnitudes = spectrum(&sine(bin as f32, 1000.0));
+            assert_eq!(loudest_index(&magnitudes), bin);
+        }
+    }
+
+    #[test]
+    fn constant_signal_has_empty_spectrum() {
+        let magnitudes = spectrum(&[1234; FFT_SIZE]);
+        assert!(magnitudes.iter().all(|&magnitude| magnitude < 1.0));
+    }
+
+    #[test]
+    fn bands_cover_all_bins_in_order() {
+        let edges = band_edges();
+        assert_eq!(edges[0], 1);
+        assert_eq!(edges[BANDS], BINS);
+        assert!(edges.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn update_shows_loudest_band_at_top() {
+        let samples: Vec<_> = sine(40.0, 1000.0).into();
+        let mut mode = SpectrumMode::new(MockAnalogInput::from_values(4096, samples));
+        block_on(mode.update());
+
+        let bands = mode.bands.unwrap();
+        let band = band_edges()
+            .windows(2)
+            .position(|edge| (edge[0]..edge[1]).contains(&40));
+        assert_eq!(Some(loudest_index(&bands)), band);
+        assert!(bands[band.unwrap()] > 99.0);
+    }
+
+    #[test]
+    fn scale_decays_to_minimum() {
+        let mut mode = SpectrumMode::new(MockAnalogInput::failing(4096));
+        mode.apply_spectrum(&[10_000.0; BINS]);
+        assert_eq!(mode.scale, 10_000.0);
+
+        for _ in 0..200 {
+            mode.apply_spectrum(&[0.0; BINS]);
+        }
+        assert_eq!(mode.scale, SpectrumMode::SCALE_MIN);
+        assert_eq!(mode.bands, Some([0.0; BANDS]));
+    }
+
+    #[test]
+    fn error_clears_spectrum() {
+        let mut mode = SpectrumMode::new(MockAnalogInput::failing(4096));
+        mode.bands = Some([50.0; BANDS]);
+        block_on(mode.update());
+        assert_eq!(mode.bands, None);
+    }
+
+    #[test]
+    fn drawing_depends_on_value() {
+        let mut low = SpectrumMode::new(MockAnalogInput::failing(4096));
+        let mut high = SpectrumMode::new(MockAnalogInput::failing(4096));
+        low.bands = Some(core::array::from_fn(|i| 100.0 - i as f32 * 5.0));
+        high.bands = Some(core::array::from_fn(|i| i as f32 * 5.0));
+
+        // The bars need the width of the display to have room for their fill
+        assert!(render(&low) != render(&high));
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut mode = SpectrumMode::new(MockAnalogInput::failing(4096));
+        for (name, bands) in [
+            ("none", None),
+            ("silent", Some([0.0; BANDS])),
+            (
+                "ramp",
+                Some(core::array::from_fn(|i| i as f32 * 100.0 / 15.0)),
+            ),
+        ] {
+            mode.bands = bands;
+            assert_snapshot(&format!("spectrum_{name}"), &mode);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use super::PeripheralError;
+
+use alloc::boxed::Box;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use async_trait::async_trait;
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::Mutex;
+
+/// Relation between the raw values of an ADC and the voltages they correspond to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdcReference {
+    /// Raw value corresponding to `full_scale_mv`, e.g. 4095 or 4096 for a 12 bit ADC.
+    pub full_scale: u16,
+    /// Voltage at full scale in millivolts.
+    pub full_scale_mv: u16,
+}
+
+impl AdcReference {
+    /// Create a reference from a known full scale voltage.
+    pub const fn new(full_scale: u16, full_scale_mv: u16) -> Self {
+        Self {
+            full_scale,
+            full_scale_mv,
+        }
+    }
+
+    /// Create the reference of an ADC converting relative to its supply voltage, from a reading
+    /// `vrefint` of an internal voltage reference. `vrefint_cal` is the reading of the same
+    /// reference taken during production at a supply voltage of `cal_mv`, as stored by STM32
+    /// devices.
+    ///
+    /// Fails if `vrefint` is 0, i.e. the internal reference hasn't been measured correctly.
+    pub fn from_vrefint(
+        full_scale: u16,
+        cal_mv: u16,
+        vrefint_cal: u16,
+        vrefint: u16,
+    ) -> Option<Self> {
+        let supply_mv = (cal_mv as u32 * vrefint_cal as u32).checked_div(vrefint as u32)?;
+        Some(Self::new(full_scale, supply_mv.min(u16::MAX as u32) as u16))
+    }
+
+    /// Voltage in millivolts corresponding to a raw value, rounded to the nearest millivolt.
+    pub fn to_mv(&self, raw: u16) -> u16 {
+        let full_scale = self.full_scale as u32;
+        let mv = (raw as u32 * self.full_scale_mv as u32 + full_scale / 2) / full_scale;
+        mv.min(u16::MAX as u32) as u16
+    }
+}
+
+#[async_trait]
+/// An analog input.
+pub trait AnalogInput: Send {
+    /// Raw input value.
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError>;
+
+    /// Maximum value this input can provide.
+    async fn max_value(&self) -> Result<u16, PeripheralError>;
+
+    /// Relative input value as compared to its maximum value.
+    async fn input_pct(&mut self) -> Result<f32, PeripheralError> {
+        let raw = self.input_raw().await?;
+        let max = self.max_value().await?;
+        let pct = (raw as f32 / max as f32) * 100.0;
+        Ok(pct)
+    }
+
+    /// Reference relating raw values to voltages. Fails for inputs that can't tell, which is the
+    /// default.
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        Err(PeripheralError::Adc)
+    }
+
+    /// Input voltage in millivolts.
+    async fn input_mv(&mut self) -> Result<u16, PeripheralError> {
+        let raw = self.input_raw().await?;
+        let reference = self.reference().await?;
+        Ok(reference.to_mv(raw))
+    }
+}
+
+#[async_trait]
+impl<T, M> AnalogInput for &Mutex<M, T>
+where
+    T: AnalogInput + Send,
+    M: RawMutex + Send + Sync,
+{
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        let mut lock = self.lock().await;
+        lock.input_raw().await
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        let lock = self.lock().await;
+        lock.max_value().await
+    }
+
+    async fn input_pct(&mut self) -> Result<f32, PeripheralError> {
+        let mut lock = self.lock().await;
+        lock.input_pct().await
+    }
+
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        let mut lock = self.lock().await;
+        lock.reference().await
+    }
+
+    async fn input_mv(&mut self) -> Result<u16, PeripheralError> {
+        let mut lock = self.lock().await;
+        lock.input_mv().await
+    }
+}
+
+// Targets without atomic compare-and-swap (e.g. Cortex-M0+) don't have `Arc`.
+#[cfg(target_has_atomic = "ptr")]
+#[async_trait]
+impl<T, M> AnalogInput for Arc<Mutex<M, T>>
+where
+    T: AnalogInput + Send,
+    M: RawMutex + Send + Sync,
+{
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        let mut lock = self.lock().await;
+        lock.input_raw().await
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        let lock = self.lock().await;
+        lock.max_value().await
+    }
+
+    async fn input_pct(&mut self) -> Result<f32, PeripheralError> {
+        let mut lock = self.lock().await;
+        lock.input_pct().await
+    }
+
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        let mut lock = self.lock().await;
+        lock.reference().await
+    }
+
+    async fn input_mv(&mut self) -> Result<u16, PeripheralError> {
+        let mut lock = self.lock().await;
+        lock.input_mv().await
+    }
+}
+
+#[async_trait]
+impl<T> AnalogInput for Box<T>
+where
+    T: AnalogInput + Sync + ?Sized,
+{
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        (**self).input_raw().await
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        (**self).max_value().await
+    }
+
+    async fn input_pct(&mut self) -> Result<f32, PeripheralError> {
+        (**self).input_pct().await
+    }
+
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        (**self).reference().await
+    }
+
+    async fn input_mv(&mut self) -> Result<u16, PeripheralError> {
+        (**self).input_mv().await
+    }
+}
+
+/// An analog input whose relative value is reversed (i.e. the maximum value registers as 0%, and 0
+/// registers as 100%).
+pub struct ReversedAnalogInput<T> {
+    input: T,
+}
+
+impl<T> ReversedAnalogInput<T> {
+    /// Create a new reversed input based on an existing input.
+    pub fn new(input: T) -> Self {
+        Self { input }
+    }
+}
+
+#[async_trait]
+impl<T> AnalogInput for ReversedAnalogInput<T>
+where
+    T: AnalogInput + Sync,
+{
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        self.input.input_raw().await
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        self.input.max_value().await
+    }
+
+    async fn input_pct(&mut self) -> Result<f32, PeripheralError> {
+        let pct = self.input.input_pct().await?;
+        Ok(100.0 - pct)
+    }
+
+    /// Reversing the relative value doesn't change the voltage at the input.
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        self.input.reference().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::block_on;
+
+    use super::*;
+    use crate::peripherals::mock::MockAnalogInput;
+
+    #[test]
+    fn reversed_input_inverts_percentage() {
+        let mut input = ReversedAnalogInput::new(MockAnalogInput::constant(4096, 1024));
+
+        assert_eq!(block_on(input.input_raw()), Ok(1024));
+        assert_eq!(block_on(input.input_pct()), Ok(75.0));
+    }
+
+    #[test]
+    fn reversed_input_keeps_voltage() {
+        let mut input = ReversedAnalogInput::new(
+            MockAnalogInput::constant(4096, 1024).with_reference(AdcReference::new(4096, 3300)),
+        );
+        assert_eq!(block_on(input.input_mv()), Ok(825));
+    }
+
+    #[test]
+    fn input_without_reference_has_no_voltage() {
+        let mut input = MockAnalogInput::constant(4096, 1024);
+        assert_eq!(block_on(input.input_mv()), Err(PeripheralError::Adc));
+    }
+
+    #[test]
+    fn reference_converts_to_millivolts() {
+        let reference = AdcReference::new(4095, 3300);
+        assert_eq!(reference.to_mv(0), 0);
+        assert_eq!(reference.to_mv(4095), 3300);
+        assert_eq!(reference.to_mv(2048), 1650);
+    }
+
+    #[test]
+    fn vrefint_corrects_for_supply_voltage() {
+        // Calibrated at 3.0V, the same reading at 3.3V is 10% lower
+        let reference = AdcReference::from_vrefint(4095, 3000, 1650, 1500).unwrap();
+        assert_eq!(reference.full_scale_mv, 3300);
+        assert_eq!(reference.to_mv(4095), 3300);
+        assert_eq!(AdcReference::from_vrefint(4095, 3000, 1650, 0), None);
+    }
+
+    #[test]
+    fn reversed_input_forwards_errors() {
+        let mut input = ReversedAnalogInput::new(MockAnalogInput::failing(4096));
+        assert_eq!(block_on(input.input_pct()), Err(PeripheralError::Adc));
+    }
+}
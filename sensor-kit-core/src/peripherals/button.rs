@@ -0,0 +1,257 @@
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use embassy_futures::select::{select, Either};
+use embassy_time::{Duration, Timer};
+
+use super::PeripheralError;
+
+/// Gesture recognized from presses of a button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonEvent {
+    /// A short press, released before a long press and not followed by a second press.
+    Press,
+    /// The button has been held for a long press. It is still held down.
+    LongPress,
+    /// Two short presses in quick succession.
+    DoubleClick,
+    /// Repeated periodically while the button is still held after a long press.
+    Hold,
+}
+
+/// Timings distinguishing the gestures of a [`ButtonDriver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ButtonTimings {
+    /// Time after a press or release during which the contacts may bounce.
+    pub debounce: Duration,
+    /// Time the button is held before a press counts as a long press.
+    pub long_press: Duration,
+    /// Time after the release of a short press within which a second press makes a double click.
+    /// This also delays [`ButtonEvent::Press`], which is only known once no second press follows.
+    pub double_click: Duration,
+    /// Interval of [`ButtonEvent::Hold`] while the button is held after a long press.
+    pub repeat: Duration,
+}
+
+impl ButtonTimings {
+    /// Create a new set of timings.
+    pub const fn new(
+        debounce: Duration,
+        long_press: Duration,
+        double_click: Duration,
+        repeat: Duration,
+    ) -> Self {
+        Self {
+            debounce,
+            long_press,
+            double_click,
+            repeat,
+        }
+    }
+}
+
+impl Default for ButtonTimings {
+    fn default() -> Self {
+        Self::new(
+            Duration::from_millis(20),
+            Duration::from_millis(600),
+            Duration::from_millis(250),
+            Duration::from_millis(200),
+        )
+    }
+}
+
+#[async_trait]
+/// A button whose state changes can be awaited.
+pub trait ButtonInput: Send {
+    /// Wait until the button is pressed. Returns immediately if it is pressed already.
+    async fn wait_for_press(&mut self) -> Result<(), PeripheralError>;
+
+    /// Wait until the button is released. Returns immediately if it is released already.
+    async fn wait_for_release(&mut self) -> Result<(), PeripheralError>;
+}
+
+#[async_trait]
+/// Clock timing the gestures recognized by a [`ButtonDriver`].
+pub trait Clock: Send {
+    /// Wait until `duration` has passed.
+    async fn delay(&mut self, duration: Duration);
+}
+
+/// The [`embassy_time`] clock, used by a [`ButtonDriver`] unless given another one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmbassyClock;
+
+#[async_trait]
+impl Clock for EmbassyClock {
+    async fn delay(&mut self, duration: Duration) {
+        Timer::after(duration).await;
+    }
+}
+
+/// Driver turning the presses and releases of a button into [`ButtonEvent`]s.
+///
+/// Bouncing contacts are ignored by not looking at the button for a while after each press or
+/// release.
+pub struct ButtonDriver<B, C = EmbassyClock> {
+    button: B,
+    clock: C,
+    timings: ButtonTimings,
+    /// Whether the button is held after a long press.
+    held: bool,
+}
+
+impl<B: ButtonInput> ButtonDriver<B> {
+    /// Create a new driver recognizing gestures with the given `timings`.
+    pub fn new(button: B, timings: ButtonTimings) -> Self {
+        Self::with_clock(button, EmbassyClock, timings)
+    }
+}
+
+impl<B: ButtonInput, C: Clock> ButtonDriver<B, C> {
+    /// Create a new driver recognizing gestures with the given `timings` as measured by `clock`.
+    pub fn with_clock(button: B, clock: C, timings: ButtonTimings) -> Self {
+        Self {
+            button,
+            clock,
+            timings,
+            held: false,
+        }
+    }
+
+    /// Wait for the next gesture.
+    pub async fn next_event(&mut self) -> Result<ButtonEvent, PeripheralError> {
+        if self.held {
+            let released = self.wait_within(false, self.timings.repeat).await;
+            if released == Ok(false) {
+                return Ok(ButtonEvent::Hold);
+            }
+            self.held = false;
+            released?;
+            self.debounce().await;
+        }
+
+        self.button.wait_for_press().await?;
+        self.debounce().await;
+
+        // The press has lasted for the debounce time already
+        let long_press = self
+            .timings
+            .long_press
+            .checked_sub(self.timings.debounce)
+            .unwrap_or(Duration::MIN);
+
+        let mut clicked = false;
+        loop {
+            if !self.wait_within(false, long_press).await? {
+                self.held = true;
+                return Ok(ButtonEvent::LongPress);
+            }
+            self.debounce().await;
+
+            if clicked {
+                return Ok(ButtonEvent::DoubleClick);
+            }
+
+            if !self.wait_within(true, self.timings.double_click).await? {
+                return Ok(ButtonEvent::Press);
+            }
+            self.debounce().await;
+            clicked = true;
+        }
+    }
+
+    /// Wait at most `timeout` for the button to be `pressed` or released. Returns whether it got
+    /// there in time.
+    async fn wait_within(
+        &mut self,
+        pressed: bool,
+        timeout: Duration,
+    ) -> Result<bool, PeripheralError> {
+        let button = &mut self.button;
+        let wait = async {
+            if pressed {
+                button.wait_for_press().await
+            } else {
+                button.wait_for_release().await
+            }
+        };
+        match select(wait, self.clock.delay(timeout)).await {
+            Either::First(result) => result.map(|()| true),
+            Either::Second(()) => Ok(false),
+        }
+    }
+
+    /// Wait for the contacts to settle, without looking at the button.
+    async fn debounce(&mut self) {
+        self.clock.delay(self.timings.debounce).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::peripherals::mock::{MockButton, MockClock};
+
+    /// Short timings keeping the timelines short. The driver runs on a simulated clock, so the
+    /// tests neither wait for them nor depend on the scheduling of the host.
+    const TIMINGS: ButtonTimings = ButtonTimings::new(
+        Duration::from_millis(5),
+        Duration::from_millis(100),
+        Duration::from_millis(30),
+        Duration::from_millis(40),
+    );
+
+    /// The first `count` events recognized from a button changing its state at the given times in
+    /// ms, alternating between pressed and released.
+    fn events(changes: &[u64], count: usize) -> Vec<ButtonEvent> {
+        let clock = MockClock::new();
+        let button = MockButton::new(&clock, changes.iter().copied());
+        let mut driver = ButtonDriver::with_clock(button, clock.clone(), TIMINGS);
+        (0..count)
+            .map(|_| clock.run(driver.next_event()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn short_press_is_press() {
+        assert_eq!(events(&[10, 40], 1), [ButtonEvent::Press]);
+    }
+
+    #[test]
+    fn long_press_starts_exactly_after_its_time() {
+        assert_eq!(events(&[10, 109], 1), [ButtonEvent::Press]);
+        assert_eq!(events(&[10, 111], 1), [ButtonEvent::LongPress]);
+    }
+
+    #[test]
+    fn bouncing_contacts_are_ignored() {
+        assert_eq!(
+            events(&[10, 11, 13, 40, 41, 43, 120, 150], 2),
+            [ButtonEvent::Press, ButtonEvent::Press]
+        );
+    }
+
+    #[test]
+    fn two_quick_presses_are_double_click() {
+        assert_eq!(
+            events(&[10, 30, 50, 70, 150, 170], 2),
+            [ButtonEvent::DoubleClick, ButtonEvent::Press]
+        );
+    }
+
+    #[test]
+    fn held_button_repeats_until_released() {
+        assert_eq!(
+            events(&[10, 250, 300, 320], 5),
+            [
+                ButtonEvent::LongPress,
+                ButtonEvent::Hold,
+                ButtonEvent::Hold,
+                ButtonEvent::Hold,
+                ButtonEvent::Press,
+            ]
+        );
+    }
+}
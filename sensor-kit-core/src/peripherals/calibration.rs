@@ -0,0 +1,235 @@
+use super::{AdcReference, AnalogInput, PeripheralError};
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use async_trait::async_trait;
+use core::ops::RangeInclusive;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+/// Error type for invalid calibrations.
+pub enum CalibrationError {
+    #[error("Calibration needs at least two points")]
+    /// Less than two calibration points were given.
+    TooFewPoints,
+    #[error("Calibration points must have distinct raw values")]
+    /// Two calibration points share the same raw value.
+    DuplicateRaw,
+}
+
+/// Mapping of raw ADC values to a physical quantity, as measured for a specific sensor.
+///
+/// Both variants interpolate linearly between their points, given as `(raw, value)`. Raw values
+/// beyond the outermost points are clamped, as the sensor hasn't been characterized there.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Calibration {
+    /// Linear mapping through two points.
+    TwoPoint([(u16, f32); 2]),
+    /// Piecewise linear mapping through any number of points, sorted by raw value.
+    Table(Vec<(u16, f32)>),
+}
+
+impl Calibration {
+    /// Create a linear calibration through two points.
+    pub fn two_point(a: (u16, f32), b: (u16, f32)) -> Result<Self, CalibrationError> {
+        if a.0 == b.0 {
+            return Err(CalibrationError::DuplicateRaw);
+        }
+        let points = if a.0 < b.0 { [a, b] } else { [b, a] };
+        Ok(Self::TwoPoint(points))
+    }
+
+    /// Create a piecewise linear calibration through `points`, in any order.
+    pub fn table(points: impl IntoIterator<Item = (u16, f32)>) -> Result<Self, CalibrationError> {
+        let mut points: Vec<_> = points.into_iter().collect();
+        if points.len() < 2 {
+            return Err(CalibrationError::TooFewPoints);
+        }
+        points.sort_unstable_by_key(|&(raw, _)| raw);
+        if points.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(CalibrationError::DuplicateRaw);
+        }
+        Ok(Self::Table(points))
+    }
+
+    fn points(&self) -> &[(u16, f32)] {
+        match self {
+            Self::TwoPoint(points) => points,
+            Self::Table(points) => points,
+        }
+    }
+
+    /// Calibrated value of a raw value.
+    pub fn apply(&self, raw: u16) -> f32 {
+        let points = self.points();
+        // Index of the first point above `raw`, selecting the segment to interpolate on
+        let upper = points
+            .iter()
+            .position(|&(point_raw, _)| point_raw > raw)
+            .unwrap_or(points.len());
+        if upper == 0 {
+            return points[0].1;
+        }
+        if upper == points.len() {
+            return points[points.len() - 1].1;
+        }
+
+        let (raw_0, value_0) = points[upper - 1];
+        let (raw_1, value_1) = points[upper];
+        let t = (raw - raw_0) as f32 / (raw_1 - raw_0) as f32;
+        value_0 + t * (value_1 - value_0)
+    }
+
+    /// Range of calibrated values. Calibrations may be decreasing, so this isn't necessarily the
+    /// range between the values of the first and last points.
+    pub fn range(&self) -> RangeInclusive<f32> {
+        let values = self.points().iter().map(|&(_, value)| value);
+        let min = values.clone().fold(f32::INFINITY, f32::min);
+        let max = values.fold(f32::NEG_INFINITY, f32::max);
+        min..=max
+    }
+}
+
+/// An analog input measuring a physical quantity, mapping its raw values to that quantity through
+/// a [`Calibration`].
+///
+/// As an [`AnalogInput`], its relative value is the position of the calibrated value within the
+/// range of the calibration.
+pub struct CalibratedAnalogInput<T> {
+    input: T,
+    calibration: Calibration,
+    /// Unit of the calibrated values.
+    unit: &'static str,
+}
+
+impl<T> CalibratedAnalogInput<T> {
+    /// Create a new calibrated input based on an existing input, with values measured in `unit`.
+    pub fn new(input: T, calibration: Calibration, unit: &'static str) -> Self {
+        Self {
+            input,
+            calibration,
+            unit,
+        }
+    }
+
+    /// Unit of the calibrated values.
+    pub fn unit(&self) -> &'static str {
+        self.unit
+    }
+
+    /// Range of calibrated values.
+    pub fn range(&self) -> RangeInclusive<f32> {
+        self.calibration.range()
+    }
+}
+
+impl<T> CalibratedAnalogInput<T>
+where
+    T: AnalogInput,
+{
+    /// Calibrated input value, in [`Self::unit`].
+    pub async fn value(&mut self) -> Result<f32, PeripheralError> {
+        let raw = self.input.input_raw().await?;
+        Ok(self.calibration.apply(raw))
+    }
+}
+
+#[async_trait]
+impl<T> AnalogInput for CalibratedAnalogInput<T>
+where
+    T: AnalogInput + Sync,
+{
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        self.input.input_raw().await
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        self.input.max_value().await
+    }
+
+    async fn input_pct(&mut self) -> Result<f32, PeripheralError> {
+        let value = self.value().await?;
+        let range = self.range();
+        let span = range.end() - range.start();
+        if span == 0.0 {
+            return Ok(0.0);
+        }
+        Ok((value - range.start()) / span * 100.0)
+    }
+
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        self.input.reference().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::block_on;
+
+    use super::*;
+    use crate::peripherals::mock::MockAnalogInput;
+
+    #[test]
+    fn two_point_interpolates_and_clamps() {
+        let calibration = Calibration::two_point((1000, 20.0), (3000, 40.0)).unwrap();
+        assert_eq!(calibration.apply(2000), 30.0);
+        assert_eq!(calibration.apply(0), 20.0);
+        assert_eq!(calibration.apply(4000), 40.0);
+        assert_eq!(calibration.range(), 20.0..=40.0);
+    }
+
+    #[test]
+    fn two_point_accepts_points_in_any_order() {
+        let calibration = Calibration::two_point((3000, 0.0), (1000, 100.0)).unwrap();
+        assert_eq!(calibration.apply(1500), 75.0);
+        assert_eq!(calibration.range(), 0.0..=100.0);
+    }
+
+    #[test]
+    fn table_interpolates_piecewise() {
+        let calibration =
+            Calibration::table([(2000, 10.0), (0, 0.0), (1000, 100.0), (4000, 50.0)]).unwrap();
+        assert_eq!(calibration.apply(500), 50.0);
+        assert_eq!(calibration.apply(1000), 100.0);
+        assert_eq!(calibration.apply(1500), 55.0);
+        assert_eq!(calibration.apply(3000), 30.0);
+        assert_eq!(calibration.apply(4095), 50.0);
+        assert_eq!(calibration.range(), 0.0..=100.0);
+    }
+
+    #[test]
+    fn invalid_calibrations_are_rejected() {
+        assert_eq!(
+            Calibration::two_point((10, 0.0), (10, 1.0)),
+            Err(CalibrationError::DuplicateRaw)
+        );
+        assert_eq!(
+            Calibration::table([(10, 0.0)]),
+            Err(CalibrationError::TooFewPoints)
+        );
+        assert_eq!(
+            Calibration::table([(10, 0.0), (20, 1.0), (10, 2.0)]),
+            Err(CalibrationError::DuplicateRaw)
+        );
+    }
+
+    #[test]
+    fn calibrated_input_reports_value_unit_and_relative_value() {
+        let calibration = Calibration::two_point((0, 0.0), (1500, 100.0)).unwrap();
+        let mut input =
+            CalibratedAnalogInput::new(MockAnalogInput::constant(4096, 750), calibration, "%");
+
+        assert_eq!(block_on(input.value()), Ok(50.0));
+        assert_eq!(block_on(input.input_pct()), Ok(50.0));
+        assert_eq!(block_on(input.input_raw()), Ok(750));
+        assert_eq!(input.unit(), "%");
+    }
+
+    #[test]
+    fn calibrated_input_clamps_relative_value() {
+        let calibration = Calibration::two_point((0, -10.0), (1500, 10.0)).unwrap();
+        let mut input =
+            CalibratedAnalogInput::new(MockAnalogInput::constant(4096, 3000), calibration, "°C");
+        assert_eq!(block_on(input.input_pct()), Ok(100.0));
+    }
+}
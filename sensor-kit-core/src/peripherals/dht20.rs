@@ -0,0 +1,212 @@
+use embassy_time::Timer;
+use embedded_hal_async::i2c::I2c;
+
+use super::PeripheralError;
+
+/// I2C address of the DHT20.
+const ADDRESS: u8 = 0x38;
+/// Command reading the status byte.
+const READ_STATUS: u8 = 0x71;
+/// Status bits set once the sensor is calibrated.
+const STATUS_CALIBRATED: u8 = 0x18;
+/// Status bit set while a measurement is running.
+const STATUS_BUSY: u8 = 0x80;
+/// Command starting a measurement.
+const TRIGGER_MEASUREMENT: [u8; 3] = [0xac, 0x33, 0x00];
+/// Registers to reset if the sensor isn't calibrated.
+const CALIBRATION_REGISTERS: [u8; 3] = [0x1b, 0x1c, 0x1e];
+/// Number of times the status is polled after a measurement is expected to be done, 1 ms apart.
+const BUSY_POLLS: usize = 20;
+/// Full scale of the 20 bit values transmitted by the sensor.
+const FULL_SCALE: f32 = (1 << 20) as f32;
+
+/// A measurement of the DHT20.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dht20Reading {
+    /// Temperature in °C.
+    pub temperature_c: f32,
+    /// Relative humidity in %.
+    pub humidity_pct: f32,
+}
+
+/// Driver for the DHT20 temperature and humidity sensor. Waits for the sensor on timers, so other
+/// tasks keep running during the 80 ms of a measurement.
+pub struct Dht20<I> {
+    i2c: I,
+}
+
+impl<I: I2c> Dht20<I> {
+    pub fn new(i2c: I) -> Self {
+        Self { i2c }
+    }
+
+    /// Measure temperature and humidity at once.
+    pub async fn read(&mut self) -> Result<Dht20Reading, PeripheralError> {
+        if self.status().await? & STATUS_CALIBRATED != STATUS_CALIBRATED {
+            for register in CALIBRATION_REGISTERS {
+                self.write(&[register, 0, 0]).await?;
+            }
+        }
+
+        Timer::after_millis(10).await;
+        self.write(&TRIGGER_MEASUREMENT).await?;
+        Timer::after_millis(80).await;
+
+        let mut response = [0; 7];
+        for _ in 0..BUSY_POLLS {
+            self.i2c
+                .read(ADDRESS, &mut response)
+                .await
+                .map_err(|_| PeripheralError::I2c)?;
+            if response[0] & STATUS_BUSY == 0 {
+                return parse(&response);
+            }
+            Timer::after_millis(1).await;
+        }
+        Err(PeripheralError::I2c)
+    }
+
+    async fn status(&mut self) -> Result<u8, PeripheralError> {
+        let mut status = [0];
+        self.i2c
+            .write_read(ADDRESS, &[READ_STATUS], &mut status)
+            .await
+            .map_err(|_| PeripheralError::I2c)?;
+        Ok(status[0])
+    }
+
+    async fn write(&mut self, data: &[u8]) -> Result<(), PeripheralError> {
+        self.i2c
+            .write(ADDRESS, data)
+            .await
+            .map_err(|_| PeripheralError::I2c)
+    }
+}
+
+/// Parse a response of status byte, 5 data bytes and CRC.
+fn parse(response: &[u8; 7]) -> Result<Dht20Reading, PeripheralError> {
+    if crc8(&response[..6]) != response[6] {
+        return Err(PeripheralError::I2c);
+    }
+
+    // Both values are 20 bits wide and share the middle byte
+    let humidity =
+        (response[1] as u32) << 12 | (response[2] as u32) << 4 | (response[3] as u32) >> 4;
+    let temperature =
+        (response[3] as u32 & 0x0f) << 16 | (response[4] as u32) << 8 | response[5] as u32;
+
+    Ok(Dht20Reading {
+        temperature_c: temperature as f32 / FULL_SCALE * 200.0 - 50.0,
+        humidity_pct: humidity as f32 / FULL_SCALE * 100.0,
+    })
+}
+
+/// CRC-8 as used by the DHT20 (polynomial 0x31, initial value 0xff).
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0xffu8;
+    for byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x31
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::Infallible;
+    use embassy_futures::block_on;
+    use embedded_hal_async::i2c::{ErrorType, Operation};
+
+    use super::*;
+
+    /// Response for 50 % humidity and 12.5 °C.
+    fn response(busy: bool) -> [u8; 7] {
+        let mut response = [0x1c, 0x80, 0x00, 0x05, 0x00, 0x00, 0];
+        if busy {
+            response[0] |= STATUS_BUSY;
+        }
+        response[6] = crc8(&response[..6]);
+        response
+    }
+
+    /// Bus with a DHT20 that stays busy for a number of reads.
+    struct Bus {
+        busy_reads: usize,
+        measurements: usize,
+    }
+
+    impl ErrorType for Bus {
+        type Error = Infallible;
+    }
+
+    impl I2c for Bus {
+        async fn transaction(
+            &mut self,
+            _address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Infallible> {
+            for operation in operations {
+                match operation {
+                    Operation::Write(data) if data[0] == TRIGGER_MEASUREMENT[0] => {
+                        self.measurements += 1
+                    }
+                    Operation::Write(_) => (),
+                    Operation::Read(buffer) => {
+                        let busy = self.busy_reads > 0;
+                        self.busy_reads = self.busy_reads.saturating_sub(1);
+                        let response = response(busy);
+                        let len = buffer.len().min(response.len());
+                        buffer[..len].copy_from_slice(&response[..len]);
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn response_is_parsed() {
+        let reading = parse(&response(false)).unwrap();
+        assert!((reading.humidity_pct - 50.0).abs() < 0.01);
+        assert!((reading.temperature_c - 12.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn corrupted_response_is_rejected() {
+        let mut response = response(false);
+        response[2] ^= 0x01;
+        assert_eq!(parse(&response), Err(PeripheralError::I2c));
+    }
+
+    #[test]
+    fn read_measures_once() {
+        let mut dht20 = Dht20::new(Bus {
+            busy_reads: 0,
+            measurements: 0,
+        });
+        assert!(block_on(dht20.read()).is_ok());
+        assert_eq!(dht20.i2c.measurements, 1);
+    }
+
+    #[test]
+    fn read_waits_while_busy() {
+        // The status read counts as the first busy read
+        let mut waiting = Dht20::new(Bus {
+            busy_reads: 3,
+            measurements: 0,
+        });
+        assert!(block_on(waiting.read()).is_ok());
+
+        let mut stuck = Dht20::new(Bus {
+            busy_reads: usize::MAX,
+            measurements: 0,
+        });
+        assert_eq!(block_on(stuck.read()), Err(PeripheralError::I2c));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::mode::environment::EnvironmentSensors;
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use bme280::i2c::AsyncBME280;
+use embassy_time::Delay;
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+use super::{Dht20, EnvironmentReading, PeripheralError};
+
+/// Async I2C interface on top of a blocking one. Transfers still block, but drivers can wait for
+/// their devices on timers in between.
+pub struct AsyncI2c<I>(I);
+
+impl<I: I2c> ErrorType for AsyncI2c<I> {
+    type Error = I::Error;
+}
+
+impl<I: I2c> embedded_hal_async::i2c::I2c for AsyncI2c<I> {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.0.transaction(address, operations)
+    }
+}
+
+/// Struct containing environment sensors, i.e. the DHT20 and BMP280.
+pub struct SensorKitEnvSensors<I> {
+    /// DHT20 Temperature and humidity sensor.
+    dht20: Dht20<AsyncI2c<I>>,
+    /// BMP280 Temperature and pressure sensor.
+    bmp280: AsyncBME280<AsyncI2c<I>>,
+}
+
+impl<I: I2c> SensorKitEnvSensors<I> {
+    /// Set up the sensors, given a device on the bus for each of them.
+    pub async fn init(dht20: I, bmp280: I) -> Result<Self, PeripheralError> {
+        let mut bmp280 = AsyncBME280::new_secondary(AsyncI2c(bmp280));
+        bmp280
+            .init(&mut Delay)
+            .await
+            .map_err(|_| PeripheralError::I2c)?;
+        Ok(Self {
+            dht20: Dht20::new(AsyncI2c(dht20)),
+            bmp280,
+        })
+    }
+}
+
+#[async_trait]
+impl<I> EnvironmentSensors for SensorKitEnvSensors<I>
+where
+    I: I2c + Send + 'static,
+    I::Error: Send,
+{
+    async fn read(&mut self) -> EnvironmentReading {
+        let dht20 = self.dht20.read().await;
+        let pressure_kpa = self
+            .bmp280
+            .measure(&mut Delay)
+            .await
+            .map(|measurements| measurements.pressure / 1000.0)
+            .map_err(|_| PeripheralError::I2c);
+        EnvironmentReading {
+            temperature_c: dht20.map(|reading| reading.temperature_c),
+            humidity_pct: dht20.map(|reading| reading.humidity_pct),
+            pressure_kpa,
+        }
+    }
+}
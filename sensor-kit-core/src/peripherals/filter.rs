@@ -0,0 +1,395 @@
+use super::{AdcReference, AnalogInput, PeripheralError, SampledInput};
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+
+/// Last `N` raw values read from an input.
+struct Window<const N: usize> {
+    values: [u16; N],
+    /// Index the next value is written to.
+    next: usize,
+    /// Number of valid values.
+    filled: usize,
+}
+
+impl<const N: usize> Window<N> {
+    fn new() -> Self {
+        assert!(N > 0, "window must not be empty");
+        Self {
+            values: [0; N],
+            next: 0,
+            filled: 0,
+        }
+    }
+
+    /// Add a value, dropping the oldest one once the window is full. Returns the valid values.
+    fn push(&mut self, value: u16) -> &[u16] {
+        self.values[self.next] = value;
+        self.next = (self.next + 1) % N;
+        self.filled = (self.filled + 1).min(N);
+        &self.values[..self.filled]
+    }
+}
+
+/// An analog input whose raw value is the average of its last `N` raw values.
+///
+/// Until `N` values have been read, the average covers the values read so far.
+pub struct MovingAverage<T, const N: usize> {
+    input: T,
+    window: Window<N>,
+}
+
+impl<T, const N: usize> MovingAverage<T, N> {
+    /// Create a new moving average of an existing input.
+    ///
+    /// # Panics
+    ///
+    /// If `N` is 0.
+    pub fn new(input: T) -> Self {
+        Self {
+            input,
+            window: Window::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl<T, const N: usize> AnalogInput for MovingAverage<T, N>
+where
+    T: AnalogInput + Sync,
+{
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        let raw = self.input.input_raw().await?;
+        let values = self.window.push(raw);
+        let sum: u32 = values.iter().map(|&value| value as u32).sum();
+        Ok(((sum + values.len() as u32 / 2) / values.len() as u32) as u16)
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        self.input.max_value().await
+    }
+
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        self.input.reference().await
+    }
+}
+
+/// An analog input whose raw value is the median of its last `N` raw values. Unlike an average,
+/// the median ignores short spikes entirely.
+///
+/// Until `N` values have been read, the median covers the values read so far.
+pub struct MedianFilter<T, const N: usize> {
+    input: T,
+    window: Window<N>,
+}
+
+impl<T, const N: usize> MedianFilter<T, N> {
+    /// Create a new median filter for an existing input.
+    ///
+    /// # Panics
+    ///
+    /// If `N` is 0.
+    pub fn new(input: T) -> Self {
+        Self {
+            input,
+            window: Window::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl<T, const N: usize> AnalogInput for MedianFilter<T, N>
+where
+    T: AnalogInput + Sync,
+{
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        let raw = self.input.input_raw().await?;
+        let values = self.window.push(raw);
+        let mut sorted = [0; N];
+        let sorted = &mut sorted[..values.len()];
+        sorted.copy_from_slice(values);
+        sorted.sort_unstable();
+        Ok(sorted[sorted.len() / 2])
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        self.input.max_value().await
+    }
+
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        self.input.reference().await
+    }
+}
+
+/// An analog input smoothed by an exponential moving average. Each new raw value is weighted by
+/// `alpha`, the previous output by `1 - alpha`.
+pub struct ExponentialSmoothing<T> {
+    input: T,
+    /// Weight of new values, between 0 (never changes) and 1 (no smoothing).
+    alpha: f32,
+    /// Current output, if any value has been read yet.
+    value: Option<f32>,
+}
+
+impl<T> ExponentialSmoothing<T> {
+    /// Create a new smoothed input based on an existing input. `alpha` is clamped to `0.0..=1.0`.
+    pub fn new(input: T, alpha: f32) -> Self {
+        Self {
+            input,
+            alpha: alpha.clamp(0.0, 1.0),
+            value: None,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> AnalogInput for ExponentialSmoothing<T>
+where
+    T: AnalogInput + Sync,
+{
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        let raw = self.input.input_raw().await? as f32;
+        let value = match self.value {
+            Some(value) => value + self.alpha * (raw - value),
+            // Start from the first value rather than ramping up from 0
+            None => raw,
+        };
+        self.value = Some(value);
+        Ok((value + 0.5) as u16)
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        self.input.max_value().await
+    }
+
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        self.input.reference().await
+    }
+}
+
+/// An analog input that ignores changes of its raw value by up to `width`. Once the raw value
+/// leaves that band, the output jumps to it and the band is centered on the new value.
+pub struct Deadband<T> {
+    input: T,
+    width: u16,
+    /// Current output, if any value has been read yet.
+    value: Option<u16>,
+}
+
+impl<T> Deadband<T> {
+    /// Create a new input with a dead band of `width` raw steps around its value.
+    pub fn new(input: T, width: u16) -> Self {
+        Self {
+            input,
+            width,
+            value: None,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> AnalogInput for Deadband<T>
+where
+    T: AnalogInput + Sync,
+{
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        let raw = self.input.input_raw().await?;
+        let value = match self.value {
+            Some(value) if raw.abs_diff(value) <= self.width => value,
+            _ => raw,
+        };
+        self.value = Some(value);
+        Ok(value)
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        self.input.max_value().await
+    }
+
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        self.input.reference().await
+    }
+}
+
+/// An analog input with hysteresis: its output lags up to `width` raw steps behind the raw value,
+/// so it only moves once the raw value keeps moving in the same direction.
+///
+/// Unlike [`Deadband`], the output follows the raw value smoothly, without jumps.
+pub struct Hysteresis<T> {
+    input: T,
+    width: u16,
+    /// Current output, if any value has been read yet.
+    value: Option<u16>,
+}
+
+impl<T> Hysteresis<T> {
+    /// Create a new input lagging up to `width` raw steps behind an existing input.
+    pub fn new(input: T, width: u16) -> Self {
+        Self {
+            input,
+            width,
+            value: None,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> AnalogInput for Hysteresis<T>
+where
+    T: AnalogInput + Sync,
+{
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        let raw = self.input.input_raw().await?;
+        let value = match self.value {
+            Some(value) => value.clamp(
+                raw.saturating_sub(self.width),
+                raw.saturating_add(self.width),
+            ),
+            None => raw,
+        };
+        self.value = Some(value);
+        Ok(value)
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        self.input.max_value().await
+    }
+
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        self.input.reference().await
+    }
+}
+
+/// An analog input whose raw value is the average of its `N` most recent samples, reducing noise
+/// by taking more samples than are read.
+///
+/// The samples are taken from a [`SampledInput`], so oversampling doesn't slow down reads.
+pub struct Oversampled<T, const N: usize> {
+    input: T,
+}
+
+impl<T, const N: usize> Oversampled<T, N> {
+    /// Create a new oversampled input based on a continuously sampled input.
+    ///
+    /// # Panics
+    ///
+    /// If `N` is 0.
+    pub fn new(input: T) -> Self {
+        assert!(N > 0, "oversampling needs at least one sample");
+        Self { input }
+    }
+}
+
+#[async_trait]
+impl<T, const N: usize> AnalogInput for Oversampled<T, N>
+where
+    T: SampledInput + Sync,
+{
+    /// Waits for `N` samples to be taken if sampling has only just started.
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        let mut samples = [0; N];
+        self.input.read_block(&mut samples).await?;
+        let sum: u32 = samples.iter().map(|&sample| sample as u32).sum();
+        Ok(((sum + N as u32 / 2) / N as u32) as u16)
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        self.input.max_value().await
+    }
+
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        self.input.reference().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use embassy_futures::block_on;
+    use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+    use fugit::HertzU32;
+
+    use super::*;
+    use crate::peripherals::mock::MockAnalogInput;
+    use crate::peripherals::{ReversedAnalogInput, ScanBuffer};
+
+    /// Read `count` raw values from `input`.
+    fn read(input: &mut impl AnalogInput, count: usize) -> Vec<u16> {
+        (0..count)
+            .map(|_| block_on(input.input_raw()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn moving_average_averages_last_values() {
+        let mut input =
+            MovingAverage::<_, 3>::new(MockAnalogInput::from_values(4096, [10, 20, 60, 100]));
+        assert_eq!(read(&mut input, 4), [10, 15, 30, 60]);
+    }
+
+    #[test]
+    fn median_ignores_spikes() {
+        let mut input =
+            MedianFilter::<_, 3>::new(MockAnalogInput::from_values(4096, [10, 4000, 12, 11, 0]));
+        assert_eq!(read(&mut input, 5), [10, 4000, 12, 12, 11]);
+    }
+
+    #[test]
+    fn exponential_smoothing_starts_at_first_value() {
+        let mut input =
+            ExponentialSmoothing::new(MockAnalogInput::from_values(4096, [100, 200, 200]), 0.5);
+        assert_eq!(read(&mut input, 3), [100, 150, 175]);
+    }
+
+    #[test]
+    fn deadband_ignores_small_changes() {
+        let mut input = Deadband::new(
+            MockAnalogInput::from_values(4096, [100, 104, 96, 110, 107]),
+            5,
+        );
+        assert_eq!(read(&mut input, 5), [100, 100, 100, 110, 110]);
+    }
+
+    #[test]
+    fn hysteresis_lags_behind_changes() {
+        let mut input = Hysteresis::new(
+            MockAnalogInput::from_values(4096, [100, 104, 110, 107, 0]),
+            5,
+        );
+        assert_eq!(read(&mut input, 5), [100, 100, 105, 105, 5]);
+    }
+
+    #[test]
+    fn oversampling_averages_latest_samples() {
+        let buffer = ScanBuffer::<CriticalSectionRawMutex, 1, 8>::new(HertzU32::Hz(100));
+        buffer.push_interleaved(&[0, 10, 20, 30, 40]);
+
+        let mut input = Oversampled::<_, 4>::new(buffer.channel(0, 4096));
+        assert_eq!(block_on(input.input_raw()), Ok(25));
+    }
+
+    #[test]
+    fn filters_stack_and_forward_properties() {
+        let mock =
+            MockAnalogInput::constant(2000, 1000).with_reference(AdcReference::new(2000, 3000));
+        let mut input = ReversedAnalogInput::new(Deadband::new(
+            MedianFilter::<_, 5>::new(MovingAverage::<_, 4>::new(mock)),
+            10,
+        ));
+
+        assert_eq!(block_on(input.max_value()), Ok(2000));
+        assert_eq!(block_on(input.input_pct()), Ok(50.0));
+        assert_eq!(block_on(input.input_mv()), Ok(1500));
+    }
+
+    #[test]
+    fn filters_forward_errors_without_losing_state() {
+        let mut input = MovingAverage::<_, 2>::new(MockAnalogInput::new(
+            4096,
+            [Ok(10), Err(PeripheralError::Adc), Ok(20)],
+        ));
+        assert_eq!(block_on(input.input_raw()), Ok(10));
+        assert_eq!(block_on(input.input_raw()), Err(PeripheralError::Adc));
+        assert_eq!(block_on(input.input_raw()), Ok(15));
+    }
+}
@@ -0,0 +1,622 @@
+//! Mock peripherals for testing modes without hardware.
+//!
+//! Inputs replay a programmed sequence of readings, one per read. Once the sequence is exhausted,
+//! its last entry is repeated. Buttons follow a timeline of presses and releases on a
+//! [`MockClock`] instead, which runs futures on simulated time. Outputs record every call made to
+//! them, and can be inspected through a clone of the mock after the original has been handed to a
+//! mode.
+
+use accelerometer::vector::F32x3;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use async_trait::async_trait;
+use core::cell::RefCell;
+use core::future::{poll_fn, Future};
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_time::Duration;
+use fugit::HertzU32;
+
+use super::{
+    AdcReference, AnalogInput, ButtonInput, Clock, DutyCycle, EnvironmentReading, PeripheralError,
+    Pwm, PwmSettings, SampledInput,
+};
+use crate::mode::acceleration::AccelerationInput;
+use crate::mode::environment::EnvironmentSensors;
+
+/// A sequence of readings that is replayed one at a time.
+struct Replay<T> {
+    /// Readings still to come.
+    readings: VecDeque<Result<T, PeripheralError>>,
+    /// Reading returned once the sequence is exhausted.
+    last: Result<T, PeripheralError>,
+}
+
+impl<T: Copy> Replay<T> {
+    /// Create a new replay. An empty sequence always fails with `error`.
+    fn new(
+        readings: impl IntoIterator<Item = Result<T, PeripheralError>>,
+        error: PeripheralError,
+    ) -> Self {
+        Self {
+            readings: readings.into_iter().collect(),
+            last: Err(error),
+        }
+    }
+
+    fn next(&mut self) -> Result<T, PeripheralError> {
+        if let Some(reading) = self.readings.pop_front() {
+            self.last = reading;
+        }
+        self.last
+    }
+}
+
+/// An analog input replaying a sequence of raw values or errors.
+pub struct MockAnalogInput {
+    readings: Replay<u16>,
+    max_value: u16,
+    reference: Option<AdcReference>,
+    sample_rate: HertzU32,
+}
+
+impl MockAnalogInput {
+    /// Create an input replaying `readings`, with raw values ranging up to `max_value`.
+    pub fn new(
+        max_value: u16,
+        readings: impl IntoIterator<Item = Result<u16, PeripheralError>>,
+    ) -> Self {
+        Self {
+            readings: Replay::new(readings, PeripheralError::Adc),
+            max_value,
+            reference: None,
+            sample_rate: HertzU32::kHz(1),
+        }
+    }
+
+    /// Relate raw values to voltages using `reference`. Without one, voltages can't be read.
+    pub fn with_reference(mut self, reference: AdcReference) -> Self {
+        self.reference = Some(reference);
+        self
+    }
+
+    /// Report being sampled at `sample_rate` rather than 1 kHz.
+    pub fn with_sample_rate(mut self, sample_rate: HertzU32) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Create an input replaying a sequence of raw values, with raw values ranging up to
+    /// `max_value`.
+    pub fn from_values(max_value: u16, values: impl IntoIterator<Item = u16>) -> Self {
+        Self::new(max_value, values.into_iter().map(Ok))
+    }
+
+    /// Create an input always returning the same raw value.
+    pub fn constant(max_value: u16, value: u16) -> Self {
+        Self::from_values(max_value, [value])
+    }
+
+    /// Create an input whose reads always fail.
+    pub fn failing(max_value: u16) -> Self {
+        Self::new(max_value, [])
+    }
+}
+
+#[async_trait]
+impl AnalogInput for MockAnalogInput {
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        self.readings.next()
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        Ok(self.max_value)
+    }
+
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        self.reference.ok_or(PeripheralError::Adc)
+    }
+}
+
+/// Blocks are filled with consecutive readings, as if sampled at the reported rate.
+#[async_trait]
+impl SampledInput for MockAnalogInput {
+    fn sample_rate(&self) -> HertzU32 {
+        self.sample_rate
+    }
+
+    async fn read_block(&mut self, samples: &mut [u16]) -> Result<(), PeripheralError> {
+        for sample in samples.iter_mut() {
+            *sample = self.readings.next()?;
+        }
+        Ok(())
+    }
+}
+
+/// A call made to a [`MockPwm`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PwmCall {
+    /// [`Pwm::set_duty_cycle_percent`] with the given percentage.
+    SetDutyCyclePercent(u8),
+    /// [`Pwm::set_frequency`] with the given frequency.
+    SetFrequency(HertzU32),
+    /// [`Pwm::enable`].
+    Enable,
+    /// [`Pwm::disable`].
+    Disable,
+}
+
+/// A PWM output recording all calls made to it.
+///
+/// Clones share the same record, so a clone kept by a test sees the calls made by a mode owning
+/// the original.
+#[derive(Clone)]
+pub struct MockPwm {
+    calls: Arc<Mutex<CriticalSectionRawMutex, RefCell<Vec<PwmCall>>>>,
+    /// Granularity of achievable frequencies, if limited.
+    frequency_step: Option<HertzU32>,
+    /// Steps per period, if the duty cycle resolution is limited.
+    duty_steps: Option<u32>,
+}
+
+impl Default for MockPwm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockPwm {
+    /// Create an output achieving any frequency exactly, with a duty cycle resolution of 1%.
+    pub fn new() -> Self {
+        Self {
+            calls: Arc::new(Mutex::new(RefCell::new(Vec::new()))),
+            frequency_step: None,
+            duty_steps: None,
+        }
+    }
+
+    /// Create an output only achieving multiples of `frequency_step`, with `duty_steps` steps per
+    /// period. Requested frequencies and duty cycles are rounded down to the next achievable value,
+    /// frequencies below `frequency_step` are unreachable.
+    pub fn quantizing(frequency_step: HertzU32, duty_steps: u32) -> Self {
+        Self {
+            frequency_step: Some(frequency_step),
+            duty_steps: Some(duty_steps),
+            ..Self::new()
+        }
+    }
+
+    /// All calls made so far, oldest first.
+    pub fn calls(&self) -> Vec<PwmCall> {
+        self.calls.lock(|calls| calls.borrow().clone())
+    }
+
+    /// Forget all calls made so far.
+    pub fn clear(&self) {
+        self.calls.lock(|calls| calls.borrow_mut().clear());
+    }
+
+    /// The most recently set duty cycle in percent, if any.
+    pub fn duty_cycle_percent(&self) -> Option<u8> {
+        self.calls().into_iter().rev().find_map(|call| match call {
+            PwmCall::SetDutyCyclePercent(percent) => Some(percent),
+            _ => None,
+        })
+    }
+
+    /// The most recently requested frequency, if any.
+    pub fn frequency(&self) -> Option<HertzU32> {
+        self.calls().into_iter().rev().find_map(|call| match call {
+            PwmCall::SetFrequency(freq) => Some(freq),
+            _ => None,
+        })
+    }
+
+    /// Whether the output has been enabled and not disabled since.
+    pub fn is_enabled(&self) -> bool {
+        self.calls()
+            .into_iter()
+            .rev()
+            .find_map(|call| match call {
+                PwmCall::Enable => Some(true),
+                PwmCall::Disable => Some(false),
+                _ => None,
+            })
+            .unwrap_or(false)
+    }
+
+    fn record(&self, call: PwmCall) {
+        self.calls.lock(|calls| calls.borrow_mut().push(call));
+    }
+
+    fn achieved_frequency(&self, freq: HertzU32) -> Result<HertzU32, PeripheralError> {
+        match self.frequency_step {
+            None => Ok(freq),
+            Some(step) if freq < step => Err(PeripheralError::Pwm),
+            Some(step) => Ok(step * (freq.to_Hz() / step.to_Hz())),
+        }
+    }
+
+    fn achieved_duty_cycle(&self, percent: u8) -> DutyCycle {
+        let steps = self.duty_steps.unwrap_or(100);
+        DutyCycle {
+            high: steps * percent as u32 / 100,
+            steps,
+        }
+    }
+}
+
+#[async_trait]
+impl Pwm for MockPwm {
+    async fn set_duty_cycle_percent(&mut self, percent: u8) -> Result<DutyCycle, PeripheralError> {
+        self.record(PwmCall::SetDutyCyclePercent(percent));
+        Ok(self.achieved_duty_cycle(percent))
+    }
+
+    async fn set_frequency(&mut self, freq: HertzU32) -> Result<HertzU32, PeripheralError> {
+        self.record(PwmCall::SetFrequency(freq));
+        self.achieved_frequency(freq)
+    }
+
+    async fn enable(&mut self) -> Result<(), PeripheralError> {
+        self.record(PwmCall::Enable);
+        Ok(())
+    }
+
+    async fn disable(&mut self) -> Result<(), PeripheralError> {
+        self.record(PwmCall::Disable);
+        Ok(())
+    }
+
+    async fn settings(&mut self) -> Result<PwmSettings, PeripheralError> {
+        Ok(PwmSettings {
+            frequency: self
+                .frequency()
+                .and_then(|freq| self.achieved_frequency(freq).ok()),
+            duty_cycle: self.achieved_duty_cycle(self.duty_cycle_percent().unwrap_or(0)),
+            enabled: self.is_enabled(),
+        })
+    }
+}
+
+/// An accelerometer replaying a sequence of normalized accelerations or errors.
+pub struct MockAccelerometer {
+    readings: Replay<F32x3>,
+}
+
+impl MockAccelerometer {
+    /// Create an accelerometer replaying `readings`.
+    pub fn new(readings: impl IntoIterator<Item = Result<F32x3, PeripheralError>>) -> Self {
+        Self {
+            readings: Replay::new(readings, PeripheralError::I2c),
+        }
+    }
+
+    /// Create an accelerometer always returning the same acceleration.
+    pub fn constant(acceleration: F32x3) -> Self {
+        Self::new([Ok(acceleration)])
+    }
+
+    /// Create an accelerometer whose reads always fail.
+    pub fn failing() -> Self {
+        Self::new([])
+    }
+}
+
+#[async_trait]
+impl AccelerationInput for MockAccelerometer {
+    async fn accel_norm(&mut self) -> Result<F32x3, PeripheralError> {
+        self.readings.next()
+    }
+}
+
+/// Environment sensors replaying separate sequences of temperature, humidity and pressure readings.
+pub struct MockEnvironmentSensors {
+    temperature: Replay<f32>,
+    humidity: Replay<f32>,
+    pressure: Replay<f32>,
+}
+
+impl MockEnvironmentSensors {
+    /// Create sensors replaying temperatures in °C, humidities in % and pressures in kPa.
+    pub fn new(
+        temperature: impl IntoIterator<Item = Result<f32, PeripheralError>>,
+        humidity: impl IntoIterator<Item = Result<f32, PeripheralError>>,
+        pressure: impl IntoIterator<Item = Result<f32, PeripheralError>>,
+    ) -> Self {
+        Self {
+            temperature: Replay::new(temperature, PeripheralError::I2c),
+            humidity: Replay::new(humidity, PeripheralError::I2c),
+            pressure: Replay::new(pressure, PeripheralError::I2c),
+        }
+    }
+
+    /// Create sensors always returning the same readings.
+    pub fn constant(temperature_c: f32, humidity_pct: f32, pressure_kpa: f32) -> Self {
+        Self::new([Ok(temperature_c)], [Ok(humidity_pct)], [Ok(pressure_kpa)])
+    }
+
+    /// Create sensors whose reads always fail.
+    pub fn failing() -> Self {
+        Self::new([], [], [])
+    }
+}
+
+#[async_trait]
+impl EnvironmentSensors for MockEnvironmentSensors {
+    async fn read(&mut self) -> EnvironmentReading {
+        EnvironmentReading {
+            temperature_c: self.temperature.next(),
+            humidity_pct: self.humidity.next(),
+            pressure_kpa: self.pressure.next(),
+        }
+    }
+}
+
+/// Simulated time of a [`MockClock`].
+#[derive(Debug, Default)]
+struct SimulatedTime {
+    /// Time since the start of the simulation.
+    now: Duration,
+    /// Earliest time a pending wait ends at, if any wait is pending.
+    next_wake: Option<Duration>,
+}
+
+/// A clock running futures on simulated time.
+///
+/// The clock starts at 0, and [`Self::run`] jumps ahead to the end of the earliest pending wait
+/// whenever the future can't make progress otherwise. Timings are thus exact and take no time at
+/// all. Clones share their time.
+#[derive(Clone)]
+pub struct MockClock {
+    time: Arc<Mutex<CriticalSectionRawMutex, RefCell<SimulatedTime>>>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockClock {
+    /// Create a clock at time 0.
+    pub fn new() -> Self {
+        Self {
+            time: Arc::new(Mutex::new(RefCell::new(SimulatedTime::default()))),
+        }
+    }
+
+    /// Time since the start of the simulation.
+    pub fn now(&self) -> Duration {
+        self.time.lock(|time| time.borrow().now)
+    }
+
+    /// Run `future` to completion, advancing the time whenever it waits.
+    ///
+    /// # Panics
+    ///
+    /// If the future waits for something other than the clock, as it would never complete.
+    pub fn run<F: Future>(&self, future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+            self.time.lock(|time| {
+                let mut time = time.borrow_mut();
+                let wake = time
+                    .next_wake
+                    .take()
+                    .expect("future waits for something other than the clock");
+                time.now = time.now.max(wake);
+            });
+        }
+    }
+
+    /// Wait until the clock reaches `at`.
+    async fn wait_until(&self, at: Duration) {
+        poll_fn(|_| {
+            self.time.lock(|time| {
+                let mut time = time.borrow_mut();
+                if time.now >= at {
+                    return Poll::Ready(());
+                }
+                time.next_wake = Some(time.next_wake.map_or(at, |wake| wake.min(at)));
+                Poll::Pending
+            })
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl Clock for MockClock {
+    async fn delay(&mut self, duration: Duration) {
+        let at = self.now() + duration;
+        self.wait_until(at).await;
+    }
+}
+
+/// A button following a timeline of presses and releases on a [`MockClock`].
+pub struct MockButton {
+    clock: MockClock,
+    /// Times at which the button changes its state, alternating between pressed and released.
+    changes: Vec<Duration>,
+}
+
+impl MockButton {
+    /// Create a released button changing its state at the given times in ms on `clock`,
+    /// alternating between pressed and released.
+    pub fn new(clock: &MockClock, changes_ms: impl IntoIterator<Item = u64>) -> Self {
+        Self {
+            clock: clock.clone(),
+            changes: changes_ms.into_iter().map(Duration::from_millis).collect(),
+        }
+    }
+
+    /// Time at which the button is next `pressed`, if it ever is. That's the current time if it is
+    /// `pressed` already.
+    fn next_time(&self, pressed: bool) -> Option<Duration> {
+        let now = self.clock.now();
+        let changes = self.changes.iter().take_while(|&&at| at <= now).count();
+        // The button is pressed after an odd number of changes
+        if (changes % 2 == 1) == pressed {
+            return Some(now);
+        }
+        self.changes.get(changes).copied()
+    }
+
+    /// Wait until the button is `pressed`.
+    async fn wait_for(&self, pressed: bool) {
+        match self.next_time(pressed) {
+            Some(at) => self.clock.wait_until(at).await,
+            None => core::future::pending().await,
+        }
+    }
+}
+
+#[async_trait]
+impl ButtonInput for MockButton {
+    async fn wait_for_press(&mut self) -> Result<(), PeripheralError> {
+        self.wait_for(true).await;
+        Ok(())
+    }
+
+    async fn wait_for_release(&mut self) -> Result<(), PeripheralError> {
+        self.wait_for(false).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::block_on;
+    use embassy_futures::select::{select, Either};
+
+    use super::*;
+
+    #[test]
+    fn analog_input_replays_readings() {
+        let mut input = MockAnalogInput::new(100, [Ok(10), Err(PeripheralError::Adc), Ok(50)]);
+
+        assert_eq!(block_on(input.input_raw()), Ok(10));
+        assert_eq!(block_on(input.input_raw()), Err(PeripheralError::Adc));
+        assert_eq!(block_on(input.input_pct()), Ok(50.0));
+        // The last reading is repeated
+        assert_eq!(block_on(input.input_raw()), Ok(50));
+    }
+
+    #[test]
+    fn empty_analog_input_fails() {
+        let mut input = MockAnalogInput::failing(100);
+        assert_eq!(block_on(input.input_raw()), Err(PeripheralError::Adc));
+    }
+
+    #[test]
+    fn clock_advances_to_earliest_wait() {
+        let clock = MockClock::new();
+        let mut button = MockButton::new(&clock, [10, 50]);
+        let ms = Duration::from_millis;
+
+        let mut timer = clock.clone();
+        let raced = clock.run(select(button.wait_for_press(), timer.delay(ms(5))));
+        assert!(matches!(raced, Either::Second(())));
+        assert_eq!(clock.now(), ms(5));
+        clock.run(button.wait_for_press()).unwrap();
+        assert_eq!(clock.now(), ms(10));
+        clock.run(clock.clone().delay(ms(20)));
+        clock.run(button.wait_for_release()).unwrap();
+        assert_eq!(clock.now(), ms(50));
+        // Released already
+        clock.run(button.wait_for_release()).unwrap();
+        assert_eq!(clock.now(), ms(50));
+    }
+
+    #[test]
+    #[should_panic(expected = "something other than the clock")]
+    fn clock_refuses_waiting_forever() {
+        let clock = MockClock::new();
+        let mut button = MockButton::new(&clock, []);
+        clock.run(button.wait_for_press()).unwrap();
+    }
+
+    #[test]
+    fn pwm_clones_share_record() {
+        let pwm = MockPwm::new();
+        let mut handle = pwm.clone();
+
+        assert_eq!(
+            block_on(handle.set_frequency(HertzU32::Hz(100))),
+            Ok(HertzU32::Hz(100))
+        );
+        block_on(handle.enable()).unwrap();
+        assert_eq!(
+            block_on(handle.set_duty_cycle_percent(20)),
+            Ok(DutyCycle {
+                high: 20,
+                steps: 100
+            })
+        );
+
+        assert_eq!(
+            pwm.calls(),
+            [
+                PwmCall::SetFrequency(HertzU32::Hz(100)),
+                PwmCall::Enable,
+                PwmCall::SetDutyCyclePercent(20)
+            ]
+        );
+        assert_eq!(pwm.frequency(), Some(HertzU32::Hz(100)));
+        assert_eq!(pwm.duty_cycle_percent(), Some(20));
+        assert!(pwm.is_enabled());
+
+        block_on(handle.disable()).unwrap();
+        assert!(!pwm.is_enabled());
+
+        pwm.clear();
+        assert!(handle.calls().is_empty());
+    }
+
+    #[test]
+    fn quantizing_pwm_rounds_down() {
+        let mut pwm = MockPwm::quantizing(HertzU32::Hz(30), 8);
+
+        assert_eq!(
+            block_on(pwm.set_frequency(HertzU32::Hz(100))),
+            Ok(HertzU32::Hz(90))
+        );
+        assert_eq!(
+            block_on(pwm.set_frequency(HertzU32::Hz(20))),
+            Err(PeripheralError::Pwm)
+        );
+        // Requests are recorded either way
+        assert_eq!(pwm.frequency(), Some(HertzU32::Hz(20)));
+
+        let duty_cycle = block_on(pwm.set_duty_cycle_percent(30)).unwrap();
+        assert_eq!(duty_cycle, DutyCycle { high: 2, steps: 8 });
+        assert_eq!(duty_cycle.percent(), 25.0);
+        assert_eq!(duty_cycle.resolution_pct(), 12.5);
+    }
+
+    #[test]
+    fn pwm_settings_reflect_achieved_values() {
+        let mut pwm = MockPwm::quantizing(HertzU32::Hz(30), 8);
+        block_on(pwm.set_frequency(HertzU32::Hz(100))).unwrap();
+        block_on(pwm.set_duty_cycle_percent(50)).unwrap();
+        block_on(pwm.enable()).unwrap();
+
+        assert_eq!(
+            block_on(pwm.settings()),
+            Ok(PwmSettings {
+                frequency: Some(HertzU32::Hz(90)),
+                duty_cycle: DutyCycle { high: 4, steps: 8 },
+                enabled: true,
+            })
+        );
+    }
+}
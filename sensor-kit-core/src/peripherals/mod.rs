@@ -0,0 +1,62 @@
+/// Accelerometer
+mod accelerometer;
+/// Analog Input.
+mod adc;
+/// Buttons.
+mod button;
+/// Calibration of analog inputs.
+mod calibration;
+/// DHT20 temperature and humidity sensor.
+mod dht20;
+/// Environment sensors.
+mod environment;
+/// Filters for analog inputs.
+mod filter;
+/// Mock peripherals for tests.
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+/// Photoresistor model.
+mod photoresistor;
+/// PWM.
+mod pwm;
+/// Background sampling of sensors.
+mod sampling;
+/// Continuously sampled analog inputs.
+mod scan;
+
+pub use adc::{AdcReference, AnalogInput, ReversedAnalogInput};
+pub use button::{ButtonDriver, ButtonEvent, ButtonInput, ButtonTimings, Clock, EmbassyClock};
+pub use calibration::{CalibratedAnalogInput, Calibration, CalibrationError};
+pub use dht20::{Dht20, Dht20Reading};
+pub use environment::{AsyncI2c, SensorKitEnvSensors};
+pub use filter::{
+    Deadband, ExponentialSmoothing, Hysteresis, MedianFilter, MovingAverage, Oversampled,
+};
+pub use photoresistor::Photoresistor;
+pub use pwm::{DutyCycle, Pwm, PwmSettings};
+pub use sampling::{
+    record, sample_acceleration, sample_analog, sample_environment, sample_sound_level,
+    AccelerationReading, AnalogReading, EnvironmentReading, LatestAnalogReading, LatestReading,
+    Reading, ReadingChannel, SoundLevelReading,
+};
+pub use scan::{SampledInput, ScanBuffer, ScanChannel};
+
+use thiserror::Error;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+/// Error type used by peripheral structs.
+pub enum PeripheralError {
+    #[error("Error during I2C transaction")]
+    /// An I2C error.
+    I2c,
+    #[error("Error during ADC operation")]
+    /// An ADC error.
+    Adc,
+    #[error("Error during PWM operation")]
+    /// A PWM error.
+    Pwm,
+    #[error("Error during GPIO operation")]
+    /// A GPIO error.
+    Gpio,
+}
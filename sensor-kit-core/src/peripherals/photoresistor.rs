@@ -0,0 +1,161 @@
+use alloc::vec::Vec;
+#[cfg_attr(not(target_os = "none"), allow(unused_imports))]
+use micromath::F32Ext;
+
+use super::{Calibration, CalibrationError};
+
+/// Model of a photoresistor (LDR) in a voltage divider, converting readings into illuminance.
+///
+/// The LDR sits between the supply and the ADC input, with a fixed resistor from the input to
+/// ground, as on the Grove light sensor. The output voltage thus rises with the illuminance. The
+/// resistance of the LDR follows `R = R10 * (E / 10 lx)^-gamma`, a straight line on a log-log scale
+/// as given in LDR datasheets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Photoresistor {
+    /// Resistance of the fixed resistor in Ω.
+    pub fixed_resistance: f32,
+    /// Resistance of the LDR at an illuminance of 10 lx in Ω.
+    pub resistance_10_lux: f32,
+    /// Slope of the LDR's resistance over illuminance on a log-log scale.
+    pub gamma: f32,
+}
+
+impl Photoresistor {
+    /// Create a new model from the resistor values and the LDR's gamma.
+    pub const fn new(fixed_resistance: f32, resistance_10_lux: f32, gamma: f32) -> Self {
+        Self {
+            fixed_resistance,
+            resistance_10_lux,
+            gamma,
+        }
+    }
+
+    /// Resistance of the LDR in Ω, given the output voltage as a fraction of the supply voltage.
+    /// Fails at either end of the range, where the LDR has no finite, non-zero resistance.
+    pub fn resistance(&self, ratio: f32) -> Option<f32> {
+        if ratio <= 0.0 || ratio >= 1.0 {
+            return None;
+        }
+        Some(self.fixed_resistance * (1.0 - ratio) / ratio)
+    }
+
+    /// Approximate illuminance in lux, given the output voltage as a fraction of the supply voltage.
+    /// Fails at either end of the range, where the illuminance is beyond what the divider can tell.
+    pub fn lux(&self, ratio: f32) -> Option<f32> {
+        let resistance = self.resistance(ratio)?;
+        Some(10.0 * (self.resistance_10_lux / resistance).powf(1.0 / self.gamma))
+    }
+
+    /// Output voltage as a fraction of the supply voltage at an illuminance of `lux`.
+    pub fn ratio(&self, lux: f32) -> f32 {
+        let resistance = self.resistance_10_lux * (lux / 10.0).powf(-self.gamma);
+        self.fixed_resistance / (self.fixed_resistance + resistance)
+    }
+
+    /// Calibration of an ADC with a maximum value of `max_value` reading the divider, mapping raw
+    /// values to lux. Its points are spaced evenly on a logarithmic scale, following the steep ends
+    /// of the curve closely. The outermost points sit one step off the rails, where the divider
+    /// can't tell the illuminance anymore.
+    pub fn calibration(&self, max_value: u16) -> Result<Calibration, CalibrationError> {
+        /// Calibration points per decade of illuminance.
+        const POINTS_PER_DECADE: f32 = 8.0;
+
+        let max = max_value as f32;
+        let lowest = 1;
+        let highest = max_value.saturating_sub(1);
+        let (Some(lux_min), Some(lux_max)) = (
+            self.lux(lowest as f32 / max),
+            self.lux(highest as f32 / max),
+        ) else {
+            return Err(CalibrationError::TooFewPoints);
+        };
+
+        let first = (lux_min.log10() * POINTS_PER_DECADE).ceil() as i32;
+        let last = (lux_max.log10() * POINTS_PER_DECADE).floor() as i32;
+        let mut raws: Vec<u16> = (first..=last)
+            .map(|step| 10.0f32.powf(step as f32 / POINTS_PER_DECADE))
+            .map(|lux| (self.ratio(lux) * max).round() as u16)
+            .filter(|raw| (lowest..=highest).contains(raw))
+            .collect();
+        raws.push(lowest);
+        raws.push(highest);
+        raws.sort_unstable();
+        raws.dedup();
+
+        // Each point lies on the curve at its rounded raw value
+        let points = raws
+            .into_iter()
+            .filter_map(|raw| Some((raw, self.lux(raw as f32 / max)?)));
+        Calibration::table(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assert that `actual` is within 2% of `expected`, as `powf` is only approximated.
+    fn assert_close(actual: Option<f32>, expected: f32) {
+        let actual = actual.unwrap();
+        assert!(
+            (actual - expected).abs() <= expected * 0.02,
+            "{actual} is not close to {expected}"
+        );
+    }
+
+    #[test]
+    fn divider_yields_ldr_resistance() {
+        let ldr = Photoresistor::new(10_000.0, 10_000.0, 1.0);
+        assert_close(ldr.resistance(0.5), 10_000.0);
+        assert_close(ldr.resistance(0.8), 2_500.0);
+        assert_eq!(ldr.resistance(0.0), None);
+        assert_eq!(ldr.resistance(1.0), None);
+    }
+
+    #[test]
+    fn lux_follows_gamma_curve() {
+        let ldr = Photoresistor::new(10_000.0, 10_000.0, 0.5);
+        // At half the supply voltage, the LDR has its resistance at 10 lx
+        assert_close(ldr.lux(0.5), 10.0);
+        // A quarter of the resistance at gamma 0.5 means 16 times the illuminance
+        assert_close(ldr.lux(0.8), 160.0);
+        assert_eq!(ldr.lux(1.0), None);
+    }
+
+    #[test]
+    fn lux_rises_with_output_voltage() {
+        let ldr = Photoresistor::new(10_000.0, 20_000.0, 0.7);
+        let readings = [0.05, 0.2, 0.5, 0.8, 0.95].map(|ratio| ldr.lux(ratio).unwrap());
+        assert!(readings.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn ratio_inverts_lux() {
+        let ldr = Photoresistor::new(10_000.0, 20_000.0, 0.7);
+        for ratio in [0.05, 0.5, 0.95] {
+            let lux = ldr.lux(ratio).unwrap();
+            assert!((ldr.ratio(lux) - ratio).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn calibration_follows_curve() {
+        let ldr = Photoresistor::new(10_000.0, 10_000.0, 0.5);
+        let calibration = ldr.calibration(4096).unwrap();
+
+        assert_close(Some(calibration.apply(2048)), 10.0);
+        assert_close(Some(calibration.apply(3277)), 160.0);
+        // Readings on the rails are clamped to the outermost points
+        assert_close(Some(calibration.apply(0)), ldr.lux(1.0 / 4096.0).unwrap());
+        assert_close(
+            Some(calibration.apply(4096)),
+            ldr.lux(4095.0 / 4096.0).unwrap(),
+        );
+    }
+
+    #[test]
+    fn calibration_needs_resolution() {
+        let ldr = Photoresistor::new(10_000.0, 10_000.0, 0.5);
+        assert_eq!(ldr.calibration(1), Err(CalibrationError::TooFewPoints));
+    }
+}
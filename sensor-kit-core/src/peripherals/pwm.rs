@@ -0,0 +1,130 @@
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use fugit::HertzU32;
+
+use crate::mode::{buzzer::BuzzerOutput, led::LedOutput};
+
+use super::PeripheralError;
+
+/// Duty cycle as applied by the hardware, in steps of the PWM counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DutyCycle {
+    /// Steps per period during which the output is high.
+    pub high: u32,
+    /// Steps per period. The duty cycle can only be adjusted in units of a single step.
+    pub steps: u32,
+}
+
+impl DutyCycle {
+    /// Duty cycle in percent.
+    pub fn percent(&self) -> f32 {
+        self.high as f32 / self.steps as f32 * 100.0
+    }
+
+    /// Smallest possible adjustment of the duty cycle in percent.
+    pub fn resolution_pct(&self) -> f32 {
+        100.0 / self.steps as f32
+    }
+}
+
+/// Current settings of a PWM output, as applied by the hardware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PwmSettings {
+    /// Output frequency, if one has been set.
+    pub frequency: Option<HertzU32>,
+    /// Duty cycle the output runs at while enabled. It is kept while the output is disabled, so
+    /// the output resumes at it once enabled again.
+    pub duty_cycle: DutyCycle,
+    /// Whether the output is enabled.
+    pub enabled: bool,
+}
+
+#[async_trait]
+/// A PWM with additional capabilities compared to embedded_hal's `SetDutyCycle`.
+pub trait Pwm {
+    /// Set duty cycle in percent. Returns the duty cycle actually applied, which is rounded to the
+    /// resolution available at the current frequency.
+    async fn set_duty_cycle_percent(&mut self, percent: u8) -> Result<DutyCycle, PeripheralError>;
+
+    /// Set PWM frequency. Returns the frequency actually achieved, which may deviate from `freq`
+    /// depending on the clock dividers available to the hardware.
+    async fn set_frequency(&mut self, freq: HertzU32) -> Result<HertzU32, PeripheralError>;
+
+    /// Enable PWM output, at the duty cycle set last.
+    async fn enable(&mut self) -> Result<(), PeripheralError>;
+
+    /// Disable PWM output. The duty cycle is kept for when the output is enabled again.
+    async fn disable(&mut self) -> Result<(), PeripheralError>;
+
+    /// Current settings of the output.
+    async fn settings(&mut self) -> Result<PwmSettings, PeripheralError>;
+}
+
+#[async_trait]
+impl<P> Pwm for Box<P>
+where
+    P: Pwm + Send + ?Sized,
+{
+    async fn set_duty_cycle_percent(&mut self, percent: u8) -> Result<DutyCycle, PeripheralError> {
+        (**self).set_duty_cycle_percent(percent).await
+    }
+
+    async fn set_frequency(&mut self, freq: HertzU32) -> Result<HertzU32, PeripheralError> {
+        (**self).set_frequency(freq).await
+    }
+
+    async fn enable(&mut self) -> Result<(), PeripheralError> {
+        (**self).enable().await
+    }
+
+    async fn disable(&mut self) -> Result<(), PeripheralError> {
+        (**self).disable().await
+    }
+
+    async fn settings(&mut self) -> Result<PwmSettings, PeripheralError> {
+        (**self).settings().await
+    }
+}
+
+#[async_trait]
+impl<P> LedOutput for P
+where
+    P: Pwm + Send,
+{
+    async fn set_brightness(&mut self, brightness_pct: f32) -> Result<f32, PeripheralError> {
+        let duty_cycle = self.set_duty_cycle_percent(brightness_pct as u8).await?;
+        Ok(duty_cycle.percent())
+    }
+
+    async fn enable(&mut self) -> Result<(), PeripheralError> {
+        self.set_frequency(HertzU32::Hz(10000)).await?;
+        self.enable().await?;
+        Ok(())
+    }
+
+    async fn disable(&mut self) -> Result<(), PeripheralError> {
+        self.disable().await
+    }
+}
+
+#[async_trait]
+impl<P> BuzzerOutput for P
+where
+    P: Pwm + Send,
+{
+    async fn enable(&mut self) -> Result<(), PeripheralError> {
+        self.set_duty_cycle_percent(50).await?;
+        self.enable().await?;
+        Ok(())
+    }
+
+    async fn disable(&mut self) -> Result<(), PeripheralError> {
+        self.disable().await
+    }
+
+    async fn set_frequency(&mut self, freq: fugit::HertzU32) -> Result<HertzU32, PeripheralError> {
+        let hertz = freq.to_Hz();
+        let freq = HertzU32::Hz(hertz);
+        self.set_frequency(freq).await
+    }
+}
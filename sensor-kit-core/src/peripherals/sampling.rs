@@ -0,0 +1,426 @@
+use accelerometer::vector::F32x3;
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::pubsub::{self, PubSubChannel, Subscriber};
+use embassy_time::{Duration, Instant, Ticker};
+
+use super::{AnalogInput, PeripheralError};
+use crate::mode::acceleration::AccelerationInput;
+use crate::mode::environment::EnvironmentSensors;
+use crate::mode::sound::{SoundLevelError, SoundLevelInput};
+
+/// A reading of a sensor, along with the time it was taken.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading<T> {
+    /// Time the reading was taken.
+    pub timestamp: Instant,
+    /// Value read.
+    pub value: T,
+}
+
+impl<T> Reading<T> {
+    /// Create a reading taken just now.
+    pub fn now(value: T) -> Self {
+        Self {
+            timestamp: Instant::now(),
+            value,
+        }
+    }
+}
+
+/// A reading of all environment sensors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnvironmentReading {
+    /// Temperature in °C.
+    pub temperature_c: Result<f32, PeripheralError>,
+    /// Humidity in %.
+    pub humidity_pct: Result<f32, PeripheralError>,
+    /// Pressure in kPa.
+    pub pressure_kpa: Result<f32, PeripheralError>,
+}
+
+/// A reading of an accelerometer, in multiples of g.
+pub type AccelerationReading = Result<F32x3, PeripheralError>;
+
+/// A reading of a sound level meter, in dB SPL.
+pub type SoundLevelReading = Result<f32, SoundLevelError>;
+
+/// A raw reading of an analog input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnalogReading {
+    /// Raw input value.
+    pub raw: u16,
+    /// Maximum value the input can provide.
+    pub max_value: u16,
+}
+
+impl AnalogReading {
+    /// Read an analog input.
+    pub async fn read(input: &mut (impl AnalogInput + ?Sized)) -> Result<Self, PeripheralError> {
+        Ok(Self {
+            raw: input.input_raw().await?,
+            max_value: input.max_value().await?,
+        })
+    }
+
+    /// Value relative to the input's range, in %.
+    pub fn pct(&self) -> f32 {
+        self.raw as f32 / self.max_value as f32 * 100.0
+    }
+}
+
+/// Channel readings of a sensor are published on. Subscribers that fall behind miss the oldest
+/// readings.
+pub type ReadingChannel<M, T, const CAP: usize, const SUBS: usize> =
+    PubSubChannel<M, Reading<T>, CAP, SUBS, 1>;
+
+/// Publish a timestamped reading on `channel`, dropping the oldest one if it is full.
+fn publish<M, T, const CAP: usize, const SUBS: usize>(
+    channel: &ReadingChannel<M, T, CAP, SUBS>,
+    value: T,
+) where
+    M: RawMutex,
+    T: Clone,
+{
+    channel
+        .immediate_publisher()
+        .publish_immediate(Reading::now(value));
+}
+
+/// Keep reading the environment sensors every `interval`, publishing the readings on `channel`.
+pub async fn sample_environment<M, const CAP: usize, const SUBS: usize>(
+    sensors: &mut (impl EnvironmentSensors + ?Sized),
+    channel: &ReadingChannel<M, EnvironmentReading, CAP, SUBS>,
+    interval: Duration,
+) where
+    M: RawMutex,
+{
+    let mut ticker = Ticker::every(interval);
+    loop {
+        publish(channel, sensors.read().await);
+        ticker.next().await;
+    }
+}
+
+/// Keep reading an accelerometer every `interval`, publishing the readings on `channel`.
+pub async fn sample_acceleration<M, const CAP: usize, const SUBS: usize>(
+    accelerometer: &mut (impl AccelerationInput + ?Sized),
+    channel: &ReadingChannel<M, AccelerationReading, CAP, SUBS>,
+    interval: Duration,
+) where
+    M: RawMutex,
+{
+    let mut ticker = Ticker::every(interval);
+    loop {
+        publish(channel, accelerometer.accel_norm().await);
+        ticker.next().await;
+    }
+}
+
+/// Keep measuring the sound level every `interval`, publishing the readings on `channel`.
+pub async fn sample_sound_level<M, const CAP: usize, const SUBS: usize>(
+    meter: &mut (impl SoundLevelInput + ?Sized),
+    channel: &ReadingChannel<M, SoundLevelReading, CAP, SUBS>,
+    interval: Duration,
+) where
+    M: RawMutex,
+{
+    let mut ticker = Ticker::every(interval);
+    loop {
+        publish(channel, meter.level_db().await);
+        ticker.next().await;
+    }
+}
+
+/// Keep reading an analog input every `interval`, publishing the readings on `channel`.
+pub async fn sample_analog<M, const CAP: usize, const SUBS: usize>(
+    input: &mut (impl AnalogInput + ?Sized),
+    channel: &ReadingChannel<M, Result<AnalogReading, PeripheralError>, CAP, SUBS>,
+    interval: Duration,
+) where
+    M: RawMutex,
+{
+    let mut ticker = Ticker::every(interval);
+    loop {
+        publish(channel, AnalogReading::read(input).await);
+        ticker.next().await;
+    }
+}
+
+/// Keep handing the readings arriving at `subscriber` to `record`, e.g. to record them into a
+/// [`crate::trend::Trend`]. Readings are missed if `record` falls behind the channel.
+pub async fn record<M, T, const CAP: usize, const SUBS: usize>(
+    mut subscriber: Subscriber<'_, M, Reading<T>, CAP, SUBS, 1>,
+    mut record: impl FnMut(Reading<T>),
+) where
+    M: RawMutex,
+    T: Clone,
+{
+    loop {
+        record(subscriber.next_message_pure().await);
+    }
+}
+
+/// Subscription to a [`ReadingChannel`], keeping the latest reading.
+///
+/// Stands in for the sensor itself: subscriptions to environment, acceleration and sound level
+/// readings implement the matching peripheral interface, returning the latest reading. Only the
+/// very first read waits for a reading to arrive. Analog readings are subscribed to through
+/// [`LatestAnalogReading`].
+pub struct LatestReading<'a, M, T, const CAP: usize, const SUBS: usize>
+where
+    M: RawMutex,
+    T: Clone,
+{
+    subscriber: Subscriber<'a, M, Reading<T>, CAP, SUBS, 1>,
+    latest: Option<Reading<T>>,
+}
+
+impl<'a, M, T, const CAP: usize, const SUBS: usize> LatestReading<'a, M, T, CAP, SUBS>
+where
+    M: RawMutex,
+    T: Clone,
+{
+    /// Subscribe to `channel`. Fails if it has no subscriber slots left.
+    pub fn new(channel: &'a ReadingChannel<M, T, CAP, SUBS>) -> Result<Self, pubsub::Error> {
+        Ok(Self {
+            subscriber: channel.subscriber()?,
+            latest: None,
+        })
+    }
+
+    /// Latest reading published so far, if any.
+    pub fn latest(&mut self) -> Option<Reading<T>> {
+        while let Some(reading) = self.subscriber.try_next_message_pure() {
+            self.latest = Some(reading);
+        }
+        self.latest.clone()
+    }
+
+    /// Latest reading, waiting for the first one if none has been published yet.
+    pub async fn wait_latest(&mut self) -> Reading<T> {
+        match self.latest() {
+            Some(reading) => reading,
+            None => {
+                let reading = self.subscriber.next_message_pure().await;
+                self.latest = Some(reading.clone());
+                reading
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<M, const CAP: usize, const SUBS: usize> EnvironmentSensors
+    for LatestReading<'_, M, EnvironmentReading, CAP, SUBS>
+where
+    M: RawMutex + Send + Sync,
+{
+    async fn read(&mut self) -> EnvironmentReading {
+        self.wait_latest().await.value
+    }
+}
+
+#[async_trait]
+impl<M, const CAP: usize, const SUBS: usize> AccelerationInput
+    for LatestReading<'_, M, AccelerationReading, CAP, SUBS>
+where
+    M: RawMutex + Send + Sync,
+{
+    async fn accel_norm(&mut self) -> Result<F32x3, PeripheralError> {
+        self.wait_latest().await.value
+    }
+}
+
+#[async_trait]
+impl<M, const CAP: usize, const SUBS: usize> SoundLevelInput
+    for LatestReading<'_, M, SoundLevelReading, CAP, SUBS>
+where
+    M: RawMutex + Send + Sync,
+{
+    async fn level_db(&mut self) -> Result<f32, SoundLevelError> {
+        self.wait_latest().await.value
+    }
+}
+
+/// Subscription to the readings of an analog input, keeping the latest one like a
+/// [`LatestReading`]. Stands in for the input itself, whose range is known before the first
+/// reading arrives.
+pub struct LatestAnalogReading<'a, M, const CAP: usize, const SUBS: usize>
+where
+    M: RawMutex,
+{
+    latest: LatestReading<'a, M, Result<AnalogReading, PeripheralError>, CAP, SUBS>,
+    /// Maximum value of the input.
+    max_value: u16,
+}
+
+impl<'a, M, const CAP: usize, const SUBS: usize> LatestAnalogReading<'a, M, CAP, SUBS>
+where
+    M: RawMutex,
+{
+    /// Subscribe to `channel`, on which the readings of an input with values up to `max_value` are
+    /// published. Fails if it has no subscriber slots left.
+    pub fn new(
+        channel: &'a ReadingChannel<M, Result<AnalogReading, PeripheralError>, CAP, SUBS>,
+        max_value: u16,
+    ) -> Result<Self, pubsub::Error> {
+        Ok(Self {
+            latest: LatestReading::new(channel)?,
+            max_value,
+        })
+    }
+}
+
+#[async_trait]
+impl<M, const CAP: usize, const SUBS: usize> AnalogInput for LatestAnalogReading<'_, M, CAP, SUBS>
+where
+    M: RawMutex + Send + Sync,
+{
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        self.latest
+            .wait_latest()
+            .await
+            .value
+            .map(|reading| reading.raw)
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        Ok(self.max_value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::future::ready;
+    use embassy_futures::block_on;
+    use embassy_futures::select::{select, Either};
+    use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+
+    use super::*;
+    use crate::peripherals::mock::{MockAccelerometer, MockAnalogInput, MockEnvironmentSensors};
+    use crate::trend::Trend;
+
+    type Channel<T> = ReadingChannel<CriticalSectionRawMutex, T, 4, 2>;
+
+    #[test]
+    fn latest_reading_skips_to_newest() {
+        let channel = Channel::<u16>::new();
+        let mut latest = LatestReading::new(&channel).unwrap();
+        assert_eq!(latest.latest(), None);
+
+        // More readings than the channel holds
+        for value in 0..10 {
+            publish(&channel, value);
+        }
+        assert_eq!(latest.latest().map(|reading| reading.value), Some(9));
+        // The latest reading is kept once the channel is drained
+        assert_eq!(latest.latest().map(|reading| reading.value), Some(9));
+    }
+
+    #[test]
+    fn subscribers_are_limited() {
+        let channel = Channel::<u16>::new();
+        let _first = LatestReading::new(&channel).unwrap();
+        let _second = LatestReading::new(&channel).unwrap();
+        assert!(LatestReading::new(&channel).is_err());
+    }
+
+    #[test]
+    fn environment_readings_are_published() {
+        let channel = Channel::<EnvironmentReading>::new();
+        let mut sensors = LatestReading::new(&channel).unwrap();
+        assert_eq!(sensors.latest(), None);
+
+        let mut mock =
+            MockEnvironmentSensors::new([Ok(21.5)], [Err(PeripheralError::I2c)], [Ok(101.3)]);
+        let sampling = sample_environment(&mut mock, &channel, Duration::from_secs(1));
+        // Sampling never ends, but publishes its first reading right away
+        let result = block_on(select(sampling, sensors.read()));
+        let Either::Second(reading) = result else {
+            panic!("Sampling ended");
+        };
+
+        assert_eq!(reading.temperature_c, Ok(21.5));
+        assert_eq!(reading.humidity_pct, Err(PeripheralError::I2c));
+        assert_eq!(reading.pressure_kpa, Ok(101.3));
+    }
+
+    #[test]
+    fn readings_are_recorded_with_their_timestamp() {
+        let channel = Channel::<u16>::new();
+        let trend = Trend::new(Duration::from_secs(60));
+        let recording = record(channel.subscriber().unwrap(), |reading| {
+            trend.push(reading.timestamp, reading.value as f32)
+        });
+
+        let reading = Reading {
+            timestamp: Instant::from_secs(1),
+            value: 7,
+        };
+        channel.immediate_publisher().publish_immediate(reading);
+        // Recording never ends, but handles the reading as soon as it is polled
+        let result = block_on(select(recording, ready(())));
+        assert!(matches!(result, Either::Second(())));
+
+        assert_eq!(trend.latest(), Some((reading.timestamp, 7.0)));
+    }
+
+    #[test]
+    fn acceleration_waits_for_first_reading() {
+        let channel = Channel::<AccelerationReading>::new();
+        let mut accelerometer = LatestReading::new(&channel).unwrap();
+        let mut mock = MockAccelerometer::constant(F32x3::new(0.0, 0.0, 1.0));
+
+        let sampling = sample_acceleration(&mut mock, &channel, Duration::from_millis(5));
+        let result = block_on(select(sampling, accelerometer.accel_norm()));
+        let Either::Second(acceleration) = result else {
+            panic!("Sampling ended");
+        };
+        assert_eq!(acceleration.unwrap().z, 1.0);
+    }
+
+    /// Sample `mock` onto `channel` until `count` readings have been published.
+    fn sample_readings(
+        mock: &mut MockAnalogInput,
+        channel: &Channel<Result<AnalogReading, PeripheralError>>,
+        count: usize,
+    ) {
+        let mut readings = channel.subscriber().unwrap();
+        let sampling = sample_analog(mock, channel, Duration::from_millis(5));
+        let published = async {
+            for _ in 0..count {
+                readings.next_message_pure().await;
+            }
+        };
+        let result = block_on(select(sampling, published));
+        assert!(matches!(result, Either::Second(())));
+    }
+
+    #[test]
+    fn analog_readings_keep_sampling() {
+        let channel = Channel::<Result<AnalogReading, PeripheralError>>::new();
+        let mut input = LatestAnalogReading::new(&channel, 100).unwrap();
+        // The range is known before any reading arrives
+        assert_eq!(block_on(input.max_value()), Ok(100));
+
+        let mut mock = MockAnalogInput::from_values(100, [10, 20, 50]);
+        sample_readings(&mut mock, &channel, 3);
+
+        assert_eq!(block_on(input.input_raw()), Ok(50));
+        assert_eq!(block_on(input.input_pct()), Ok(50.0));
+    }
+
+    #[test]
+    fn failed_reads_are_published() {
+        let channel = Channel::<Result<AnalogReading, PeripheralError>>::new();
+        let mut input = LatestAnalogReading::new(&channel, 100).unwrap();
+
+        let mut mock = MockAnalogInput::failing(100);
+        sample_readings(&mut mock, &channel, 1);
+
+        assert_eq!(block_on(input.input_raw()), Err(PeripheralError::Adc));
+        assert_eq!(block_on(input.max_value()), Ok(100));
+    }
+}
@@ -0,0 +1,286 @@
+use super::{AdcReference, AnalogInput, PeripheralError};
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use core::cell::RefCell;
+use core::future::poll_fn;
+use core::task::Poll;
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::waitqueue::MultiWakerRegistration;
+use fugit::HertzU32;
+
+#[async_trait]
+/// An analog input that is sampled continuously at a fixed rate.
+pub trait SampledInput: AnalogInput {
+    /// Rate at which the input is sampled.
+    fn sample_rate(&self) -> HertzU32;
+
+    /// Fill `samples` with the most recent raw samples, oldest first. Waits until enough samples
+    /// have been taken if sampling has only just started.
+    async fn read_block(&mut self, samples: &mut [u16]) -> Result<(), PeripheralError>;
+}
+
+/// Ring buffer holding the most recent `LEN` scans of `CHANNELS` analog inputs.
+///
+/// A scan is one sample of each input, taken at the same time. The buffer is filled by the
+/// platform, usually by a DMA driven scan of the ADC, and read through the [`ScanChannel`] handles
+/// of the individual inputs.
+pub struct ScanBuffer<M, const CHANNELS: usize, const LEN: usize>
+where
+    M: RawMutex,
+{
+    /// Rate at which scans are taken.
+    sample_rate: HertzU32,
+    state: Mutex<M, RefCell<ScanState<CHANNELS, LEN>>>,
+}
+
+struct ScanState<const CHANNELS: usize, const LEN: usize> {
+    scans: [[u16; CHANNELS]; LEN],
+    /// Index of the next scan to be written, i.e. of the oldest scan once the buffer is full.
+    next: usize,
+    /// Number of valid scans in the buffer.
+    filled: usize,
+    /// Reference of the ADC taking the scans, if known.
+    reference: Option<AdcReference>,
+    /// Readers waiting for the buffer to fill up.
+    wakers: MultiWakerRegistration<CHANNELS>,
+}
+
+impl<M, const CHANNELS: usize, const LEN: usize> ScanBuffer<M, CHANNELS, LEN>
+where
+    M: RawMutex,
+{
+    /// Create an empty buffer for scans taken at `sample_rate`.
+    pub fn new(sample_rate: HertzU32) -> Self {
+        Self {
+            sample_rate,
+            state: Mutex::new(RefCell::new(ScanState {
+                scans: [[0; CHANNELS]; LEN],
+                next: 0,
+                filled: 0,
+                reference: None,
+                wakers: MultiWakerRegistration::new(),
+            })),
+        }
+    }
+
+    /// Rate at which scans are taken.
+    pub fn sample_rate(&self) -> HertzU32 {
+        self.sample_rate
+    }
+
+    /// Set the reference relating the scanned raw values to voltages. Platforms whose reference
+    /// depends on the supply voltage update it continuously.
+    pub fn set_reference(&self, reference: AdcReference) {
+        self.state
+            .lock(|state| state.borrow_mut().reference = Some(reference));
+    }
+
+    /// Append a single scan, overwriting the oldest one if the buffer is full.
+    pub fn push(&self, scan: [u16; CHANNELS]) {
+        self.push_interleaved(&scan);
+    }
+
+    /// Append consecutive scans with interleaved channels, as written by a DMA scan of the ADC.
+    /// A trailing incomplete scan is ignored.
+    pub fn push_interleaved(&self, samples: &[u16]) {
+        self.state.lock(|state| {
+            let mut state = state.borrow_mut();
+            for scan in samples.as_chunks::<CHANNELS>().0 {
+                let next = state.next;
+                state.scans[next] = *scan;
+                state.next = (next + 1) % LEN;
+                state.filled = (state.filled + 1).min(LEN);
+            }
+            state.wakers.wake();
+        });
+    }
+
+    /// Handle for reading a single input, whose raw values range up to `max_value`.
+    ///
+    /// # Panics
+    ///
+    /// If `channel` is not below `CHANNELS`.
+    pub fn channel(&self, channel: usize, max_value: u16) -> ScanChannel<'_, M, CHANNELS, LEN> {
+        assert!(channel < CHANNELS, "channel {channel} out of range");
+        ScanChannel {
+            buffer: self,
+            channel,
+            max_value,
+        }
+    }
+}
+
+/// A single analog input read from a [`ScanBuffer`].
+pub struct ScanChannel<'a, M, const CHANNELS: usize, const LEN: usize>
+where
+    M: RawMutex,
+{
+    buffer: &'a ScanBuffer<M, CHANNELS, LEN>,
+    /// Index of the input within a scan.
+    channel: usize,
+    /// Maximum signal value, used for computing relative value.
+    max_value: u16,
+}
+
+// Not derived, as that would require `M: Clone`
+impl<M, const CHANNELS: usize, const LEN: usize> Clone for ScanChannel<'_, M, CHANNELS, LEN>
+where
+    M: RawMutex,
+{
+    /// Another handle reading the same input, e.g. for a second mode.
+    fn clone(&self) -> Self {
+        Self {
+            buffer: self.buffer,
+            channel: self.channel,
+            max_value: self.max_value,
+        }
+    }
+}
+
+#[async_trait]
+impl<M, const CHANNELS: usize, const LEN: usize> AnalogInput for ScanChannel<'_, M, CHANNELS, LEN>
+where
+    M: RawMutex + Sync,
+{
+    /// The most recent sample. Fails if no scan has been taken yet.
+    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
+        self.buffer.state.lock(|state| {
+            let state = state.borrow();
+            if state.filled == 0 {
+                return Err(PeripheralError::Adc);
+            }
+            Ok(state.scans[(state.next + LEN - 1) % LEN][self.channel])
+        })
+    }
+
+    async fn max_value(&self) -> Result<u16, PeripheralError> {
+        Ok(self.max_value)
+    }
+
+    /// Fails until the platform has set a reference.
+    async fn reference(&mut self) -> Result<AdcReference, PeripheralError> {
+        self.buffer
+            .state
+            .lock(|state| state.borrow().reference)
+            .ok_or(PeripheralError::Adc)
+    }
+}
+
+#[async_trait]
+impl<M, const CHANNELS: usize, const LEN: usize> SampledInput for ScanChannel<'_, M, CHANNELS, LEN>
+where
+    M: RawMutex + Sync,
+{
+    fn sample_rate(&self) -> HertzU32 {
+        self.buffer.sample_rate
+    }
+
+    /// Fails if more samples are requested than the buffer holds.
+    async fn read_block(&mut self, samples: &mut [u16]) -> Result<(), PeripheralError> {
+        if samples.len() > LEN {
+            return Err(PeripheralError::Adc);
+        }
+
+        poll_fn(|cx| {
+            self.buffer.state.lock(|state| {
+                let mut state = state.borrow_mut();
+                if state.filled < samples.len() {
+                    state.wakers.register(cx.waker());
+                    return Poll::Pending;
+                }
+
+                let start = state.next + LEN - samples.len();
+                for (i, sample) in samples.iter_mut().enumerate() {
+                    *sample = state.scans[(start + i) % LEN][self.channel];
+                }
+                Poll::Ready(Ok(()))
+            })
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::{block_on, poll_once};
+    use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+
+    use super::*;
+
+    type Buffer = ScanBuffer<CriticalSectionRawMutex, 2, 4>;
+
+    #[test]
+    fn empty_buffer_has_no_latest_value() {
+        let buffer = Buffer::new(HertzU32::Hz(100));
+        assert_eq!(
+            block_on(buffer.channel(0, 4096).input_raw()),
+            Err(PeripheralError::Adc)
+        );
+    }
+
+    #[test]
+    fn channels_read_latest_scan() {
+        let buffer = Buffer::new(HertzU32::Hz(100));
+        buffer.push([1, 10]);
+        buffer.push([2, 20]);
+
+        assert_eq!(block_on(buffer.channel(0, 4096).input_raw()), Ok(2));
+        assert_eq!(block_on(buffer.channel(1, 4096).input_raw()), Ok(20));
+        assert_eq!(block_on(buffer.channel(1, 40).input_pct()), Ok(50.0));
+        assert_eq!(buffer.channel(1, 40).sample_rate(), HertzU32::Hz(100));
+    }
+
+    #[test]
+    fn channels_read_voltage_once_reference_is_set() {
+        let buffer = Buffer::new(HertzU32::Hz(100));
+        buffer.push([2048, 4095]);
+        let mut channel = buffer.channel(1, 4096);
+        assert_eq!(block_on(channel.input_mv()), Err(PeripheralError::Adc));
+
+        buffer.set_reference(AdcReference::new(4095, 3300));
+        assert_eq!(block_on(channel.input_mv()), Ok(3300));
+        assert_eq!(block_on(buffer.channel(0, 4096).input_mv()), Ok(1650));
+    }
+
+    #[test]
+    fn blocks_are_ordered_oldest_first_across_wrap_around() {
+        let buffer = Buffer::new(HertzU32::Hz(100));
+        buffer.push_interleaved(&[1, 10, 2, 20, 3, 30, 4, 40, 5, 50, 6, 60, 7]);
+
+        let mut block = [0; 3];
+        block_on(buffer.channel(1, 4096).read_block(&mut block)).unwrap();
+        assert_eq!(block, [40, 50, 60]);
+
+        let mut block = [0; 4];
+        block_on(buffer.channel(0, 4096).read_block(&mut block)).unwrap();
+        assert_eq!(block, [3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn block_waits_until_enough_scans_are_taken() {
+        let buffer = Buffer::new(HertzU32::Hz(100));
+        let mut channel = buffer.channel(0, 4096);
+        let mut block = [0; 2];
+
+        buffer.push([1, 10]);
+        {
+            let mut read = channel.read_block(&mut block);
+            assert!(poll_once(&mut read).is_pending());
+            buffer.push([2, 20]);
+            assert_eq!(poll_once(&mut read), core::task::Poll::Ready(Ok(())));
+        }
+        assert_eq!(block, [1, 2]);
+    }
+
+    #[test]
+    fn block_longer_than_buffer_fails() {
+        let buffer = Buffer::new(HertzU32::Hz(100));
+        let mut block = [0; 5];
+        assert_eq!(
+            block_on(buffer.channel(0, 4096).read_block(&mut block)),
+            Err(PeripheralError::Adc)
+        );
+    }
+}
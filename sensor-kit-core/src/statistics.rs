@@ -0,0 +1,99 @@
+//! Running statistics of measured quantities.
+
+/// Running minimum, maximum and mean of the values of a measured quantity.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Statistics {
+    /// Smallest and largest value, if there are any values.
+    range: Option<(f32, f32)>,
+    /// Mean of all values.
+    mean: f32,
+    /// Number of values.
+    count: u32,
+}
+
+impl Statistics {
+    /// Create statistics of no values.
+    pub const fn new() -> Self {
+        Self {
+            range: None,
+            mean: 0.0,
+            count: 0,
+        }
+    }
+
+    /// Include `value` in the statistics.
+    pub fn push(&mut self, value: f32) {
+        self.range = Some(match self.range {
+            Some((min, max)) => (f32::min(min, value), f32::max(max, value)),
+            None => (value, value),
+        });
+        self.count = self.count.saturating_add(1);
+        // Updated incrementally, a sum would lose precision over many values
+        self.mean += (value - self.mean) / self.count as f32;
+    }
+
+    /// Forget all values.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Smallest value, if any.
+    pub fn min(&self) -> Option<f32> {
+        self.range.map(|(min, _)| min)
+    }
+
+    /// Largest value, if any.
+    pub fn max(&self) -> Option<f32> {
+        self.range.map(|(_, max)| max)
+    }
+
+    /// Mean of all values, if any.
+    pub fn mean(&self) -> Option<f32> {
+        self.range.map(|_| self.mean)
+    }
+
+    /// Number of values.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_statistics_have_no_values() {
+        let statistics = Statistics::new();
+        assert_eq!(statistics.min(), None);
+        assert_eq!(statistics.max(), None);
+        assert_eq!(statistics.mean(), None);
+        assert_eq!(statistics.count(), 0);
+    }
+
+    #[test]
+    fn values_are_summarized() {
+        let mut statistics = Statistics::new();
+        for value in [3.0, -1.0, 4.0, 2.0] {
+            statistics.push(value);
+        }
+
+        assert_eq!(statistics.min(), Some(-1.0));
+        assert_eq!(statistics.max(), Some(4.0));
+        assert_eq!(statistics.mean(), Some(2.0));
+        assert_eq!(statistics.count(), 4);
+
+        statistics.reset();
+        assert_eq!(statistics, Statistics::new());
+    }
+
+    #[test]
+    fn mean_stays_precise() {
+        let mut statistics = Statistics::new();
+        for i in 0..100_000 {
+            statistics.push(60.0 + (i % 2) as f32);
+        }
+        let mean = statistics.mean().unwrap();
+        assert!((mean - 60.5).abs() < 0.01, "{mean} is not close to 60.5");
+    }
+}
@@ -0,0 +1,216 @@
+//! Helpers shared by unit tests.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use core::convert::Infallible;
+use embassy_futures::block_on;
+use embassy_time::Duration;
+use embedded_graphics::mock_display::MockDisplay;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use std::path::PathBuf;
+use std::{env, eprintln, fs};
+use u8g2_fonts::{fonts, U8g2TextStyle};
+
+use crate::app::{AppMode, AppStyle, Draw, EventResult};
+use crate::peripherals::ButtonEvent;
+use crate::trend::Trend;
+use crate::ui::TitleFrame;
+
+/// The style used by the firmware.
+pub fn style() -> AppStyle<BinaryColor> {
+    let text_style = U8g2TextStyle::new(fonts::u8g2_font_mercutio_basic_nbp_tf, BinaryColor::On);
+    let title_style = U8g2TextStyle::new(fonts::u8g2_font_mercutio_sc_nbp_tf, BinaryColor::On);
+    AppStyle::new(title_style, text_style, BinaryColor::On)
+}
+
+/// Area modes are drawn into.
+pub fn draw_area() -> Rectangle {
+    Rectangle::new(Point::new(2, 12), Size::new(60, 50))
+}
+
+/// An empty trend covering `span`, living as long as the modes showing it need.
+pub fn trend(span: Duration) -> &'static Trend {
+    Box::leak(Box::new(Trend::new(span)))
+}
+
+/// Draw an element into [`draw_area`] of a mock display and return the resulting display.
+pub fn draw(element: &impl Draw<MockDisplay<BinaryColor>>) -> MockDisplay<BinaryColor> {
+    let mut display = MockDisplay::new();
+    display.set_allow_overdraw(true);
+    display.set_allow_out_of_bounds_drawing(true);
+    element
+        .draw_with_style(&style(), draw_area(), &mut display)
+        .unwrap();
+    display
+}
+
+/// Hand `event` to a mode drawn to a mock display.
+pub fn handle_event(
+    mode: &mut impl AppMode<MockDisplay<BinaryColor>>,
+    event: ButtonEvent,
+) -> EventResult {
+    block_on(mode.handle_event(event))
+}
+
+/// Perform the menu action labelled `label` of a mode drawn to a mock display. Panics if the mode
+/// doesn't offer it.
+pub fn perform_action(mode: &mut impl AppMode<MockDisplay<BinaryColor>>, label: &str) {
+    let index = mode
+        .actions()
+        .iter()
+        .position(|action| action == label)
+        .unwrap_or_else(|| panic!("Mode has no action '{label}'"));
+    block_on(mode.perform_action(index)).unwrap();
+}
+
+/// Width of the display in pixels.
+const WIDTH: usize = 128;
+/// Height of the display in pixels.
+const HEIGHT: usize = 64;
+
+/// Monochrome framebuffer with the size of the sensor kit's display.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Frame {
+    pixels: [[bool; WIDTH]; HEIGHT],
+}
+
+impl Frame {
+    fn new() -> Self {
+        Self {
+            pixels: [[false; WIDTH]; HEIGHT],
+        }
+    }
+
+    /// Encode as a plain (ASCII) PBM image.
+    fn to_pbm(&self) -> String {
+        let mut pbm = format!("P1\n{WIDTH} {HEIGHT}\n");
+        for row in self.pixels.iter() {
+            pbm.extend(row.iter().map(|&on| if on { '1' } else { '0' }));
+            pbm.push('\n');
+        }
+        pbm
+    }
+
+    /// Decode a plain PBM image, as written by [`Self::to_pbm`].
+    fn from_pbm(pbm: &str) -> Option<Self> {
+        let mut lines = pbm.lines().filter(|line| !line.starts_with('#'));
+        if lines.next()?.trim() != "P1" || lines.next()?.trim() != format!("{WIDTH} {HEIGHT}") {
+            return None;
+        }
+
+        let mut bits = lines.flat_map(str::chars).filter(|c| !c.is_whitespace());
+        let mut frame = Self::new();
+        for pixel in frame.pixels.iter_mut().flatten() {
+            *pixel = match bits.next()? {
+                '0' => false,
+                '1' => true,
+                _ => return None,
+            };
+        }
+        Some(frame)
+    }
+
+    /// Render the difference to `actual`, one character per pixel: `#` for pixels lit in both,
+    /// `.` for pixels lit in neither, `+` for pixels only lit in `actual` and `-` for pixels only
+    /// lit in `self`.
+    fn diff(&self, actual: &Self) -> String {
+        let mut diff = String::new();
+        let mut count = 0;
+        for (expected_row, actual_row) in self.pixels.iter().zip(actual.pixels.iter()) {
+            for (&expected_on, &actual_on) in expected_row.iter().zip(actual_row.iter()) {
+                diff.push(match (expected_on, actual_on) {
+                    (true, true) => '#',
+                    (false, false) => '.',
+                    (false, true) => '+',
+                    (true, false) => '-',
+                });
+                count += (expected_on != actual_on) as usize;
+            }
+            diff.push('\n');
+        }
+        diff + &format!("{count} pixels differ (+: only in actual, -: only in reference)")
+    }
+}
+
+impl OriginDimensions for Frame {
+    fn size(&self) -> Size {
+        Size::new(WIDTH as u32, HEIGHT as u32)
+    }
+}
+
+impl DrawTarget for Frame {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let (Ok(x @ 0..WIDTH), Ok(y @ 0..HEIGHT)) =
+                (usize::try_from(point.x), usize::try_from(point.y))
+            {
+                self.pixels[y][x] = color.is_on();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Render a mode the way the firmware shows it: inside a [`TitleFrame`] filling the display.
+pub fn render(mode: &impl AppMode<Frame>) -> Frame {
+    let style = style();
+    let mut frame = Frame::new();
+
+    let title = mode.title();
+    let title_frame = TitleFrame::new(
+        &title,
+        style.title_style.clone(),
+        BinaryColor::On,
+        frame.bounding_box(),
+    );
+    let inner_area = title_frame.inner_area();
+
+    title_frame.draw(&mut frame).unwrap();
+    mode.draw_with_style(&style, inner_area, &mut frame)
+        .unwrap();
+    frame
+}
+
+/// Render a mode and compare the result to the reference image `snapshots/<name>.pbm`.
+///
+/// Fails if the reference is missing or differs. Set the `UPDATE_SNAPSHOTS` environment variable
+/// to record new references or replace existing ones after an intended change.
+pub fn assert_snapshot(name: &str, mode: &impl AppMode<Frame>) {
+    let actual = render(mode);
+
+    let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("snapshots");
+    let path = directory.join(format!("{name}.pbm"));
+    let reference = fs::read_to_string(&path)
+        .ok()
+        .and_then(|pbm| Frame::from_pbm(&pbm));
+    let update = env::var_os("UPDATE_SNAPSHOTS").is_some();
+
+    match reference {
+        Some(reference) if reference == actual => {}
+        Some(reference) if !update => panic!(
+            "Snapshot '{name}' does not match {}:\n{}\n\
+            Run with UPDATE_SNAPSHOTS=1 if the change is intended.",
+            path.display(),
+            reference.diff(&actual),
+        ),
+        None if !update => panic!(
+            "Snapshot '{name}' has no reference {}.\n\
+            Run with UPDATE_SNAPSHOTS=1 to record it.",
+            path.display(),
+        ),
+        _ => {
+            fs::create_dir_all(&directory).unwrap();
+            fs::write(&path, actual.to_pbm()).unwrap();
+            eprintln!("Recorded snapshot {}", path.display());
+        }
+    }
+}
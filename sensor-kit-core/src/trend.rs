@@ -0,0 +1,103 @@
+//! Trends of measured quantities, recorded in the background for the modes to show.
+
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_time::{Duration, Instant};
+
+use crate::history::History;
+use crate::statistics::Statistics;
+
+/// History and statistics of a measured quantity, shared between the task recording the readings
+/// of its sensor and the mode showing them. As the readings are recorded from their channel, see
+/// [`crate::peripherals::record`], nothing is missed while the mode isn't shown.
+pub struct Trend {
+    state: Mutex<CriticalSectionRawMutex, RefCell<TrendState>>,
+}
+
+struct TrendState {
+    history: History,
+    statistics: Statistics,
+}
+
+impl Trend {
+    /// Create an empty trend, its history covering `span`.
+    pub const fn new(span: Duration) -> Self {
+        Self {
+            state: Mutex::new(RefCell::new(TrendState {
+                history: History::new(span),
+                statistics: Statistics::new(),
+            })),
+        }
+    }
+
+    /// Add `value` measured at `timestamp`. The statistics include every value, even those
+    /// following too closely to be kept in the history.
+    pub fn push(&self, timestamp: Instant, value: f32) {
+        self.state.lock(|state| {
+            let mut state = state.borrow_mut();
+            state.history.push(timestamp, value);
+            state.statistics.push(value);
+        });
+    }
+
+    /// Span of time covered by the history.
+    pub fn span(&self) -> Duration {
+        self.state.lock(|state| state.borrow().history.span())
+    }
+
+    /// The newest entry of the history, if any.
+    pub fn latest(&self) -> Option<(Instant, f32)> {
+        self.state.lock(|state| state.borrow().history.latest())
+    }
+
+    /// Points of a chart of the history, see [`History::points`].
+    pub fn points(&self, now: Instant) -> Vec<(f32, f32)> {
+        self.state.lock(|state| state.borrow().history.points(now))
+    }
+
+    /// Statistics of all values since the last reset.
+    pub fn statistics(&self) -> Statistics {
+        self.state.lock(|state| state.borrow().statistics)
+    }
+
+    /// Forget the values included in the statistics, keeping the history.
+    pub fn reset_statistics(&self) {
+        self.state
+            .lock(|state| state.borrow_mut().statistics.reset());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_recorded() {
+        let trend = Trend::new(Duration::from_secs(60));
+        trend.push(Instant::from_secs(1), 21.5);
+        trend.push(Instant::from_secs(2), 22.0);
+
+        assert_eq!(trend.latest(), Some((Instant::from_secs(2), 22.0)));
+        assert_eq!(
+            trend.points(Instant::from_secs(3)),
+            [(-2.0, 21.5), (-1.0, 22.0)]
+        );
+    }
+
+    #[test]
+    fn statistics_include_every_value() {
+        let trend = Trend::new(Duration::from_secs(60));
+        // The second value follows too closely for the history
+        trend.push(Instant::from_secs(1), 18.0);
+        trend.push(Instant::from_millis(1500), 24.0);
+        assert_eq!(trend.points(Instant::from_secs(2)).len(), 1);
+        assert_eq!(trend.statistics().count(), 2);
+        assert_eq!(trend.statistics().max(), Some(24.0));
+
+        trend.reset_statistics();
+        assert_eq!(trend.statistics().count(), 0);
+        assert_eq!(trend.latest(), Some((Instant::from_secs(1), 18.0)));
+    }
+}
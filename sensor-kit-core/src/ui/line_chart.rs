@@ -0,0 +1,277 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use embassy_time::Duration;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Line, Polyline, PrimitiveStyle, Rectangle};
+use embedded_graphics::text::renderer::{CharacterStyle, TextRenderer};
+use embedded_graphics::text::{Alignment, Baseline, Text, TextStyleBuilder};
+use embedded_layout::prelude::*;
+
+/// Format a value for an axis label, with a precision fitting its magnitude.
+fn format_value(value: f32, unit: &str) -> String {
+    if value.abs() < 100.0 {
+        format!("{value:.1}{unit}")
+    } else {
+        format!("{value:.0}{unit}")
+    }
+}
+
+/// Format a span of time for the time axis label, in the largest unit it is a whole multiple of.
+fn format_span(span: Duration) -> String {
+    let secs = span.as_secs();
+    if secs >= 3600 && secs % 3600 == 0 {
+        format!("-{}h", secs / 3600)
+    } else if secs >= 60 && secs % 60 == 0 {
+        format!("-{}min", secs / 60)
+    } else {
+        format!("-{secs}s")
+    }
+}
+
+/// A chart plotting values over time as a line, with the newest values on the right.
+///
+/// The value axis is scaled to the range of the values, which is labeled at its top and bottom.
+/// The time axis covers a span of time up to now.
+pub struct LineChart<'a, S, C> {
+    /// Points of the line, given as their time in seconds relative to now and their value. Points
+    /// are connected in the given order.
+    pub points: &'a [(f32, f32)],
+    /// Span of time covered by the time axis.
+    pub span: Duration,
+    /// Unit appended to the value labels.
+    pub unit: &'a str,
+    /// Style used to draw the labels.
+    pub text_style: S,
+    /// Color of the line and axes.
+    pub color: C,
+    /// Area filled by the chart, including its labels.
+    pub area: Rectangle,
+}
+
+impl<'a, S, C> LineChart<'a, S, C> {
+    /// Create a new [`LineChart`].
+    pub fn new(
+        points: &'a [(f32, f32)],
+        span: Duration,
+        unit: &'a str,
+        text_style: S,
+        color: C,
+        area: Rectangle,
+    ) -> Self {
+        Self {
+            points,
+            span,
+            unit,
+            text_style,
+            color,
+            area,
+        }
+    }
+
+    /// Range of the value axis. Widened around the value if all values are the same.
+    fn value_range(&self) -> Option<(f32, f32)> {
+        let mut values = self.points.iter().map(|&(_, value)| value);
+        let first = values.next()?;
+        let (min, max) = values.fold((first, first), |(min, max), value| {
+            (f32::min(min, value), f32::max(max, value))
+        });
+        if (max - min).abs() < f32::EPSILON {
+            Some((min - 1.0, max + 1.0))
+        } else {
+            Some((min, max))
+        }
+    }
+}
+
+impl<S, C> View for LineChart<'_, S, C> {
+    fn translate_impl(&mut self, by: Point) {
+        self.area.translate_impl(by)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        self.area
+    }
+}
+
+impl<S, C> Drawable for LineChart<'_, S, C>
+where
+    C: PixelColor,
+    S: CharacterStyle<Color = C> + TextRenderer<Color = C>,
+{
+    type Color = C;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let Some((min, max)) = self.value_range() else {
+            return Ok(());
+        };
+
+        let max_label = format_value(max, self.unit);
+        let min_label = format_value(min, self.unit);
+        let label_width = [&max_label, &min_label]
+            .iter()
+            .map(|label| {
+                self.text_style
+                    .measure_string(label, Point::zero(), Baseline::Top)
+                    .bounding_box
+                    .size
+                    .width
+            })
+            .max()
+            .unwrap_or(0)
+            + 2;
+        let line_height = self.text_style.line_height();
+
+        // The plot takes the area right of the value labels and above the time labels
+        let plot = Rectangle::new(
+            self.area.top_left + Point::new(label_width as i32, 0),
+            Size::new(
+                self.area.size.width.saturating_sub(label_width),
+                self.area.size.height.saturating_sub(line_height),
+            ),
+        );
+        let Some(bottom_right) = plot.bottom_right() else {
+            return Ok(());
+        };
+
+        let left = TextStyleBuilder::new()
+            .alignment(Alignment::Left)
+            .baseline(Baseline::Top)
+            .build();
+        let right = TextStyleBuilder::new()
+            .alignment(Alignment::Right)
+            .baseline(Baseline::Bottom)
+            .build();
+
+        // Value labels at the top and bottom of the value axis
+        Text::with_text_style(
+            &max_label,
+            self.area.top_left,
+            self.text_style.clone(),
+            left,
+        )
+        .draw(target)?;
+        Text::with_text_style(
+            &min_label,
+            Point::new(
+                self.area.top_left.x,
+                bottom_right.y - line_height as i32 + 1,
+            ),
+            self.text_style.clone(),
+            left,
+        )
+        .draw(target)?;
+
+        // Time labels below both ends of the time axis
+        Text::with_text_style(
+            &format_span(self.span),
+            Point::new(plot.top_left.x, bottom_right.y + 1),
+            self.text_style.clone(),
+            left,
+        )
+        .draw(target)?;
+        Text::with_text_style(
+            "now",
+            Point::new(
+                bottom_right.x,
+                self.area.bottom_right().map_or(0, |point| point.y),
+            ),
+            self.text_style.clone(),
+            right,
+        )
+        .draw(target)?;
+
+        // Axes along the left and bottom of the plot
+        let axis_style = PrimitiveStyle::with_stroke(self.color, 1);
+        Line::new(plot.top_left, Point::new(plot.top_left.x, bottom_right.y))
+            .into_styled(axis_style)
+            .draw(target)?;
+        Line::new(Point::new(plot.top_left.x, bottom_right.y), bottom_right)
+            .into_styled(axis_style)
+            .draw(target)?;
+
+        // The line, inside the axes
+        let width = (bottom_right.x - plot.top_left.x - 1) as f32;
+        let height = (bottom_right.y - plot.top_left.y - 1) as f32;
+        let span_s = (self.span.as_millis() as f32 / 1000.0).max(f32::EPSILON);
+        let points: Vec<_> = self
+            .points
+            .iter()
+            .map(|&(time_s, value)| {
+                let x = bottom_right.x as f32 - (-time_s / span_s).clamp(0.0, 1.0) * width;
+                let y = plot.top_left.y as f32 + (max - value) / (max - min) * height;
+                Point::new(x as i32, y as i32)
+            })
+            .collect();
+
+        Polyline::new(&points)
+            .into_styled(axis_style)
+            .draw(target)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    use super::*;
+    use crate::test_utils::style;
+
+    fn draw(points: &[(f32, f32)]) -> MockDisplay<BinaryColor> {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        let chart = LineChart::new(
+            points,
+            Duration::from_secs(60),
+            "%",
+            style().text_style,
+            BinaryColor::On,
+            Rectangle::new(Point::zero(), Size::new(64, 40)),
+        );
+        chart.draw(&mut display).unwrap();
+        display
+    }
+
+    #[test]
+    fn labels_fit_magnitude() {
+        assert_eq!(format_value(21.46, "°C"), "21.5°C");
+        assert_eq!(format_value(101.3, "kPa"), "101kPa");
+        assert_eq!(format_span(Duration::from_secs(3600)), "-1h");
+        assert_eq!(format_span(Duration::from_secs(60)), "-1min");
+        assert_eq!(format_span(Duration::from_secs(90)), "-90s");
+    }
+
+    #[test]
+    fn constant_values_get_a_range() {
+        let points = [(-10.0, 5.0), (0.0, 5.0)];
+        let chart = LineChart::new(
+            &points,
+            Duration::from_secs(60),
+            "",
+            (),
+            BinaryColor::On,
+            Rectangle::zero(),
+        );
+        assert_eq!(chart.value_range(), Some((4.0, 6.0)));
+    }
+
+    #[test]
+    fn nothing_drawn_without_points() {
+        assert!(draw(&[]).affected_area().is_zero_sized());
+    }
+
+    #[test]
+    fn drawing_depends_on_values() {
+        let rising = draw(&[(-60.0, 0.0), (-30.0, 5.0), (0.0, 10.0)]);
+        let falling = draw(&[(-60.0, 10.0), (-30.0, 5.0), (0.0, 0.0)]);
+        assert_ne!(rising, falling);
+        assert!(!rising.affected_area().is_zero_sized());
+    }
+}
@@ -0,0 +1,97 @@
+use alloc::string::String;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyleBuilder, Rectangle, StrokeAlignment};
+use embedded_graphics::text::renderer::{CharacterStyle, TextRenderer};
+use embedded_graphics::text::{Baseline, Text};
+use embedded_layout::prelude::*;
+
+/// A vertical list of entries with a frame around the selected one. Scrolls to keep the selected
+/// entry visible if not all entries fit.
+pub struct ListMenu<'a, S, C> {
+    /// Entries of the menu, from top to bottom.
+    pub items: &'a [String],
+    /// Index of the selected entry.
+    pub selected: usize,
+    /// Style used to draw the entries.
+    pub text_style: S,
+    /// Color of the frame around the selected entry.
+    pub color: C,
+    /// Area filled by the menu.
+    pub area: Rectangle,
+}
+
+impl<'a, S, C> ListMenu<'a, S, C> {
+    /// Create a new [`ListMenu`].
+    pub fn new(
+        items: &'a [String],
+        selected: usize,
+        text_style: S,
+        color: C,
+        area: Rectangle,
+    ) -> Self {
+        Self {
+            items,
+            selected,
+            text_style,
+            color,
+            area,
+        }
+    }
+}
+
+impl<S, C> View for ListMenu<'_, S, C> {
+    fn translate_impl(&mut self, by: Point) {
+        self.area.translate_impl(by)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        self.area
+    }
+}
+
+impl<S, C> Drawable for ListMenu<'_, S, C>
+where
+    C: PixelColor,
+    S: CharacterStyle<Color = C> + TextRenderer<Color = C>,
+{
+    type Color = C;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        // Leave room for the frame above and below each entry
+        let line_height = self.text_style.line_height() + 2;
+        let rows = (self.area.size.height / line_height).max(1) as usize;
+        let first = (self.selected + 1).saturating_sub(rows);
+
+        let frame_style = PrimitiveStyleBuilder::new()
+            .stroke_color(self.color)
+            .stroke_width(1)
+            .stroke_alignment(StrokeAlignment::Inside)
+            .build();
+
+        for (row, (index, item)) in self
+            .items
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(rows)
+            .enumerate()
+        {
+            let top_left = self.area.top_left + Point::new(0, (row as u32 * line_height) as i32);
+            let text_position = top_left + Point::new(2, 1);
+            Text::with_baseline(item, text_position, self.text_style.clone(), Baseline::Top)
+                .draw(target)?;
+
+            if index == self.selected {
+                Rectangle::new(top_left, Size::new(self.area.size.width, line_height))
+                    .into_styled(frame_style)
+                    .draw(target)?;
+            }
+        }
+
+        Ok(())
+    }
+}
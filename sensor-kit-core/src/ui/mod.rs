@@ -0,0 +1,17 @@
+mod filled_circle;
+mod horizontal_bar;
+mod line_chart;
+mod list_menu;
+mod statistics_table;
+mod title_frame;
+mod vector_box;
+mod vertical_bar;
+
+pub use filled_circle::FilledCircle;
+pub use horizontal_bar::HorizontalBar;
+pub use line_chart::LineChart;
+pub use list_menu::ListMenu;
+pub use statistics_table::{StatisticsRow, StatisticsTable};
+pub use title_frame::TitleFrame;
+pub use vector_box::VectorBox;
+pub use vertical_bar::VerticalBar;
@@ -0,0 +1,202 @@
+use alloc::format;
+use alloc::string::String;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::text::renderer::{CharacterStyle, TextRenderer};
+use embedded_graphics::text::{Alignment, Baseline, Text, TextStyleBuilder};
+use embedded_layout::prelude::*;
+
+use crate::statistics::Statistics;
+
+/// Format a value for a table cell with the given number of decimals. Values too large for a cell
+/// are shown in thousands.
+fn format_value(value: Option<f32>, decimals: usize) -> String {
+    match value {
+        None => String::from("-"),
+        Some(value) if value.abs() < 10_000.0 => format!("{value:.decimals$}"),
+        Some(value) => format!("{:.0}k", value / 1000.0),
+    }
+}
+
+/// Format a number of values for a table cell.
+fn format_count(count: u32) -> String {
+    if count < 10_000 {
+        format!("{count}")
+    } else {
+        format!("{}k", count / 1000)
+    }
+}
+
+/// A row of a [`StatisticsTable`], showing the statistics of one quantity.
+#[derive(Debug, Clone, Copy)]
+pub struct StatisticsRow<'a> {
+    /// Label of the quantity, usually its unit.
+    pub label: &'a str,
+    /// Number of decimals the values of the quantity are shown with.
+    pub decimals: usize,
+    /// Statistics of the quantity.
+    pub statistics: Statistics,
+}
+
+impl<'a> StatisticsRow<'a> {
+    /// Create a new [`StatisticsRow`].
+    pub fn new(label: &'a str, decimals: usize, statistics: Statistics) -> Self {
+        Self {
+            label,
+            decimals,
+            statistics,
+        }
+    }
+}
+
+/// A table listing the minimum, maximum, mean and number of values of one or more quantities,
+/// one row per quantity below a header.
+pub struct StatisticsTable<'a, S> {
+    /// Rows of the table, one per quantity.
+    pub rows: &'a [StatisticsRow<'a>],
+    /// Style used to draw the table.
+    pub text_style: S,
+    /// Area filled by the table.
+    pub area: Rectangle,
+}
+
+impl<'a, S> StatisticsTable<'a, S> {
+    /// Create a new [`StatisticsTable`].
+    pub fn new(rows: &'a [StatisticsRow<'a>], text_style: S, area: Rectangle) -> Self {
+        Self {
+            rows,
+            text_style,
+            area,
+        }
+    }
+}
+
+impl<S> View for StatisticsTable<'_, S> {
+    fn translate_impl(&mut self, by: Point) {
+        self.area.translate_impl(by)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        self.area
+    }
+}
+
+impl<S, C> Drawable for StatisticsTable<'_, S>
+where
+    C: PixelColor,
+    S: CharacterStyle<Color = C> + TextRenderer<Color = C>,
+{
+    type Color = C;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        const HEADER: [&str; 4] = ["min", "max", "avg", "n"];
+
+        let label_width = self
+            .rows
+            .iter()
+            .map(|row| {
+                self.text_style
+                    .measure_string(row.label, Point::zero(), Baseline::Top)
+                    .bounding_box
+                    .size
+                    .width
+            })
+            .max()
+            .unwrap_or(0);
+        // The values share the width right of the labels, right-aligned in their columns
+        let column_width = self.area.size.width.saturating_sub(label_width) / HEADER.len() as u32;
+        let column_right = |column: usize| {
+            self.area.top_left.x + (label_width + (column as u32 + 1) * column_width) as i32 - 1
+        };
+        let line_height = self.text_style.line_height() as i32;
+
+        let left = TextStyleBuilder::new()
+            .alignment(Alignment::Left)
+            .baseline(Baseline::Top)
+            .build();
+        let right = TextStyleBuilder::new()
+            .alignment(Alignment::Right)
+            .baseline(Baseline::Top)
+            .build();
+
+        for (column, heading) in HEADER.iter().enumerate() {
+            let position = Point::new(column_right(column), self.area.top_left.y);
+            Text::with_text_style(heading, position, self.text_style.clone(), right)
+                .draw(target)?;
+        }
+
+        for (index, row) in self.rows.iter().enumerate() {
+            let y = self.area.top_left.y + (index as i32 + 1) * line_height;
+            Text::with_text_style(
+                row.label,
+                Point::new(self.area.top_left.x, y),
+                self.text_style.clone(),
+                left,
+            )
+            .draw(target)?;
+
+            let cells = [
+                format_value(row.statistics.min(), row.decimals),
+                format_value(row.statistics.max(), row.decimals),
+                format_value(row.statistics.mean(), row.decimals),
+                format_count(row.statistics.count()),
+            ];
+            for (column, cell) in cells.iter().enumerate() {
+                let position = Point::new(column_right(column), y);
+                Text::with_text_style(cell, position, self.text_style.clone(), right)
+                    .draw(target)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    use super::*;
+    use crate::test_utils::style;
+
+    fn draw(statistics: Statistics) -> MockDisplay<BinaryColor> {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        // The mock display is narrower than the table needs
+        display.set_allow_out_of_bounds_drawing(true);
+        let rows = [StatisticsRow::new("T", 1, statistics)];
+        let table = StatisticsTable::new(
+            &rows,
+            style().text_style,
+            Rectangle::new(Point::zero(), Size::new(64, 40)),
+        );
+        table.draw(&mut display).unwrap();
+        display
+    }
+
+    #[test]
+    fn cells_use_row_precision() {
+        assert_eq!(format_value(None, 1), "-");
+        assert_eq!(format_value(Some(21.46), 1), "21.5");
+        assert_eq!(format_value(Some(101.3), 1), "101.3");
+        assert_eq!(format_value(Some(40.25), 0), "40");
+        assert_eq!(format_value(Some(1.234), 2), "1.23");
+        assert_eq!(format_value(Some(32_400.0), 1), "32k");
+        assert_eq!(format_count(1234), "1234");
+        assert_eq!(format_count(72_500), "72k");
+    }
+
+    #[test]
+    fn drawing_depends_on_statistics() {
+        let mut statistics = Statistics::new();
+        let empty = draw(statistics);
+        statistics.push(21.5);
+
+        assert_ne!(draw(statistics), empty);
+    }
+}
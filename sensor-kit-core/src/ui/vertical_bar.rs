@@ -1,12 +1,12 @@
 use embedded_graphics::{
-    geometry::AnchorX,
+    geometry::AnchorY,
     prelude::*,
     primitives::{PrimitiveStyleBuilder, Rectangle, StrokeAlignment},
 };
 use embedded_layout::prelude::*;
 
-/// A horizontal, partially filled "loading bar"-like element.
-pub struct HorizontalBar<C> {
+/// A vertical, partially filled bar, filling up from the bottom.
+pub struct VerticalBar<C> {
     /// Percentage of the bar that is filled.
     pub fill_pct: f32,
     /// Color of the bar.
@@ -15,7 +15,7 @@ pub struct HorizontalBar<C> {
     pub area: Rectangle,
 }
 
-impl<C> HorizontalBar<C> {
+impl<C> VerticalBar<C> {
     pub fn new(fill_pct: f32, color: C, area: Rectangle) -> Self {
         Self {
             fill_pct,
@@ -25,7 +25,7 @@ impl<C> HorizontalBar<C> {
     }
 }
 
-impl<C> View for HorizontalBar<C> {
+impl<C> View for VerticalBar<C> {
     fn translate_impl(&mut self, by: Point) {
         self.area.translate_impl(by)
     }
@@ -35,7 +35,7 @@ impl<C> View for HorizontalBar<C> {
     }
 }
 
-impl<C> Drawable for HorizontalBar<C>
+impl<C> Drawable for VerticalBar<C>
 where
     C: PixelColor,
 {
@@ -57,11 +57,11 @@ where
         let frame = self.area.into_styled(frame_style);
 
         let inner_area = self.area.offset(-2);
-        let full_width = inner_area.size.width;
-        let fill_width = (full_width as f32 * self.fill_pct.clamp(0.0, 100.0) / 100.0) as u32;
+        let full_height = inner_area.size.height;
+        let fill_height = (full_height as f32 * self.fill_pct.clamp(0.0, 100.0) / 100.0) as u32;
 
         let fill = inner_area
-            .resized_width(fill_width, AnchorX::Left)
+            .resized_height(fill_height, AnchorY::Bottom)
             .into_styled(fill_style);
 
         frame.draw(target)?;
@@ -1,93 +1,177 @@
 //! # Arduino Sensor Kit Firmware
 //!
 //! This application demonstrates the use of a variety of peripherals found on the Arduino Sensor
-//! Kit. The button connected to pin D4 cycles through a variety of modes, some of which can be
-//! controlled by the potentiometer dial connected to pin A0.
+//! Kit. Pressing the button connected to pin D4 cycles through a variety of modes, some of which can
+//! be controlled by the potentiometer dial connected to pin A0. A double click goes back to the
+//! previous mode, unless the mode uses it to switch between its views. A long press always opens a
+//! menu listing the actions of the active mode, e.g. resetting its statistics, followed by all
+//! modes. The potentiometer selects an entry, a press confirms it.
 //!
-//! The application is structured into 3 distinct layers: The application layer describing the
-//! various modes in [`mode`], the peripheral layer in [`peripherals`] defining the abstract
-//! peripheral interfaces used by the application layer, and the platform layer containing platform
-//! specific implementations of those interfaces. Finally, a main loop runs and switches between
-//! the different modes.
-//!
-//! A mode is anything that implements [`AppMode`], which apart from some bookkeeping requires
-//! implementing both [`app::Update`] and [`app::Draw`]. The [`app::Update`] implementation defines
-//! how a mode updates its internal state (e.g. based on sensor data), and [`app::Draw`] determines
-//! what should be shown on the display during this mode, depending on the mode's internal state.
-//! Defining a new mode is as simple as freely defining these two operations.
+//! The platform independent parts of the application, i.e. the modes and the abstract peripheral
+//! interfaces they use, live in the [`sensor_kit_core`] library. This crate adds the platform
+//! layer in [`platform`] containing platform specific implementations of those interfaces, and a
+//! main loop that runs the modes.
 
-#![no_std]
-#![no_main]
+#![cfg_attr(not(feature = "sim"), no_std)]
+#![cfg_attr(not(feature = "sim"), no_main)]
 
 extern crate alloc;
 
-mod app;
-mod mode;
-mod peripherals;
 mod platform;
-mod ui;
 
 #[cfg(feature = "nucleo-f413zh")]
 use platform::nucleo_f413zh as hw_platform;
 
+#[cfg(feature = "nucleo-l476rg")]
+use platform::nucleo_l476rg as hw_platform;
+
 #[cfg(feature = "rp-pico")]
 use platform::rp_pico as hw_platform;
 
+#[cfg(feature = "rp2040")]
+use platform::rp2040 as hw_platform;
+
+#[cfg(feature = "nrf52840")]
+use platform::nrf52840 as hw_platform;
+
+#[cfg(feature = "qemu")]
+use platform::qemu as hw_platform;
+
+#[cfg(feature = "sim")]
+use platform::sim as hw_platform;
+
 use hw_platform::{platform, I2c, PinError};
 
-use app::{AppMode, AppStyle};
-use embassy_embedded_hal::shared_bus::blocking::i2c::I2cDevice;
-use mode::buzzer::BuzzerMode;
-use mode::{
-    AccelerationMode, EnvironmentMode, LedMode, LightSensorMode, PotentiometerMode, SoundMode,
+use platform::{DynSafeWait, PinButton, Sampler};
+use sensor_kit_core::app::{AppMode, AppStyle, Draw};
+use sensor_kit_core::manager::ModeManager;
+use sensor_kit_core::mode::acceleration::AccelerationInput;
+use sensor_kit_core::mode::buzzer::BuzzerMode;
+use sensor_kit_core::mode::environment::EnvironmentSensors;
+use sensor_kit_core::mode::sound::SoundLevelInput;
+use sensor_kit_core::mode::{
+    AccelerationMode, EnvironmentMode, EnvironmentTrends, LedMode, LightSensorMode,
+    PotentiometerMode, ScopeMode, SoundLevelMeter, SoundMode, SpectrumMode,
+};
+use sensor_kit_core::peripherals::{
+    record, sample_acceleration, sample_analog, sample_environment, sample_sound_level,
+    AccelerationReading, AnalogInput, AnalogReading, ButtonDriver, ButtonEvent, ButtonTimings,
+    Calibration, EnvironmentReading, ExponentialSmoothing, Hysteresis, LatestAnalogReading,
+    LatestReading, Oversampled, PeripheralError, Photoresistor, ReadingChannel,
+    ReversedAnalogInput, SensorKitEnvSensors, SoundLevelReading,
 };
-use peripherals::{ReversedAnalogInput, SensorKitEnvSensors};
-use platform::DynSafeWait;
-use ui::TitleFrame;
+use sensor_kit_core::trend::Trend;
+use sensor_kit_core::ui::TitleFrame;
+
+use embassy_embedded_hal::shared_bus::blocking::i2c::I2cDevice;
 
 use alloc::vec;
-use alloc::{boxed::Box, sync::Arc, vec::Vec};
-use bme280::i2c::BME280;
+use alloc::{boxed::Box, vec::Vec};
 use core::cell::RefCell;
+#[cfg(not(feature = "sim"))]
 use core::mem::MaybeUninit;
 use display_interface_i2c::I2CInterface;
 use embassy_executor::{task, Spawner};
+use embassy_futures::join::join5;
+use embassy_futures::select::{select3, Either3};
 use embassy_sync::{
     blocking_mutex::raw::CriticalSectionRawMutex, blocking_mutex::Mutex as BlockingMutex,
-    mutex::Mutex, signal::Signal,
+    signal::Signal,
 };
-use embassy_time::{Delay, Timer};
+use embassy_time::{Duration, Instant, Timer};
+#[cfg(not(feature = "sim"))]
 use embedded_alloc::LlffHeap as Heap;
-use embedded_dht_rs::dht20::Dht20;
 use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
 use lis3dh::Lis3dh;
 use ssd1315::Ssd1315;
 use static_cell::StaticCell;
 use u8g2_fonts::{fonts, U8g2TextStyle};
+#[cfg(feature = "rtt")]
 use {defmt_rtt as _, panic_probe as _};
+#[cfg(feature = "qemu")]
+use {defmt_semihosting as _, panic_semihosting as _};
 
+// The simulator runs on the host and keeps using the system allocator.
+#[cfg(not(feature = "sim"))]
 #[global_allocator]
 static HEAP: Heap = Heap::empty();
 
-static BUTTON_SIGNAL: Signal<CriticalSectionRawMutex, bool> = Signal::new();
+/// Model of the light sensor on A3: a photoresistor above a 10 kΩ resistor. The LDR values are
+/// typical for the GL5528 type used on most kits, adjust them to the datasheet of yours.
+const LIGHT_SENSOR: Photoresistor = Photoresistor::new(10_000.0, 15_000.0, 0.7);
+/// Sensitivity of the sound sensor on A2 in Pa per raw ADC count: an amplitude of 50 counts RMS
+/// corresponds to 60 dB SPL, i.e. 0.02 Pa. The sensitivity differs between kits, compare with a
+/// sound level meter to adjust it.
+const SOUND_PA_PER_COUNT: f32 = 0.02 / 50.0;
+
+/// Interval between readings of the environment sensors, which change slowly. A measurement of
+/// the DHT20 takes 80 ms, the bus is free for other devices meanwhile.
+const ENVIRONMENT_INTERVAL: Duration = Duration::from_secs(2);
+/// Interval between readings of the accelerometer.
+const ACCELERATION_INTERVAL: Duration = Duration::from_millis(20);
+/// Interval between readings of the potentiometer, short enough for dialing in values smoothly.
+const POTENTIOMETER_INTERVAL: Duration = Duration::from_millis(20);
+/// Interval between readings of the light sensor.
+const LIGHT_INTERVAL: Duration = Duration::from_millis(100);
+/// Interval between measurements of the sound level, short enough not to miss short sounds.
+const SOUND_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Readings of an analog input. The potentiometer has the most subscribers: the modes it controls,
+/// the mode menu and the trend recorder.
+type AnalogReadings =
+    ReadingChannel<CriticalSectionRawMutex, Result<AnalogReading, PeripheralError>, 2, 6>;
+
+// Each sensor's readings are subscribed to by the modes showing them and by the trend recorder.
+static ENVIRONMENT_READINGS: ReadingChannel<CriticalSectionRawMutex, EnvironmentReading, 2, 2> =
+    ReadingChannel::new();
+static ACCELERATION_READINGS: ReadingChannel<CriticalSectionRawMutex, AccelerationReading, 2, 2> =
+    ReadingChannel::new();
+static POTENTIOMETER_READINGS: AnalogReadings = ReadingChannel::new();
+static LIGHT_READINGS: AnalogReadings = ReadingChannel::new();
+static SOUND_LEVELS: ReadingChannel<CriticalSectionRawMutex, SoundLevelReading, 2, 2> =
+    ReadingChannel::new();
+
+static ENVIRONMENT_TRENDS: EnvironmentTrends = EnvironmentTrends::new();
+static ACCELERATION_TREND: Trend = Trend::new(AccelerationMode::HISTORY_SPAN);
+static POTENTIOMETER_TREND: Trend = Trend::new(PotentiometerMode::HISTORY_SPAN);
+static LIGHT_TREND: Trend = Trend::new(LightSensorMode::HISTORY_SPAN);
+static SOUND_TREND: Trend = Trend::new(SoundMode::HISTORY_SPAN);
+
+static BUTTON_SIGNAL: Signal<CriticalSectionRawMutex, ButtonEvent> = Signal::new();
 
 static I2C_BUS: StaticCell<BlockingMutex<CriticalSectionRawMutex, RefCell<I2c>>> =
     StaticCell::new();
 
-#[embassy_executor::main]
+// The low-power executor stops the MCU while all tasks wait, see `platform::nucleo_l476rg`.
+#[cfg_attr(
+    feature = "nucleo-l476rg",
+    embassy_executor::main(executor = "embassy_stm32::low_power::Executor")
+)]
+#[cfg_attr(not(feature = "nucleo-l476rg"), embassy_executor::main)]
 async fn main(spawner: Spawner) {
-    defmt::info!("Hello world");
-    const HEAP_SIZE: usize = 4096;
-    static mut HEAP_MEM: [MaybeUninit<u8>; HEAP_SIZE] = [MaybeUninit::uninit(); HEAP_SIZE];
-    #[allow(static_mut_refs)]
-    unsafe {
-        HEAP.init(HEAP_MEM.as_ptr() as usize, HEAP_SIZE)
+    #[cfg(not(feature = "sim"))]
+    {
+        defmt::info!("Hello world");
+        // The emulated peripherals of the QEMU platform live on the heap as well. Elsewhere, the
+        // largest allocations are the modes' histories and the futures of modes reading blocks of
+        // samples.
+        const HEAP_SIZE: usize = if cfg!(feature = "qemu") {
+            32 * 1024
+        } else {
+            16 * 1024
+        };
+        static mut HEAP_MEM: [MaybeUninit<u8>; HEAP_SIZE] = [MaybeUninit::uninit(); HEAP_SIZE];
+        #[allow(static_mut_refs)]
+        unsafe {
+            HEAP.init(HEAP_MEM.as_ptr() as usize, HEAP_SIZE)
+        }
     }
 
-
     let platform = platform();
 
-    let (i2c, a0, a2, a3, d4, d5, d6) = platform.split();
+    let (i2c, a0, a2, a3, d4, d5, d6, sampler) = platform.split();
+    // Raw inputs shown by the scope mode
+    let scope_inputs = (a0.clone(), a2.clone(), a3.clone());
 
     let i2c = BlockingMutex::new(RefCell::new(i2c));
     let i2c = I2C_BUS.init(i2c);
@@ -102,15 +186,32 @@ async fn main(spawner: Spawner) {
         .expect("Failed to initialize LIS3DHTR");
 
     // Environment sensors
-    let dht20 = Dht20::new(I2cDevice::new(i2c), Delay);
-    let mut bmp280 = BME280::new_secondary(I2cDevice::new(i2c));
-    bmp280
-        .init(&mut Delay)
-        .expect("Failed to initialize BMP280)");
-
-    // Potentiometer input
-    let potentiometer = ReversedAnalogInput::new(a0);
-    let potentiometer: Arc<Mutex<CriticalSectionRawMutex, _>> = Arc::new(Mutex::new(potentiometer));
+    let sensors = SensorKitEnvSensors::init(I2cDevice::new(i2c), I2cDevice::new(i2c))
+        .await
+        .expect("Failed to initialize BMP280");
+
+    // Potentiometer input. Averaging the latest samples removes most of the noise, hysteresis keeps
+    // the value from flickering between neighbouring steps.
+    let potentiometer = ReversedAnalogInput::new(Hysteresis::new(Oversampled::<_, 16>::new(a0), 8));
+
+    let potentiometer_max = potentiometer
+        .max_value()
+        .await
+        .expect("Failed to read potentiometer range");
+
+    // Light sensor input, smoothed to follow changes in brightness calmly.
+    let light_sensor = ExponentialSmoothing::new(Oversampled::<_, 16>::new(a3), 0.3);
+    let light_max = light_sensor
+        .max_value()
+        .await
+        .expect("Failed to read light sensor range");
+
+    // Sensors are read by tasks of their own, the modes subscribe to their readings. Each user of
+    // the potentiometer needs its own subscription.
+    let subscribe = |channel: &'static AnalogReadings, max_value| {
+        LatestAnalogReading::new(channel, max_value).expect("Too many subscribers to analog input")
+    };
+    let subscribe_potentiometer = || subscribe(&POTENTIOMETER_READINGS, potentiometer_max);
 
     // PWM
     let pwm_led = d6;
@@ -130,62 +231,123 @@ async fn main(spawner: Spawner) {
     // Set up modes
 
     // Environment mode
-    let sensors = SensorKitEnvSensors::new(dht20, bmp280);
-    let environment_mode = EnvironmentMode::new(sensors);
+    let environment_readings =
+        LatestReading::new(&ENVIRONMENT_READINGS).expect("Too many subscribers to environment");
+    let environment_mode = EnvironmentMode::new(environment_readings, &ENVIRONMENT_TRENDS);
 
     // Potentiometer mode
-    let potentiometer_mode = PotentiometerMode::new(potentiometer.clone());
-
-    // Light sensor mode.
-    let light_sensor = a3;
-    let light_mode = LightSensorMode::new(light_sensor);
-
-    // Sound sensor mode.
+    let potentiometer_mode =
+        PotentiometerMode::new(subscribe_potentiometer(), &POTENTIOMETER_TREND);
+
+    // Light sensor mode, converting the readings to lux on a table calibration of the sensor.
+    let light_calibration = LIGHT_SENSOR
+        .calibration(light_max)
+        .expect("Light sensor resolution too low");
+    let light_mode = LightSensorMode::new(
+        subscribe(&LIGHT_READINGS, light_max),
+        light_calibration.clone(),
+        &LIGHT_TREND,
+    );
+
+    // Sound sensor mode, showing the sound level measured in a task of its own. The meter converts
+    // the samples to sound pressure, the offset doesn't matter as the level only depends on the
+    // amplitude.
     let sound_sensor = a2;
-    let sound_mode = SoundMode::new(sound_sensor);
+    let sound_max = sound_sensor
+        .max_value()
+        .await
+        .expect("Failed to read sound sensor range");
+    let sound_calibration =
+        Calibration::two_point((0, 0.0), (sound_max, sound_max as f32 * SOUND_PA_PER_COUNT))
+            .expect("Sound sensor resolution too low");
+    let sound_meter = SoundLevelMeter::new(sound_sensor.clone(), sound_calibration);
+    let sound_levels = LatestReading::new(&SOUND_LEVELS).expect("Too many subscribers to sound");
+    let sound_mode = SoundMode::new(sound_levels, &SOUND_TREND);
+
+    // Spectrum mode, analyzing the sound sensor as well.
+    let spectrum_mode = SpectrumMode::new(sound_sensor);
+
+    // Scope mode, with the potentiometer choosing the time base.
+    let (scope_a0, scope_a2, scope_a3) = scope_inputs;
+    let scope_mode = ScopeMode::new(subscribe_potentiometer())
+        .with_input("A0", scope_a0)
+        .with_input("A2", scope_a2)
+        .with_input("A3", scope_a3);
 
     // LED mode
-    let led_mode = LedMode::new(pwm_led, potentiometer.clone());
+    let led_mode = LedMode::new(pwm_led, subscribe_potentiometer());
 
     // Buzzer mode
-    let buzzer_mode = BuzzerMode::new(potentiometer.clone(), buzzer_pwm);
+    let buzzer_mode = BuzzerMode::new(subscribe_potentiometer(), buzzer_pwm);
 
     // Acceleration mode
-    let acceleration_mode = AccelerationMode::new(lis3dh);
+    let acceleration_readings =
+        LatestReading::new(&ACCELERATION_READINGS).expect("Too many subscribers to accelerometer");
+    let acceleration_mode = AccelerationMode::new(acceleration_readings, &ACCELERATION_TREND);
 
-    let mut modes: Vec<Box<dyn AppMode<_>>> = vec![
+    let modes: Vec<Box<dyn AppMode<_>>> = vec![
         Box::new(environment_mode),
         Box::new(potentiometer_mode),
         Box::new(acceleration_mode),
         Box::new(light_mode),
         Box::new(sound_mode),
+        Box::new(spectrum_mode),
+        Box::new(scope_mode),
         Box::new(led_mode),
         Box::new(buzzer_mode),
     ];
 
     // Spawn ancillary tasks
+    spawner.spawn(sampler_task(sampler)).unwrap();
+    spawner.spawn(environment_task(Box::new(sensors))).unwrap();
+    spawner.spawn(acceleration_task(Box::new(lis3dh))).unwrap();
+    spawner
+        .spawn(analog_task(
+            Box::new(potentiometer),
+            &POTENTIOMETER_READINGS,
+            POTENTIOMETER_INTERVAL,
+        ))
+        .unwrap();
+    spawner
+        .spawn(analog_task(
+            Box::new(light_sensor),
+            &LIGHT_READINGS,
+            LIGHT_INTERVAL,
+        ))
+        .unwrap();
+    spawner.spawn(sound_task(Box::new(sound_meter))).unwrap();
+    spawner.spawn(trend_task(light_calibration)).unwrap();
     spawner
         .spawn(button_handler(Box::new(button), &BUTTON_SIGNAL))
         .unwrap();
 
-    // Loop over all modes (.cycle() requires Clone, which dyn AppMode isn't)
-    loop {
-        for mode in modes.iter_mut() {
-            let title = mode.title();
-            _ = mode.enter().await;
+    // The potentiometer also selects entries of the mode menu
+    let mut manager = ModeManager::new(modes, subscribe_potentiometer());
+    _ = manager.start().await;
 
-            // Continuously run the active mode
-            loop {
-                // If the button handler signals us, switch to the next mode
-                if let Some(true) = &BUTTON_SIGNAL.try_take() {
-                    _ = mode.exit().await;
-                    break;
-                }
-
-                // Update state
-                mode.update().await;
+    // Continuously run the active mode, updating and redrawing it at its own intervals. Button
+    // events are handled right away, cancelling updates still running: slow sensor reads shouldn't
+    // delay switching modes.
+    let mut next_update = Instant::now();
+    let mut next_redraw = Instant::now();
+    loop {
+        let event = match select3(
+            BUTTON_SIGNAL.wait(),
+            Timer::at(next_update),
+            Timer::at(next_redraw),
+        )
+        .await
+        {
+            Either3::First(event) => Some(event),
+            Either3::Second(()) => {
+                next_update = Instant::now() + manager.update_interval();
+                manager.update_until(BUTTON_SIGNAL.wait()).await
+            }
+            Either3::Third(()) => {
+                next_redraw = Instant::now() + manager.redraw_interval();
 
                 // Set up frame with mode title
+                let title = manager.title();
                 let frame = TitleFrame::new(
                     &title,
                     app_style.title_style.clone(),
@@ -197,25 +359,111 @@ async fn main(spawner: Spawner) {
 
                 // Draw both frame and content
                 _ = frame.draw(&mut display);
-                _ = mode.draw_with_style(&app_style, inner_area, &mut display);
+                _ = manager.draw_with_style(&app_style, inner_area, &mut display);
                 display.flush_screen();
-
-                Timer::after_millis(100).await;
+                None
             }
+        };
+
+        // Update and redraw right away, to show the effect of the event without delay
+        if let Some(event) = event {
+            _ = manager.handle_event(event).await;
+            next_update = Instant::now();
+            next_redraw = Instant::now();
         }
     }
 }
 
 #[task]
-/// Task that signals button presses.
+/// Task that keeps sampling the analog inputs into the scan buffer.
+async fn sampler_task(sampler: Sampler) {
+    sampler.await
+}
+
+#[task]
+/// Task that keeps reading the environment sensors.
+async fn environment_task(mut sensors: Box<dyn EnvironmentSensors>) {
+    sample_environment(
+        sensors.as_mut(),
+        &ENVIRONMENT_READINGS,
+        ENVIRONMENT_INTERVAL,
+    )
+    .await
+}
+
+#[task]
+/// Task that keeps reading the accelerometer.
+async fn acceleration_task(mut accelerometer: Box<dyn AccelerationInput>) {
+    sample_acceleration(
+        accelerometer.as_mut(),
+        &ACCELERATION_READINGS,
+        ACCELERATION_INTERVAL,
+    )
+    .await
+}
+
+#[task(pool_size = 2)]
+/// Task that keeps reading an analog input.
+async fn analog_task(
+    mut input: Box<dyn AnalogInput>,
+    channel: &'static AnalogReadings,
+    interval: Duration,
+) {
+    sample_analog(input.as_mut(), channel, interval).await
+}
+
+#[task]
+/// Task that keeps measuring the sound level.
+async fn sound_task(mut meter: Box<dyn SoundLevelInput>) {
+    sample_sound_level(meter.as_mut(), &SOUND_LEVELS, SOUND_INTERVAL).await
+}
+
+#[task]
+/// Task that keeps recording the sensor readings into the trends shown by the modes, also while the
+/// modes aren't shown.
+async fn trend_task(light_calibration: Calibration) {
+    let environment = ENVIRONMENT_READINGS
+        .subscriber()
+        .expect("Too many subscribers to environment");
+    let acceleration = ACCELERATION_READINGS
+        .subscriber()
+        .expect("Too many subscribers to accelerometer");
+    let potentiometer = POTENTIOMETER_READINGS
+        .subscriber()
+        .expect("Too many subscribers to potentiometer");
+    let light = LIGHT_READINGS
+        .subscriber()
+        .expect("Too many subscribers to light sensor");
+    let sound = SOUND_LEVELS
+        .subscriber()
+        .expect("Too many subscribers to sound");
+
+    join5(
+        record(environment, |reading| ENVIRONMENT_TRENDS.push(&reading)),
+        record(acceleration, |reading| {
+            AccelerationMode::record(&ACCELERATION_TREND, &reading)
+        }),
+        record(potentiometer, |reading| {
+            PotentiometerMode::record(&POTENTIOMETER_TREND, &reading)
+        }),
+        record(light, |reading| {
+            LightSensorMode::record(&LIGHT_TREND, &light_calibration, &reading)
+        }),
+        record(sound, |reading| SoundMode::record(&SOUND_TREND, &reading)),
+    )
+    .await;
+}
+
+#[task]
+/// Task that signals the gestures made with the button.
 async fn button_handler(
-    mut button: Box<dyn DynSafeWait<Error = PinError> + 'static>,
-    signal: &'static Signal<CriticalSectionRawMutex, bool>,
+    button: Box<dyn DynSafeWait<Error = PinError> + Send + 'static>,
+    signal: &'static Signal<CriticalSectionRawMutex, ButtonEvent>,
 ) {
+    let mut driver = ButtonDriver::new(PinButton::new(button), ButtonTimings::default());
     loop {
-        if button.wait_for_high().await.is_ok() {
-            signal.signal(true);
+        if let Ok(event) = driver.next_event().await {
+            signal.signal(event);
         }
-        Timer::after_millis(200).await; // Interval before a new event is signaled
     }
 }
@@ -1,21 +0,0 @@
-/// Acceleration mode
-pub mod acceleration;
-/// Buzzer mode.
-pub mod buzzer;
-/// Environment mode.
-pub mod environment;
-/// LED mode.
-pub mod led;
-/// Light sensor mode.
-pub mod light;
-/// Potentiometer mode.
-pub mod potentiometer;
-/// Sound mode.
-pub mod sound;
-
-pub use acceleration::AccelerationMode;
-pub use environment::EnvironmentMode;
-pub use led::LedMode;
-pub use light::LightSensorMode;
-pub use potentiometer::PotentiometerMode;
-pub use sound::SoundMode;
@@ -0,0 +1,22 @@
+use alloc::sync::Arc;
+use embassy_time::{Duration, Ticker};
+use sensor_kit_core::peripherals::AdcReference;
+
+use super::script::{Channel, Script};
+use crate::platform::ScanBuffer;
+
+/// Reference of the emulated ADC, a 12 bit ADC supplied with 3.3V.
+const REFERENCE: AdcReference = AdcReference::new(4095, 3300);
+
+/// Sample the analog channels of `script` into `buffer`, at the buffer's sample rate. Values are
+/// 12 bit, like those of a real ADC.
+pub async fn scan(script: Arc<Script>, buffer: &'static ScanBuffer) {
+    buffer.set_reference(REFERENCE);
+    let mut ticker = Ticker::every(Duration::from_hz(buffer.sample_rate().to_Hz() as u64));
+    loop {
+        let scan = [Channel::A0, Channel::A2, Channel::A3]
+            .map(|channel| script.value(channel).clamp(0.0, 4095.0) as u16);
+        buffer.push(scan);
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,131 @@
+use alloc::sync::Arc;
+
+use super::{Device, Registers};
+use crate::platform::emulated::script::{Channel, Script};
+
+const CHIP_ID: u8 = 0x58;
+const REG_CHIP_ID: u8 = 0xd0;
+const REG_CALIBRATION: u8 = 0x88;
+const REG_PRESSURE: u8 = 0xf7;
+const REG_TEMPERATURE: u8 = 0xfa;
+const REG_HUMIDITY: u8 = 0xfd;
+
+/// Compensation parameters, taken from the example in the BMP280 datasheet.
+struct Calibration {
+    t: (u16, i16, i16),
+    p: (u16, i16, i16, i16, i16, i16, i16, i16, i16),
+}
+
+const CALIBRATION: Calibration = Calibration {
+    t: (27504, 26435, -1000),
+    p: (36477, -10685, 3024, 2855, 140, -7, 15500, -14600, 6000),
+};
+
+/// Simulated BMP280 pressure and temperature sensor.
+///
+/// Raw readings are derived from the scripted values by inverting the compensation formulas from
+/// the datasheet, so drivers applying the regular compensation end up with the scripted values.
+pub struct Bmp280 {
+    script: Arc<Script>,
+    registers: Registers,
+}
+
+impl Bmp280 {
+    pub fn new(script: Arc<Script>) -> Self {
+        let mut registers = Registers::new();
+        registers.set(REG_CHIP_ID, CHIP_ID);
+
+        let (t1, t2, t3) = CALIBRATION.t;
+        let (p1, p2, p3, p4, p5, p6, p7, p8, p9) = CALIBRATION.p;
+        let words = [
+            t1, t2 as u16, t3 as u16, p1, p2 as u16, p3 as u16, p4 as u16, p5 as u16, p6 as u16,
+            p7 as u16, p8 as u16, p9 as u16,
+        ];
+        for (i, word) in words.iter().enumerate() {
+            let [low, high] = word.to_le_bytes();
+            registers.set(REG_CALIBRATION + 2 * i as u8, low);
+            registers.set(REG_CALIBRATION + 2 * i as u8 + 1, high);
+        }
+
+        // The BMP280 has no humidity sensor and reports the "skipped" value instead
+        registers.set(REG_HUMIDITY, 0x80);
+        registers.set(REG_HUMIDITY + 1, 0x00);
+
+        let mut bmp280 = Self { script, registers };
+        bmp280.measure();
+        bmp280
+    }
+
+    fn measure(&mut self) {
+        let temperature = self.script.value(Channel::Temperature);
+        let pressure_pa = self.script.value(Channel::Pressure) * 1000.0;
+
+        let adc_t = invert(|adc| compensate_temperature(adc).0, temperature, true);
+        let t_fine = compensate_temperature(adc_t).1;
+        let adc_p = invert(|adc| compensate_pressure(adc, t_fine), pressure_pa, false);
+
+        for (register, adc) in [(REG_PRESSURE, adc_p), (REG_TEMPERATURE, adc_t)] {
+            self.registers.set(register, (adc >> 12) as u8);
+            self.registers.set(register + 1, (adc >> 4) as u8);
+            self.registers.set(register + 2, (adc << 4) as u8);
+        }
+    }
+}
+
+impl Device for Bmp280 {
+    fn write(&mut self, data: &[u8]) {
+        self.registers.write(data);
+        self.measure();
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) {
+        self.registers.read(buffer);
+    }
+}
+
+/// Compensated temperature in °C and the intermediate `t_fine` value for a raw reading.
+fn compensate_temperature(adc_t: u32) -> (f32, f32) {
+    let (t1, t2, t3) = CALIBRATION.t;
+    let (adc_t, t1, t2, t3) = (adc_t as f32, t1 as f32, t2 as f32, t3 as f32);
+
+    let var1 = (adc_t / 16384.0 - t1 / 1024.0) * t2;
+    let var2 = (adc_t / 131072.0 - t1 / 8192.0) * (adc_t / 131072.0 - t1 / 8192.0) * t3;
+    let t_fine = var1 + var2;
+    (t_fine / 5120.0, t_fine)
+}
+
+/// Compensated pressure in Pa for a raw reading.
+fn compensate_pressure(adc_p: u32, t_fine: f32) -> f32 {
+    let (p1, p2, p3, p4, p5, p6, p7, p8, p9) = CALIBRATION.p;
+    let [p1, p2, p3, p4, p5, p6, p7, p8, p9] = [
+        p1 as f32, p2 as f32, p3 as f32, p4 as f32, p5 as f32, p6 as f32, p7 as f32, p8 as f32,
+        p9 as f32,
+    ];
+
+    let mut var1 = t_fine / 2.0 - 64000.0;
+    let mut var2 = var1 * var1 * p6 / 32768.0;
+    var2 += var1 * p5 * 2.0;
+    var2 = var2 / 4.0 + p4 * 65536.0;
+    var1 = (p3 * var1 * var1 / 524288.0 + p2 * var1) / 524288.0;
+    var1 = (1.0 + var1 / 32768.0) * p1;
+
+    let mut p = 1048576.0 - adc_p as f32;
+    p = (p - var2 / 4096.0) * 6250.0 / var1;
+    var1 = p9 * p * p / 2147483648.0;
+    var2 = p * p8 / 32768.0;
+    p + (var1 + var2 + p7) / 16.0
+}
+
+/// Find the 20 bit raw reading for which a monotonic compensation function is closest to `target`.
+fn invert(compensate: impl Fn(u32) -> f32, target: f32, increasing: bool) -> u32 {
+    let (mut low, mut high) = (0u32, (1 << 20) - 1);
+    while low < high {
+        let mid = (low + high) / 2;
+        if (compensate(mid) < target) == increasing {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
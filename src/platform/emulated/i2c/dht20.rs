@@ -0,0 +1,78 @@
+use alloc::sync::Arc;
+
+use super::Device;
+use crate::platform::emulated::script::{Channel, Script};
+
+/// Command starting a measurement.
+const TRIGGER_MEASUREMENT: u8 = 0xac;
+/// Status byte of an idle, calibrated sensor.
+const STATUS_IDLE: u8 = 0x18;
+
+/// Simulated DHT20 temperature and humidity sensor.
+pub struct Dht20 {
+    script: Arc<Script>,
+    /// Status byte followed by the last measurement and its CRC.
+    response: [u8; 7],
+}
+
+impl Dht20 {
+    pub fn new(script: Arc<Script>) -> Self {
+        let mut dht20 = Self {
+            script,
+            response: [0; 7],
+        };
+        dht20.measure();
+        dht20
+    }
+
+    fn measure(&mut self) {
+        let humidity = self.script.value(Channel::Humidity).clamp(0.0, 100.0);
+        let temperature = self.script.value(Channel::Temperature).clamp(-50.0, 150.0);
+
+        // Both values are transmitted as 20 bit fractions of their full range
+        let full_scale = (1 << 20) as f32;
+        let humidity = ((humidity / 100.0 * full_scale) as u32).min(0xfffff);
+        let temperature = (((temperature + 50.0) / 200.0 * full_scale) as u32).min(0xfffff);
+
+        self.response = [
+            STATUS_IDLE,
+            (humidity >> 12) as u8,
+            (humidity >> 4) as u8,
+            ((humidity << 4) as u8) | ((temperature >> 16) as u8 & 0x0f),
+            (temperature >> 8) as u8,
+            temperature as u8,
+            0,
+        ];
+        self.response[6] = crc8(&self.response[..6]);
+    }
+}
+
+impl Device for Dht20 {
+    fn write(&mut self, data: &[u8]) {
+        if data.first() == Some(&TRIGGER_MEASUREMENT) {
+            self.measure();
+        }
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) {
+        buffer.fill(0);
+        let len = buffer.len().min(self.response.len());
+        buffer[..len].copy_from_slice(&self.response[..len]);
+    }
+}
+
+/// CRC-8 as used by the DHT20 (polynomial 0x31, initial value 0xff).
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0xffu8;
+    for byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x31
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
@@ -0,0 +1,72 @@
+use alloc::sync::Arc;
+
+use super::{Device, Registers};
+use crate::platform::emulated::script::{Channel, Script};
+
+const REG_WHO_AM_I: u8 = 0x0f;
+const REG_CTRL1: u8 = 0x20;
+const REG_CTRL4: u8 = 0x23;
+const REG_STATUS: u8 = 0x27;
+const REG_OUT_X_L: u8 = 0x28;
+
+const WHO_AM_I: u8 = 0x33;
+/// Data available on all axes.
+const STATUS_DATA_READY: u8 = 0x0f;
+/// Bit in the register address enabling auto-increment for multi-byte transfers.
+const AUTO_INCREMENT: u8 = 0x80;
+
+/// Simulated LIS3DH accelerometer.
+pub struct Lis3dh {
+    script: Arc<Script>,
+    registers: Registers,
+}
+
+impl Lis3dh {
+    pub fn new(script: Arc<Script>) -> Self {
+        let mut registers = Registers::new();
+        registers.set(REG_WHO_AM_I, WHO_AM_I);
+        registers.set(REG_CTRL1, 0x07);
+        registers.set(REG_STATUS, STATUS_DATA_READY);
+        Self { script, registers }
+    }
+
+    fn measure(&mut self) {
+        let low_power = self.registers.get(REG_CTRL1) & 0x08 != 0;
+        let ctrl4 = self.registers.get(REG_CTRL4);
+        let high_resolution = ctrl4 & 0x08 != 0;
+        let full_scale = ((ctrl4 >> 4) & 0x03) as usize;
+
+        // Sensitivity in mg/digit and output resolution, see LIS3DH datasheet table 4
+        let (sensitivity, bits) = match (low_power, high_resolution) {
+            (true, _) => ([16, 32, 64, 192][full_scale], 8),
+            (false, true) => ([1, 2, 4, 12][full_scale], 12),
+            (false, false) => ([4, 8, 16, 48][full_scale], 10),
+        };
+        let limit = (1i32 << (bits - 1)) - 1;
+
+        let axes = [Channel::AccelX, Channel::AccelY, Channel::AccelZ];
+        for (i, axis) in axes.into_iter().enumerate() {
+            let mg = self.script.value(axis) * 1000.0;
+            let counts = ((mg / sensitivity as f32) as i32).clamp(-limit - 1, limit);
+            // Output registers are left-justified
+            let [low, high] = ((counts << (16 - bits)) as i16).to_le_bytes();
+            self.registers.set(REG_OUT_X_L + 2 * i as u8, low);
+            self.registers.set(REG_OUT_X_L + 2 * i as u8 + 1, high);
+        }
+    }
+}
+
+impl Device for Lis3dh {
+    fn write(&mut self, data: &[u8]) {
+        let mut data = data.to_vec();
+        if let Some(address) = data.first_mut() {
+            *address &= !AUTO_INCREMENT;
+        }
+        self.registers.write(&data);
+        self.measure();
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) {
+        self.registers.read(buffer);
+    }
+}
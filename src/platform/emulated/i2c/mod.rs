@@ -0,0 +1,134 @@
+mod bmp280;
+mod dht20;
+mod lis3dh;
+mod ssd1315;
+
+pub use bmp280::Bmp280;
+pub use dht20::Dht20;
+pub use lis3dh::Lis3dh;
+pub use ssd1315::{Framebuffer, Ssd1315};
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ops::Range;
+use embedded_hal::i2c::{self, ErrorKind, NoAcknowledgeSource, Operation};
+
+/// A simulated device attached to the [`SimI2c`] bus.
+pub trait Device: Send {
+    /// Handle data written to the device.
+    fn write(&mut self, data: &[u8]);
+
+    /// Fill `buffer` with data read from the device.
+    fn read(&mut self, buffer: &mut [u8]);
+}
+
+#[derive(Debug)]
+/// Error returned by the simulated bus.
+pub struct SimI2cError(ErrorKind);
+
+impl i2c::Error for SimI2cError {
+    fn kind(&self) -> ErrorKind {
+        self.0
+    }
+}
+
+/// An I2C bus with simulated devices attached to it.
+#[derive(Default)]
+pub struct SimI2c {
+    devices: Vec<(u8, Box<dyn Device>)>,
+}
+
+impl SimI2c {
+    /// Create a new bus without any devices.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attach a device at the given 7-bit address.
+    pub fn attach(mut self, address: u8, device: impl Device + 'static) -> Self {
+        self.devices.push((address, Box::new(device)));
+        self
+    }
+}
+
+impl i2c::ErrorType for SimI2c {
+    type Error = SimI2cError;
+}
+
+impl i2c::I2c for SimI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let (_, device) =
+            self.devices
+                .iter_mut()
+                .find(|(a, _)| *a == address)
+                .ok_or(SimI2cError(ErrorKind::NoAcknowledge(
+                    NoAcknowledgeSource::Address,
+                )))?;
+
+        for operation in operations {
+            match operation {
+                Operation::Write(data) => device.write(data),
+                Operation::Read(buffer) => device.read(buffer),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Register file of a device using the common "write register address, then read or write data
+/// with auto-increment" access pattern.
+pub struct Registers {
+    values: [u8; 256],
+    pointer: u8,
+}
+
+impl Registers {
+    pub const fn new() -> Self {
+        Self {
+            values: [0; 256],
+            pointer: 0,
+        }
+    }
+
+    pub fn get(&self, register: u8) -> u8 {
+        self.values[register as usize]
+    }
+
+    pub fn set(&mut self, register: u8, value: u8) {
+        self.values[register as usize] = value;
+    }
+
+    /// Handle a write transfer. The first byte selects the register, the remaining bytes are
+    /// written starting at that register. Returns the range of registers written to.
+    pub fn write(&mut self, data: &[u8]) -> Range<usize> {
+        let Some((&pointer, values)) = data.split_first() else {
+            return 0..0;
+        };
+        self.pointer = pointer;
+
+        for value in values {
+            self.values[self.pointer as usize] = *value;
+            self.pointer = self.pointer.wrapping_add(1);
+        }
+
+        pointer as usize..pointer as usize + values.len()
+    }
+
+    pub fn read(&mut self, buffer: &mut [u8]) {
+        for byte in buffer {
+            *byte = self.values[self.pointer as usize];
+            self.pointer = self.pointer.wrapping_add(1);
+        }
+    }
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
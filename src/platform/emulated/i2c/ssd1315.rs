@@ -0,0 +1,289 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use super::Device;
+
+/// Width of the display in pixels.
+pub const WIDTH: usize = 128;
+/// Height of the display in pixels.
+pub const HEIGHT: usize = 64;
+const PAGES: usize = HEIGHT / 8;
+
+/// Content of the display, as the user would see it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Framebuffer {
+    /// Display RAM, one byte per column and page of 8 rows, least significant bit on top.
+    ram: [u8; WIDTH * PAGES],
+}
+
+impl Framebuffer {
+    const fn new() -> Self {
+        Self {
+            ram: [0; WIDTH * PAGES],
+        }
+    }
+
+    /// Number of lit pixels.
+    #[cfg(feature = "qemu")]
+    pub fn lit_pixels(&self) -> usize {
+        self.ram.iter().map(|byte| byte.count_ones() as usize).sum()
+    }
+
+    /// Whether the pixel at the given position is lit.
+    #[cfg(feature = "sim")]
+    pub fn pixel(&self, x: usize, y: usize) -> bool {
+        self.ram[(y / 8) * WIDTH + x] & (1 << (y % 8)) != 0
+    }
+
+    /// Encode as a plain (ASCII) PBM image.
+    #[cfg(feature = "sim")]
+    pub fn to_pbm(&self) -> Vec<u8> {
+        let mut pbm = Vec::new();
+        pbm.extend_from_slice(alloc::format!("P1\n{WIDTH} {HEIGHT}\n").as_bytes());
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                pbm.push(if self.pixel(x, y) { b'1' } else { b'0' });
+            }
+            pbm.push(b'\n');
+        }
+        pbm
+    }
+
+    /// Encode as an uncompressed 8 bit grayscale PNG image.
+    #[cfg(feature = "sim")]
+    pub fn to_png(&self) -> Vec<u8> {
+        // Scanlines, each starting with filter type 0 (none)
+        let mut raw = Vec::with_capacity(HEIGHT * (WIDTH + 1));
+        for y in 0..HEIGHT {
+            raw.push(0);
+            raw.extend((0..WIDTH).map(|x| if self.pixel(x, y) { 0xff } else { 0x00 }));
+        }
+
+        // zlib stream made up of stored (uncompressed) deflate blocks
+        let mut zlib = Vec::from([0x78, 0x01]);
+        let mut blocks = raw.chunks(u16::MAX as usize).peekable();
+        while let Some(block) = blocks.next() {
+            let is_final = blocks.peek().is_none();
+            let len = block.len() as u16;
+            zlib.push(is_final as u8);
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&(WIDTH as u32).to_be_bytes());
+        header.extend_from_slice(&(HEIGHT as u32).to_be_bytes());
+        // Bit depth 8, grayscale, default compression and filter method, no interlacing
+        header.extend_from_slice(&[8, 0, 0, 0, 0]);
+
+        let mut png = Vec::from(*b"\x89PNG\r\n\x1a\n");
+        for (kind, data) in [(b"IHDR", &header), (b"IDAT", &zlib), (b"IEND", &Vec::new())] {
+            png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            let start = png.len();
+            png.extend_from_slice(kind);
+            png.extend_from_slice(data);
+            let crc = crc32(&png[start..]);
+            png.extend_from_slice(&crc.to_be_bytes());
+        }
+        png
+    }
+}
+
+/// Addressing mode for display RAM writes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum AddressingMode {
+    Horizontal,
+    Vertical,
+    Page,
+}
+
+/// Simulated SSD1315 OLED display controller.
+///
+/// Interprets the SSD1306-compatible command set as far as needed to track the display RAM, and
+/// reports a frame each time the last byte of the display RAM has been written.
+pub struct Ssd1315 {
+    ram: Framebuffer,
+    mode: AddressingMode,
+    columns: (usize, usize),
+    pages: (usize, usize),
+    column: usize,
+    page: usize,
+    display_on: bool,
+    inverted: bool,
+    /// Partially received multi-byte command.
+    command: Vec<u8>,
+    on_frame: Box<dyn FnMut(&Framebuffer) + Send>,
+}
+
+impl Ssd1315 {
+    /// Create a new display calling `on_frame` with each completed frame.
+    pub fn new(on_frame: impl FnMut(&Framebuffer) + Send + 'static) -> Self {
+        Self {
+            ram: Framebuffer::new(),
+            mode: AddressingMode::Page,
+            columns: (0, WIDTH - 1),
+            pages: (0, PAGES - 1),
+            column: 0,
+            page: 0,
+            display_on: false,
+            inverted: false,
+            command: Vec::new(),
+            on_frame: Box::new(on_frame),
+        }
+    }
+
+    /// Number of bytes (including the command itself) of a command starting with `command`.
+    fn command_len(command: u8) -> usize {
+        match command {
+            0x26 | 0x27 => 7,
+            0x29 | 0x2a => 6,
+            0x21 | 0x22 | 0xa3 => 3,
+            0x20 | 0x81 | 0x8d | 0xa8 | 0xad | 0xd3 | 0xd5 | 0xd9 | 0xda | 0xdb => 2,
+            _ => 1,
+        }
+    }
+
+    fn command_byte(&mut self, byte: u8) {
+        self.command.push(byte);
+        if self.command.len() < Self::command_len(self.command[0]) {
+            return;
+        }
+
+        match *self.command.as_slice() {
+            [0x20, mode] => {
+                self.mode = match mode & 0x03 {
+                    0 => AddressingMode::Horizontal,
+                    1 => AddressingMode::Vertical,
+                    _ => AddressingMode::Page,
+                }
+            }
+            [0x21, start, end] => {
+                self.columns = (start as usize % WIDTH, end as usize % WIDTH);
+                self.column = self.columns.0;
+            }
+            [0x22, start, end] => {
+                self.pages = (start as usize % PAGES, end as usize % PAGES);
+                self.page = self.pages.0;
+            }
+            [low @ 0x00..=0x0f] => self.column = (self.column & 0xf0) | low as usize,
+            [high @ 0x10..=0x1f] => {
+                self.column = ((high as usize & 0x0f) << 4 | (self.column & 0x0f)) % WIDTH
+            }
+            [page @ 0xb0..=0xb7] => self.page = (page & 0x07) as usize,
+            [0xa6] => self.inverted = false,
+            [0xa7] => self.inverted = true,
+            [0xae] => self.display_on = false,
+            [0xaf] => self.display_on = true,
+            // Scrolling, contrast, timing and hardware configuration don't affect the content
+            _ => {}
+        }
+
+        self.command.clear();
+    }
+
+    fn data_byte(&mut self, byte: u8) {
+        self.ram.ram[self.page * WIDTH + self.column] = byte;
+        let frame_complete = self.page == PAGES - 1 && self.column == WIDTH - 1;
+
+        let (columns, pages) = (self.columns, self.pages);
+        match self.mode {
+            AddressingMode::Horizontal => {
+                if self.column < columns.1 {
+                    self.column += 1;
+                } else {
+                    self.column = columns.0;
+                    self.page = if self.page < pages.1 {
+                        self.page + 1
+                    } else {
+                        pages.0
+                    };
+                }
+            }
+            AddressingMode::Vertical => {
+                if self.page < pages.1 {
+                    self.page += 1;
+                } else {
+                    self.page = pages.0;
+                    self.column = if self.column < columns.1 {
+                        self.column + 1
+                    } else {
+                        columns.0
+                    };
+                }
+            }
+            AddressingMode::Page => self.column = (self.column + 1) % WIDTH,
+        }
+
+        if frame_complete {
+            let mut frame = self.ram.clone();
+            if !self.display_on {
+                frame.ram.fill(0);
+            } else if self.inverted {
+                frame.ram.iter_mut().for_each(|byte| *byte = !*byte);
+            }
+            (self.on_frame)(&frame);
+        }
+    }
+}
+
+impl Device for Ssd1315 {
+    fn write(&mut self, data: &[u8]) {
+        let mut bytes = data.iter().copied();
+
+        // Each transfer starts with a control byte. If its continuation bit is set, only the next
+        // byte is affected by it and another control byte follows.
+        while let Some(control) = bytes.next() {
+            let is_data = control & 0x40 != 0;
+            let continuation = control & 0x80 != 0;
+
+            let mut handle = |byte| {
+                if is_data {
+                    self.data_byte(byte)
+                } else {
+                    self.command_byte(byte)
+                }
+            };
+
+            if continuation {
+                if let Some(byte) = bytes.next() {
+                    handle(byte);
+                }
+            } else {
+                bytes.by_ref().for_each(handle);
+            }
+        }
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) {
+        // Status register: display on/off
+        buffer.fill(if self.display_on { 0x00 } else { 0x40 });
+    }
+}
+
+#[cfg(feature = "sim")]
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(feature = "sim")]
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
@@ -0,0 +1,76 @@
+//! Emulated sensor kit peripherals, shared by the platforms running without the real hardware.
+//!
+//! Analog inputs and the button follow a [`Script`], and the I2C devices (DHT20, BMP280, LIS3DH
+//! and the SSD1315 display) are emulated on register level, so the regular drivers and the main
+//! loop run unmodified.
+
+pub mod adc;
+pub mod i2c;
+pub mod script;
+
+use super::DynSafeWait;
+use script::{Channel, Script};
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use async_trait::async_trait;
+use core::convert::Infallible;
+use embassy_time::{Duration, Timer};
+
+/// Interval at which simulated digital inputs are polled.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Simulated digital input following a script channel.
+pub struct SimPin {
+    script: Arc<Script>,
+    channel: Channel,
+}
+
+impl SimPin {
+    pub fn new(script: Arc<Script>, channel: Channel) -> Self {
+        Self { script, channel }
+    }
+
+    fn is_high(&self) -> bool {
+        self.script.value(self.channel) >= 0.5
+    }
+
+    async fn wait_for_level(&self, high: bool) {
+        while self.is_high() != high {
+            Timer::after(POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[async_trait]
+impl DynSafeWait for SimPin {
+    type Error = Infallible;
+
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(true).await;
+        Ok(())
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(false).await;
+        Ok(())
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(false).await;
+        self.wait_for_level(true).await;
+        Ok(())
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(true).await;
+        self.wait_for_level(false).await;
+        Ok(())
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        let level = self.is_high();
+        self.wait_for_level(!level).await;
+        Ok(())
+    }
+}
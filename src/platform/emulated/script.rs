@@ -0,0 +1,195 @@
+use alloc::vec::Vec;
+use embassy_time::{Duration, Instant};
+use thiserror::Error;
+
+/// An input of the simulated sensor kit that can be driven by a script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    /// Potentiometer on A0, in raw ADC counts.
+    A0,
+    /// Sound sensor on A2, in raw ADC counts.
+    A2,
+    /// Light sensor on A3, in raw ADC counts.
+    A3,
+    /// Button on D4, 0 (released) or 1 (pressed).
+    D4,
+    /// DHT20/BMP280 temperature in °C.
+    Temperature,
+    /// DHT20 humidity in %.
+    Humidity,
+    /// BMP280 pressure in kPa.
+    Pressure,
+    /// LIS3DH acceleration along the x axis in g.
+    AccelX,
+    /// LIS3DH acceleration along the y axis in g.
+    AccelY,
+    /// LIS3DH acceleration along the z axis in g.
+    AccelZ,
+}
+
+impl Channel {
+    const COUNT: usize = 10;
+
+    fn from_name(name: &str) -> Option<Self> {
+        let channel = match name {
+            "a0" => Self::A0,
+            "a2" => Self::A2,
+            "a3" => Self::A3,
+            "d4" => Self::D4,
+            "temperature" => Self::Temperature,
+            "humidity" => Self::Humidity,
+            "pressure" => Self::Pressure,
+            "accel_x" => Self::AccelX,
+            "accel_y" => Self::AccelY,
+            "accel_z" => Self::AccelZ,
+            _ => return None,
+        };
+        Some(channel)
+    }
+
+    /// Value used while the script doesn't mention the channel.
+    fn default_value(self) -> f32 {
+        match self {
+            Self::A0 => 2048.0,
+            Self::A2 => 1000.0,
+            Self::A3 => 700.0,
+            Self::D4 => 0.0,
+            Self::Temperature => 21.0,
+            Self::Humidity => 45.0,
+            Self::Pressure => 101.3,
+            Self::AccelX => 0.0,
+            Self::AccelY => 0.0,
+            Self::AccelZ => 1.0,
+        }
+    }
+
+    /// Whether values between two points are interpolated, or the previous value is held.
+    fn is_interpolated(self) -> bool {
+        self != Self::D4
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("line {line}: {message}")]
+/// Error encountered while parsing a script.
+pub struct ScriptError {
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Description of the problem.
+    pub message: &'static str,
+}
+
+/// A point of a channel's time series.
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    time_ms: u64,
+    value: f32,
+}
+
+/// Time series for all simulated inputs.
+///
+/// Scripts are plain text, one entry per line, in the form `<time in ms> <channel> <value>`.
+/// Values of analog channels are interpolated linearly between points, `d4` holds its last value.
+/// `<time in ms> end` stops the simulator. Everything after a `#` is a comment.
+///
+/// ```text
+/// # Turn the potentiometer all the way, then press the button.
+/// 0     a0  0
+/// 2000  a0  4095
+/// 2500  d4  1
+/// 2600  d4  0
+/// 5000  end
+/// ```
+pub struct Script {
+    series: [Vec<Point>; Channel::COUNT],
+    end_ms: Option<u64>,
+    start: Instant,
+}
+
+impl Script {
+    /// An empty script, all channels keep their default values forever.
+    pub fn empty() -> Self {
+        Self {
+            series: Default::default(),
+            end_ms: None,
+            start: Instant::now(),
+        }
+    }
+
+    /// Parse a script. Its time base starts now.
+    pub fn parse(source: &str) -> Result<Self, ScriptError> {
+        let mut script = Self::empty();
+
+        for (index, line) in source.lines().enumerate() {
+            let error = |message| ScriptError {
+                line: index + 1,
+                message,
+            };
+
+            let line = line.split('#').next().unwrap_or_default();
+            let mut fields = line.split_whitespace();
+
+            let Some(time) = fields.next() else {
+                continue;
+            };
+            let time_ms = time.parse().map_err(|_| error("invalid time"))?;
+
+            match fields.next() {
+                Some("end") => script.end_ms = Some(time_ms),
+                Some(name) => {
+                    let channel = Channel::from_name(name).ok_or(error("unknown channel"))?;
+                    let value = fields
+                        .next()
+                        .ok_or(error("missing value"))?
+                        .parse()
+                        .map_err(|_| error("invalid value"))?;
+                    script.series[channel as usize].push(Point { time_ms, value });
+                }
+                None => return Err(error("missing channel")),
+            }
+
+            if fields.next().is_some() {
+                return Err(error("unexpected trailing input"));
+            }
+        }
+
+        for series in script.series.iter_mut() {
+            series.sort_by_key(|point| point.time_ms);
+        }
+
+        Ok(script)
+    }
+
+    /// Time after which the simulator should stop, if any.
+    pub fn end(&self) -> Option<Duration> {
+        self.end_ms.map(Duration::from_millis)
+    }
+
+    /// Current value of a channel.
+    pub fn value(&self, channel: Channel) -> f32 {
+        let elapsed = Instant::now() - self.start;
+        self.value_at(channel, elapsed.as_millis())
+    }
+
+    /// Value of a channel at a specific point in time, in ms since the script was started.
+    pub fn value_at(&self, channel: Channel, time_ms: u64) -> f32 {
+        let series = &self.series[channel as usize];
+
+        // Index of the first point that lies in the future
+        let next = series.partition_point(|point| point.time_ms <= time_ms);
+
+        match (next.checked_sub(1).map(|i| series[i]), series.get(next)) {
+            (None, None) => channel.default_value(),
+            (None, Some(next)) => next.value,
+            (Some(previous), None) => previous.value,
+            (Some(previous), Some(next)) => {
+                if !channel.is_interpolated() {
+                    return previous.value;
+                }
+                let span = (next.time_ms - previous.time_ms) as f32;
+                let t = (time_ms - previous.time_ms) as f32 / span;
+                previous.value + (next.value - previous.value) * t
+            }
+        }
+    }
+}
@@ -1,14 +1,69 @@
+#[cfg(any(feature = "sim", feature = "qemu"))]
+pub mod emulated;
+#[cfg(feature = "nrf52840")]
+pub mod nrf52840;
 #[cfg(feature = "nucleo-f413zh")]
 pub mod nucleo_f413zh;
+#[cfg(feature = "nucleo-l476rg")]
+pub mod nucleo_l476rg;
+#[cfg(feature = "qemu")]
+pub mod qemu;
+#[cfg(any(feature = "rp-pico", feature = "rp2040"))]
+pub mod rp;
+#[cfg(feature = "rp2040")]
+pub mod rp2040;
 #[cfg(feature = "rp-pico")]
 pub mod rp_pico;
+#[cfg(feature = "sim")]
+pub mod sim;
+#[cfg(any(feature = "nucleo-f413zh", feature = "nucleo-l476rg"))]
+pub mod stm32;
 
-use crate::peripherals::AnalogInput;
-use crate::peripherals::Pwm;
+use sensor_kit_core::peripherals::AnalogInput;
+use sensor_kit_core::peripherals::ButtonInput;
+use sensor_kit_core::peripherals::PeripheralError;
+use sensor_kit_core::peripherals::Pwm;
+use sensor_kit_core::peripherals::ScanChannel;
 
 use alloc::boxed::Box;
 use async_trait::async_trait;
+use core::future::Future;
+use core::pin::Pin;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embedded_hal::i2c::I2c;
+use fugit::HertzU32;
+use static_cell::StaticCell;
+
+/// Number of scanned analog inputs: A0, A2 and A3, in this order.
+pub const SCAN_CHANNELS: usize = 3;
+/// Number of scans kept for reading blocks of samples.
+pub const SCAN_LEN: usize = 256;
+
+/// Ring buffer the analog inputs are continuously sampled into.
+pub type ScanBuffer =
+    sensor_kit_core::peripherals::ScanBuffer<CriticalSectionRawMutex, SCAN_CHANNELS, SCAN_LEN>;
+/// Analog input read from the [`ScanBuffer`].
+pub type ScanInput = ScanChannel<'static, CriticalSectionRawMutex, SCAN_CHANNELS, SCAN_LEN>;
+/// Background work filling the [`ScanBuffer`], run in its own task.
+pub type Sampler = Pin<Box<dyn Future<Output = ()>>>;
+
+static SCAN_BUFFER: StaticCell<ScanBuffer> = StaticCell::new();
+
+/// Maximum raw value of the scanned inputs, the full scale of a 12 bit ADC.
+const SCAN_MAX_VALUE: u16 = 4096;
+
+/// Set up the scan buffer for scans taken at `sample_rate`, and create the inputs A0, A2 and A3
+/// reading from it. The inputs cover the full range of the ADC, sensors that don't are calibrated
+/// by the application.
+pub fn scan_inputs(
+    sample_rate: HertzU32,
+) -> (&'static ScanBuffer, ScanInput, ScanInput, ScanInput) {
+    let buffer = SCAN_BUFFER.init(ScanBuffer::new(sample_rate));
+    let a0 = buffer.channel(0, SCAN_MAX_VALUE);
+    let a2 = buffer.channel(1, SCAN_MAX_VALUE);
+    let a3 = buffer.channel(2, SCAN_MAX_VALUE);
+    (buffer, a0, a2, a3)
+}
 
 pub struct Platform<I2C, AIN, PWM, PIN>
 where
@@ -24,6 +79,7 @@ where
     pub d4: PIN,
     pub d5: PWM,
     pub d6: PWM,
+    pub sampler: Sampler,
 }
 
 impl<I2C, AIN, PWM, PIN> Platform<I2C, AIN, PWM, PIN>
@@ -33,7 +89,17 @@ where
     PWM: Pwm,
     PIN: DynSafeWait,
 {
-    pub fn new(i2c: I2C, a0: AIN, a2: AIN, a3: AIN, d4: PIN, d5: PWM, d6: PWM) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        i2c: I2C,
+        a0: AIN,
+        a2: AIN,
+        a3: AIN,
+        d4: PIN,
+        d5: PWM,
+        d6: PWM,
+        sampler: Sampler,
+    ) -> Self {
         Self {
             i2c,
             a0,
@@ -42,12 +108,21 @@ where
             d4,
             d5,
             d6,
+            sampler,
         }
     }
 
-    pub fn split(self) -> (I2C, AIN, AIN, AIN, PIN, PWM, PWM) {
+    #[allow(clippy::type_complexity)]
+    pub fn split(self) -> (I2C, AIN, AIN, AIN, PIN, PWM, PWM, Sampler) {
         (
-            self.i2c, self.a0, self.a2, self.a3, self.d4, self.d5, self.d6,
+            self.i2c,
+            self.a0,
+            self.a2,
+            self.a3,
+            self.d4,
+            self.d5,
+            self.d6,
+            self.sampler,
         )
     }
 }
@@ -62,3 +137,54 @@ pub trait DynSafeWait {
     async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error>;
     async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error>;
 }
+
+#[async_trait]
+impl<T: DynSafeWait + Send + ?Sized> DynSafeWait for Box<T> {
+    type Error = T::Error;
+
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        (**self).wait_for_high().await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        (**self).wait_for_low().await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        (**self).wait_for_rising_edge().await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        (**self).wait_for_falling_edge().await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        (**self).wait_for_any_edge().await
+    }
+}
+
+/// Button on a digital input, pressed while the input is high.
+pub struct PinButton<P>(P);
+
+impl<P: DynSafeWait + Send> PinButton<P> {
+    pub fn new(pin: P) -> Self {
+        Self(pin)
+    }
+}
+
+#[async_trait]
+impl<P: DynSafeWait + Send> ButtonInput for PinButton<P> {
+    async fn wait_for_press(&mut self) -> Result<(), PeripheralError> {
+        self.0
+            .wait_for_high()
+            .await
+            .map_err(|_| PeripheralError::Gpio)
+    }
+
+    async fn wait_for_release(&mut self) -> Result<(), PeripheralError> {
+        self.0
+            .wait_for_low()
+            .await
+            .map_err(|_| PeripheralError::Gpio)
+    }
+}
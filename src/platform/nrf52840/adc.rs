@@ -0,0 +1,29 @@
+use embassy_nrf::saadc::Saadc;
+use embassy_time::{Duration, Ticker};
+use fugit::HertzU32;
+use sensor_kit_core::peripherals::AdcReference;
+
+use crate::platform::{ScanBuffer, SCAN_CHANNELS};
+
+/// Rate at which the inputs are sampled.
+pub const SAMPLE_RATE: HertzU32 = HertzU32::kHz(1);
+/// Reference of the channels, which use the internal 0.6V reference and a gain of 1/6. Unlike a
+/// reference derived from the supply, it doesn't change with the supply voltage.
+const REFERENCE: AdcReference = AdcReference::new(4096, 3600);
+
+/// Sample all channels of the SAADC into `buffer` at [`SAMPLE_RATE`].
+///
+/// The SAADC samples all of its configured channels at once, writing them to memory via EasyDMA,
+/// so each tick yields a complete scan. The SAADC calibrates its offset before the first scan.
+pub async fn scan(mut saadc: Saadc<'static, SCAN_CHANNELS>, buffer: &'static ScanBuffer) {
+    saadc.calibrate().await;
+    buffer.set_reference(REFERENCE);
+    let mut ticker = Ticker::every(Duration::from_hz(SAMPLE_RATE.to_Hz() as u64));
+    loop {
+        let mut samples = [0; SCAN_CHANNELS];
+        saadc.sample(&mut samples).await;
+        // Single ended measurements can end up slightly below zero due to noise
+        buffer.push(samples.map(|sample| sample.max(0) as u16));
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,90 @@
+mod adc;
+mod pwm;
+
+use super::{scan_inputs, DynSafeWait, Platform, ScanInput};
+use pwm::PwmPin;
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use embassy_nrf::{
+    bind_interrupts,
+    gpio::{self, Input},
+    peripherals::{self, TWISPI0},
+    pwm::SimplePwm,
+    saadc::{self, ChannelConfig, Saadc},
+    twim::{self, Twim},
+};
+use embedded_hal_async::digital::Wait;
+use sensor_kit_core::peripherals::Pwm;
+
+bind_interrupts!(struct Irqs {
+    TWISPI0 => twim::InterruptHandler<peripherals::TWISPI0>;
+    SAADC => saadc::InterruptHandler;
+});
+
+pub type I2c<'a> = Twim<'a, TWISPI0>;
+pub type PinError = core::convert::Infallible;
+
+/// Platform for the nRF52840-DK, using the pins of its Arduino header:
+///
+/// | Header | nRF52840 | Peripheral    |
+/// | ------ | -------- | ------------- |
+/// | SDA    | P0.26    | TWIM0         |
+/// | SCL    | P0.27    | TWIM0         |
+/// | A0     | P0.03    | SAADC (AIN1)  |
+/// | A2     | P0.28    | SAADC (AIN4)  |
+/// | A3     | P0.29    | SAADC (AIN5)  |
+/// | D4     | P1.05    | GPIOTE        |
+/// | D5     | P1.06    | PWM0          |
+/// | D6     | P1.07    | PWM1          |
+pub fn platform<'a>() -> Platform<I2c<'a>, ScanInput, Box<dyn Pwm + Send + 'a>, Input<'a>> {
+    let p = embassy_nrf::init(Default::default());
+
+    let mut i2c_config = twim::Config::default();
+    i2c_config.frequency = twim::Frequency::K400;
+    let i2c = Twim::new(p.TWISPI0, Irqs, p.P0_26, p.P0_27, i2c_config);
+
+    let mut adc_config = saadc::Config::default();
+    adc_config.resolution = saadc::Resolution::_12BIT;
+    let channels = [
+        ChannelConfig::single_ended(p.P0_03),
+        ChannelConfig::single_ended(p.P0_28),
+        ChannelConfig::single_ended(p.P0_29),
+    ];
+    let saadc = Saadc::new(p.SAADC, Irqs, adc_config, channels);
+
+    let (scan_buffer, a0, a2, a3) = scan_inputs(adc::SAMPLE_RATE);
+    let sampler = Box::pin(adc::scan(saadc, scan_buffer));
+
+    let d4 = Input::new(p.P1_05, gpio::Pull::Down);
+
+    let d5: Box<dyn Pwm + Send> = Box::new(PwmPin::new(SimplePwm::new_1ch(p.PWM0, p.P1_06)));
+    let d6: Box<dyn Pwm + Send> = Box::new(PwmPin::new(SimplePwm::new_1ch(p.PWM1, p.P1_07)));
+
+    Platform::new(i2c, a0, a2, a3, d4, d5, d6, sampler)
+}
+
+#[async_trait]
+impl DynSafeWait for Input<'_> {
+    type Error = core::convert::Infallible;
+
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_high(self).await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_low(self).await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_rising_edge(self).await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_falling_edge(self).await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_any_edge(self).await
+    }
+}
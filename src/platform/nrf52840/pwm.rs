@@ -0,0 +1,128 @@
+use sensor_kit_core::peripherals::{DutyCycle, PeripheralError, Pwm, PwmSettings};
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use embassy_nrf::pwm::{Instance, Prescaler, SimplePwm};
+use fugit::HertzU32;
+
+/// Base clock of the PWM peripherals.
+const PWM_CLOCK_HZ: u32 = 16_000_000;
+/// Largest value of the 15 bit PWM counter.
+const COUNTER_TOP_MAX: u32 = 0x7fff;
+
+/// A single channel PWM output using a whole PWM instance.
+pub struct PwmPin<'a, T>
+where
+    T: Instance,
+{
+    pwm: SimplePwm<'a, T>,
+    /// Duty cycle in percent, reapplied whenever the frequency changes.
+    duty_cycle_pct: u8,
+    /// Achieved frequency, if set.
+    frequency: Option<HertzU32>,
+    /// Whether the output is enabled.
+    enabled: bool,
+}
+
+impl<'a, T> PwmPin<'a, T>
+where
+    T: Instance,
+{
+    pub fn new(pwm: SimplePwm<'a, T>) -> Self {
+        Self {
+            pwm,
+            duty_cycle_pct: 0,
+            frequency: None,
+            enabled: false,
+        }
+    }
+
+    fn apply_duty_cycle(&mut self) -> DutyCycle {
+        let duty_cycle = self.duty_cycle();
+        // `SimplePwm` duty values are inverted, they specify the time the output is low.
+        self.pwm
+            .set_duty(0, (duty_cycle.steps - duty_cycle.high) as u16);
+        duty_cycle
+    }
+
+    fn duty_cycle(&self) -> DutyCycle {
+        let steps = self.pwm.max_duty() as u32;
+        DutyCycle {
+            high: steps * self.duty_cycle_pct as u32 / 100,
+            steps,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> Pwm for PwmPin<'_, T>
+where
+    T: Instance + Send,
+{
+    async fn set_duty_cycle_percent(&mut self, percent: u8) -> Result<DutyCycle, PeripheralError> {
+        if percent > 100 {
+            return Err(PeripheralError::Pwm);
+        }
+        self.duty_cycle_pct = percent;
+        Ok(self.apply_duty_cycle())
+    }
+
+    async fn set_frequency(&mut self, freq: HertzU32) -> Result<HertzU32, PeripheralError> {
+        let freq_hz = freq.to_Hz();
+        if freq_hz == 0 {
+            return Err(PeripheralError::Pwm);
+        }
+
+        // Use the fastest clock that still lets the 15 bit counter reach the requested period,
+        // for the best possible duty cycle resolution.
+        let prescalers = [
+            Prescaler::Div1,
+            Prescaler::Div2,
+            Prescaler::Div4,
+            Prescaler::Div8,
+            Prescaler::Div16,
+            Prescaler::Div32,
+            Prescaler::Div64,
+            Prescaler::Div128,
+        ];
+        let (shift, prescaler) = prescalers
+            .into_iter()
+            .enumerate()
+            .find(|(shift, _)| (PWM_CLOCK_HZ >> shift) / freq_hz <= COUNTER_TOP_MAX)
+            .ok_or(PeripheralError::Pwm)?;
+        let clock_hz = PWM_CLOCK_HZ >> shift;
+        // The counter counts whole clock cycles, so it can't go faster than the clock.
+        let counter_top = clock_hz / freq_hz;
+        if counter_top == 0 {
+            return Err(PeripheralError::Pwm);
+        }
+
+        self.pwm.set_prescaler(prescaler);
+        self.pwm.set_period(freq_hz);
+        self.apply_duty_cycle();
+
+        let achieved = HertzU32::Hz(clock_hz / counter_top);
+        self.frequency = Some(achieved);
+        Ok(achieved)
+    }
+
+    async fn enable(&mut self) -> Result<(), PeripheralError> {
+        self.pwm.enable();
+        self.enabled = true;
+        Ok(())
+    }
+
+    async fn disable(&mut self) -> Result<(), PeripheralError> {
+        self.pwm.disable();
+        self.enabled = false;
+        Ok(())
+    }
+
+    async fn settings(&mut self) -> Result<PwmSettings, PeripheralError> {
+        Ok(PwmSettings {
+            frequency: self.frequency,
+            duty_cycle: self.duty_cycle(),
+            enabled: self.enabled,
+        })
+    }
+}
@@ -1,56 +1,46 @@
-use crate::peripherals::{AnalogInput, PeripheralError};
+use embassy_stm32::adc::RingBufferedAdc;
+use embassy_stm32::peripherals::ADC1;
 
-use alloc::boxed::Box;
-use alloc::sync::Arc;
-use async_trait::async_trait;
-use embassy_stm32::adc::{self, Adc as HalAdc, AnyAdcChannel};
-use embassy_sync::{blocking_mutex::raw::RawMutex, mutex::Mutex};
+use crate::platform::stm32::vrefint_reference;
+use crate::platform::{ScanBuffer, SCAN_CHANNELS};
 
-/// An analog input measured by an ADC.
-pub struct Adc<'a, ADC, M>
-where
-    ADC: adc::Instance,
-    M: RawMutex + Sync + Send,
-{
-    /// ADC measuring the signal.
-    adc: Arc<Mutex<M, HalAdc<'a, ADC>>>,
-    /// ADC channel.
-    channel: AnyAdcChannel<ADC>,
-    /// Maximum signal value, used for computing relative value.
-    max_value: u16,
-}
+/// Number of channels in the regular sequence: the scanned inputs, followed by VREFINT.
+pub const SEQUENCE_LEN: usize = SCAN_CHANNELS + 1;
+/// Length of the DMA ring buffer in samples. Each half holds a whole number of sequences.
+pub const DMA_BUFFER_LEN: usize = 2 * 32 * SEQUENCE_LEN;
+/// ADC cycles per conversion, i.e. the sampling time of 480 cycles plus 12 cycles for converting.
+pub const CYCLES_PER_CONVERSION: u32 = 480 + 12;
 
-impl<'a, ADC, M> Adc<'a, ADC, M>
-where
-    ADC: adc::Instance,
-    M: RawMutex + Sync + Send,
-{
-    /// Create a new input instance.
-    pub fn new(
-        adc: Arc<Mutex<M, HalAdc<'a, ADC>>>,
-        channel: AnyAdcChannel<ADC>,
-        max_value: Option<u16>,
-    ) -> Self {
-        Self {
-            adc,
-            channel,
-            max_value: max_value.unwrap_or(4096),
-        }
-    }
-}
+/// Address of VREFINT_CAL on the STM32F413.
+const VREFINT_CAL_ADDRESS: usize = 0x1fff_7a2a;
+/// VDDA at which VREFINT_CAL was taken.
+const VREFINT_CAL_MV: u16 = 3300;
 
-#[async_trait]
-impl<ADC, M> AnalogInput for Adc<'_, ADC, M>
-where
-    ADC: adc::Instance + Send + Sync,
-    M: RawMutex + Send + Sync,
-{
-    async fn input_raw(&mut self) -> Result<u16, PeripheralError> {
-        let mut adc = self.adc.lock().await;
-        Ok(adc.blocking_read(&mut self.channel))
-    }
-
-    async fn max_value(&self) -> Result<u16, PeripheralError> {
-        Ok(self.max_value)
+/// Continuously move the samples of a free-running ADC scan into `buffer`.
+///
+/// The ADC converts its regular sequence in continuous mode, while DMA writes the results into a
+/// ring buffer. Each read waits for half of that ring buffer to fill up. The VREFINT readings of
+/// each block update the reference of `buffer`.
+pub async fn scan(mut adc: RingBufferedAdc<'static, ADC1>, buffer: &'static ScanBuffer) {
+    let mut samples = [0; DMA_BUFFER_LEN / 2];
+    loop {
+        match adc.read(&mut samples).await {
+            Ok(_) => {
+                let mut vrefint_sum = 0;
+                for sequence in samples.chunks_exact(SEQUENCE_LEN) {
+                    let (scan, vrefint) = sequence.split_at(SCAN_CHANNELS);
+                    buffer.push_interleaved(scan);
+                    vrefint_sum += vrefint[0] as u32;
+                }
+                let vrefint = vrefint_sum / (samples.len() / SEQUENCE_LEN) as u32;
+                if let Some(reference) =
+                    vrefint_reference(VREFINT_CAL_ADDRESS, VREFINT_CAL_MV, vrefint as u16)
+                {
+                    buffer.set_reference(reference);
+                }
+            }
+            // The ADC is restarted by the next read, from the start of the sequence
+            Err(_) => defmt::warn!("ADC overrun, samples lost"),
+        }
     }
 }
@@ -1,21 +1,20 @@
 mod adc;
-mod pwm;
 
-use super::{DynSafeWait, Platform};
-use adc::Adc;
-use pwm::SharedPwm;
+use super::stm32::pwm::SharedPwm;
+use super::{scan_inputs, Platform, ScanInput};
 
+use adc::{scan, CYCLES_PER_CONVERSION, DMA_BUFFER_LEN, SEQUENCE_LEN};
 use alloc::boxed::Box;
 use alloc::sync::Arc;
-use async_trait::async_trait;
 use embassy_stm32::{
-    adc::{Adc as HalAdc, AdcChannel},
+    adc::{Adc as HalAdc, SampleTime, Sequence},
     bind_interrupts,
     exti::ExtiInput,
     gpio,
     i2c::{self, I2c as HalI2c},
     mode::Async,
-    peripherals::{ADC1, TIM1},
+    peripherals::TIM1,
+    rcc,
     time::Hertz,
     timer::{
         self,
@@ -24,7 +23,8 @@ use embassy_stm32::{
     },
 };
 use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex};
-use embedded_hal_async::digital::Wait;
+use fugit::HertzU32;
+use static_cell::StaticCell;
 
 bind_interrupts!(struct Irqs {
     I2C1_ER => i2c::ErrorInterruptHandler<embassy_stm32::peripherals::I2C1>;
@@ -34,13 +34,16 @@ bind_interrupts!(struct Irqs {
 pub type I2c<'a> = HalI2c<'a, Async>;
 pub type PinError = core::convert::Infallible;
 
+/// Ring buffer the ADC scan is written into by DMA.
+static ADC_DMA_BUFFER: StaticCell<[u16; DMA_BUFFER_LEN]> = StaticCell::new();
+
 pub fn platform<'a>() -> Platform<
     HalI2c<'a, Async>,
-    Adc<'a, ADC1, CriticalSectionRawMutex>,
+    ScanInput,
     SharedPwm<'a, CriticalSectionRawMutex, TIM1>,
     ExtiInput<'a>,
 > {
-    let p = embassy_stm32::init(Default::default());
+    let mut p = embassy_stm32::init(Default::default());
 
     let i2c1 = I2c::new(
         p.I2C1,
@@ -54,17 +57,25 @@ pub fn platform<'a>() -> Platform<
     );
 
     let adc = HalAdc::new(p.ADC1);
-    let adc: Arc<Mutex<CriticalSectionRawMutex, _>> = Arc::new(Mutex::new(adc));
-
-    let a0_adc_channel = p.PA3.degrade_adc();
-    let a2_adc_channel = p.PC3.degrade_adc();
-    let a3_adc_channel = p.PC1.degrade_adc();
-
-    let a0 = Adc::new(adc.clone(), a0_adc_channel, None);
-
-    let a2 = Adc::new(adc.clone(), a2_adc_channel, Some(2800));
-
-    let a3 = Adc::new(adc.clone(), a3_adc_channel, Some(1500));
+    let mut vrefint = adc.enable_vrefint();
+    let mut adc = adc.into_ring_buffered(p.DMA2_CH0, ADC_DMA_BUFFER.init([0; DMA_BUFFER_LEN]));
+    adc.set_sample_sequence(Sequence::One, &mut p.PA3, SampleTime::CYCLES480);
+    adc.set_sample_sequence(Sequence::Two, &mut p.PC3, SampleTime::CYCLES480);
+    adc.set_sample_sequence(Sequence::Three, &mut p.PC1, SampleTime::CYCLES480);
+    // Measured along with the inputs, to determine the reference voltage VDDA
+    adc.set_sample_sequence(Sequence::Four, &mut vrefint, SampleTime::CYCLES480);
+
+    // The ADC is clocked from APB2, divided down to no more than 36 MHz
+    let pclk2 = rcc::clocks(&p.RCC).pclk2.to_hertz().unwrap().0;
+    let prescaler = [2, 4, 6, 8]
+        .into_iter()
+        .find(|div| pclk2 / div <= 36_000_000)
+        .unwrap_or(8);
+    let adc_clock = pclk2 / prescaler;
+    let sample_rate = HertzU32::Hz(adc_clock / (CYCLES_PER_CONVERSION * SEQUENCE_LEN as u32));
+
+    let (scan_buffer, a0, a2, a3) = scan_inputs(sample_rate);
+    let sampler = Box::pin(scan(adc, scan_buffer));
 
     let d4 = ExtiInput::new(p.PF14, p.EXTI14, gpio::Pull::Down);
 
@@ -82,33 +93,10 @@ pub fn platform<'a>() -> Platform<
     );
     let pwm: Arc<Mutex<CriticalSectionRawMutex, _>> = Arc::new(Mutex::new(pwm));
 
-    let d5 = SharedPwm::new(pwm.clone(), timer::Channel::Ch2);
-    let d6 = SharedPwm::new(pwm.clone(), timer::Channel::Ch1);
-
-    Platform::new(i2c1, a0, a2, a3, d4, d5, d6)
-}
-
-#[async_trait]
-impl<'a> DynSafeWait for ExtiInput<'a> {
-    type Error = <ExtiInput<'a> as embedded_hal::digital::ErrorType>::Error;
-
-    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
-        Wait::wait_for_high(self).await
-    }
-
-    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
-        Wait::wait_for_low(self).await
-    }
-
-    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
-        Wait::wait_for_rising_edge(self).await
-    }
-
-    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
-        Wait::wait_for_falling_edge(self).await
-    }
+    // TIM1 is on APB2
+    let tim1_clock = rcc::clocks(&p.RCC).pclk2_tim.to_hertz().unwrap();
+    let d5 = SharedPwm::new(pwm.clone(), timer::Channel::Ch2, tim1_clock);
+    let d6 = SharedPwm::new(pwm.clone(), timer::Channel::Ch1, tim1_clock);
 
-    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
-        Wait::wait_for_any_edge(self).await
-    }
+    Platform::new(i2c1, a0, a2, a3, d4, d5, d6, sampler)
 }
@@ -0,0 +1,44 @@
+use embassy_stm32::adc::{Adc, AnyAdcChannel, SampleTime};
+use embassy_stm32::peripherals::ADC1;
+use embassy_time::{Duration, Ticker};
+use fugit::HertzU32;
+
+use crate::platform::stm32::vrefint_reference;
+use crate::platform::{ScanBuffer, SCAN_CHANNELS};
+
+/// Rate at which the analog inputs are scanned. Every scan wakes the MCU from STOP2, so higher
+/// rates would keep it out of STOP2 for most of the time.
+pub const SAMPLE_RATE: HertzU32 = HertzU32::Hz(100);
+
+/// Address of VREFINT_CAL on the STM32L476.
+const VREFINT_CAL_ADDRESS: usize = 0x1fff_75aa;
+/// VDDA at which VREFINT_CAL was taken.
+const VREFINT_CAL_MV: u16 = 3000;
+
+/// Scan `channels` at [`SAMPLE_RATE`] and push the results into `buffer`.
+///
+/// The ADC can't run in STOP2, so rather than converting continuously, each scan is triggered by a
+/// timer and the MCU is stopped again in between. Each scan also measures VREFINT to update the
+/// reference of `buffer`, as the supply voltage drops along with the battery voltage.
+pub async fn scan(
+    mut adc: Adc<'static, ADC1>,
+    mut channels: [AnyAdcChannel<ADC1>; SCAN_CHANNELS],
+    buffer: &'static ScanBuffer,
+) {
+    // VREFINT needs a sampling time of at least 4 µs
+    adc.set_sample_time(SampleTime::CYCLES247_5);
+    let mut vrefint_channel = adc.enable_vrefint();
+
+    let mut ticker = Ticker::every(Duration::from_hz(SAMPLE_RATE.to_Hz() as u64));
+    loop {
+        let scan = channels
+            .each_mut()
+            .map(|channel| adc.blocking_read(channel));
+        let vrefint = adc.blocking_read(&mut vrefint_channel);
+        if let Some(reference) = vrefint_reference(VREFINT_CAL_ADDRESS, VREFINT_CAL_MV, vrefint) {
+            buffer.set_reference(reference);
+        }
+        buffer.push(scan);
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,131 @@
+//! Platform for the Nucleo-L476RG, optimized for running from batteries.
+//!
+//! The firmware runs on embassy's low-power executor on this board (see [`low_power::Executor`]).
+//! Whenever all tasks are waiting, e.g. between the frames of the main loop, the executor puts the
+//! MCU into STOP2 and lets the RTC wake it up again in time for the next timer to expire. Pins with
+//! an external interrupt, like the button on D4, wake it up as well. Peripherals that can't operate
+//! in STOP2 keep the MCU in the regular sleep mode while they are busy.
+//!
+//! The sensor kit plugs into the Arduino header:
+//!
+//! | Header | STM32L476 | Peripheral      |
+//! | ------ | --------- | --------------- |
+//! | SDA    | PB9       | I2C1            |
+//! | SCL    | PB8       | I2C1            |
+//! | A0     | PA0       | ADC1 (IN5)      |
+//! | A2     | PA4       | ADC1 (IN9)      |
+//! | A3     | PB0       | ADC1 (IN15)     |
+//! | D4     | PB5       | EXTI5           |
+//! | D5     | PB4       | TIM3, channel 1 |
+//! | D6     | PB10      | TIM2, channel 3 |
+
+mod adc;
+
+use super::stm32::pwm::SharedPwm;
+use super::{scan_inputs, Platform, ScanInput};
+
+use adc::{scan, SAMPLE_RATE};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use embassy_stm32::{
+    adc::{Adc as HalAdc, AdcChannel},
+    bind_interrupts,
+    exti::ExtiInput,
+    gpio,
+    i2c::{self, I2c as HalI2c},
+    low_power,
+    mode::Async,
+    rcc::{self, LsConfig},
+    rtc::{Rtc, RtcConfig},
+    time::Hertz,
+    timer::{
+        self,
+        low_level::CountingMode,
+        simple_pwm::{PwmPin, SimplePwm},
+    },
+};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex};
+use sensor_kit_core::peripherals::Pwm;
+use static_cell::StaticCell;
+
+bind_interrupts!(struct Irqs {
+    I2C1_ER => i2c::ErrorInterruptHandler<embassy_stm32::peripherals::I2C1>;
+    I2C1_EV => i2c::EventInterruptHandler<embassy_stm32::peripherals::I2C1>;
+});
+
+pub type I2c<'a> = HalI2c<'a, Async>;
+pub type PinError = core::convert::Infallible;
+
+/// RTC waking the MCU up from STOP2.
+static RTC: StaticCell<Rtc> = StaticCell::new();
+
+pub fn platform<'a>(
+) -> Platform<HalI2c<'a, Async>, ScanInput, Box<dyn Pwm + Send + 'a>, ExtiInput<'a>> {
+    let mut config = embassy_stm32::Config::default();
+    // The RTC runs from the 32.768 kHz crystal on the board, which keeps running in STOP2.
+    config.rcc.ls = LsConfig::default_lse();
+    // Keeping the debug interface powered in STOP2 would draw more than the MCU itself. As a
+    // consequence, a debug probe loses its connection while the MCU is stopped.
+    config.enable_debug_during_sleep = false;
+    let p = embassy_stm32::init(config);
+
+    let rtc = Rtc::new(p.RTC, RtcConfig::default());
+    low_power::stop_with_rtc(RTC.init(rtc));
+
+    let i2c1 = I2c::new(
+        p.I2C1,
+        p.PB8,
+        p.PB9,
+        Irqs,
+        p.DMA1_CH6,
+        p.DMA1_CH7,
+        Hertz::khz(400),
+        Default::default(),
+    );
+
+    let adc = HalAdc::new(p.ADC1);
+    let channels = [
+        p.PA0.degrade_adc(),
+        p.PA4.degrade_adc(),
+        p.PB0.degrade_adc(),
+    ];
+
+    let (scan_buffer, a0, a2, a3) = scan_inputs(SAMPLE_RATE);
+    let sampler = Box::pin(scan(adc, channels, scan_buffer));
+
+    let d4 = ExtiInput::new(p.PB5, p.EXTI5, gpio::Pull::Down);
+
+    // D5 and D6 are on different timers, each one only driving a single channel.
+    let d5 = PwmPin::new_ch1(p.PB4, gpio::OutputType::PushPull);
+    let pwm_tim3 = SimplePwm::new(
+        p.TIM3,
+        Some(d5),
+        None,
+        None,
+        None,
+        Hertz::hz(50),
+        CountingMode::EdgeAlignedUp,
+    );
+    let pwm_tim3: Arc<Mutex<CriticalSectionRawMutex, _>> = Arc::new(Mutex::new(pwm_tim3));
+
+    let d6 = PwmPin::new_ch3(p.PB10, gpio::OutputType::PushPull);
+    let pwm_tim2 = SimplePwm::new(
+        p.TIM2,
+        None,
+        None,
+        Some(d6),
+        None,
+        Hertz::hz(50),
+        CountingMode::EdgeAlignedUp,
+    );
+    let pwm_tim2: Arc<Mutex<CriticalSectionRawMutex, _>> = Arc::new(Mutex::new(pwm_tim2));
+
+    // TIM2 and TIM3 are both on APB1
+    let timer_clock = rcc::clocks(&p.RCC).pclk1_tim.to_hertz().unwrap();
+    let d5: Box<dyn Pwm + Send> =
+        Box::new(SharedPwm::new(pwm_tim3, timer::Channel::Ch1, timer_clock));
+    let d6: Box<dyn Pwm + Send> =
+        Box::new(SharedPwm::new(pwm_tim2, timer::Channel::Ch3, timer_clock));
+
+    Platform::new(i2c1, a0, a2, a3, d4, d5, d6, sampler)
+}
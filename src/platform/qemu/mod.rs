@@ -0,0 +1,80 @@
+//! Firmware running on an LM3S6965EVB board emulated by QEMU.
+//!
+//! The board has none of the sensor kit's peripherals, so they are provided by
+//! [`emulated`](super::emulated) following the [demo script](../../../sim/demo.script), just like
+//! in the simulator. The executor, heap and time driver on the other hand are the real thing, so
+//! everything but the hardware drivers runs as it would on a board. Logs are written via
+//! semihosting, and the emulator exits once the end of the script is reached.
+
+mod pwm;
+mod time_driver;
+
+use super::emulated::adc;
+use super::emulated::i2c::{Bmp280, Dht20, Framebuffer, Lis3dh, SimI2c, Ssd1315};
+use super::emulated::script::{Channel, Script};
+use super::emulated::SimPin;
+use super::{scan_inputs, Platform, ScanInput};
+use pwm::QemuPwm;
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use embassy_time::Instant;
+use fugit::HertzU32;
+
+pub type I2c = SimI2c;
+pub type PinError = core::convert::Infallible;
+
+/// Script driving the emulated inputs.
+const SCRIPT: &str = include_str!("../../../sim/demo.script");
+
+/// Rate at which the analog inputs are sampled. A fraction of the 1 kHz tick rate, to leave the
+/// emulated CPU time for the main loop.
+const SAMPLE_RATE: HertzU32 = HertzU32::Hz(250);
+
+pub fn platform() -> Platform<SimI2c, ScanInput, QemuPwm, SimPin> {
+    let core = cortex_m::Peripherals::take().unwrap();
+    time_driver::init(core.SYST);
+
+    let script = Arc::new(Script::parse(SCRIPT).expect("Invalid script"));
+    if let Some(end) = script.end() {
+        time_driver::exit_at(Instant::now() + end);
+    }
+
+    let mut frames = FrameLogger::default();
+    let display = Ssd1315::new(move |frame| frames.log(frame));
+
+    let i2c = SimI2c::new()
+        .attach(0x3c, display)
+        .attach(0x38, Dht20::new(script.clone()))
+        .attach(0x77, Bmp280::new(script.clone()))
+        .attach(0x19, Lis3dh::new(script.clone()));
+
+    let (scan_buffer, a0, a2, a3) = scan_inputs(SAMPLE_RATE);
+    let sampler = Box::pin(adc::scan(script.clone(), scan_buffer));
+
+    let d4 = SimPin::new(script.clone(), Channel::D4);
+
+    let d5 = QemuPwm::new("D5");
+    let d6 = QemuPwm::new("D6");
+
+    Platform::new(i2c, a0, a2, a3, d4, d5, d6, sampler)
+}
+
+/// Logs each distinct frame shown on the display.
+#[derive(Default)]
+struct FrameLogger {
+    count: usize,
+    last_frame: Option<Framebuffer>,
+}
+
+impl FrameLogger {
+    fn log(&mut self, frame: &Framebuffer) {
+        if self.last_frame.as_ref() == Some(frame) {
+            return;
+        }
+        self.last_frame = Some(frame.clone());
+
+        defmt::info!("Frame {}: {} pixels lit", self.count, frame.lit_pixels());
+        self.count += 1;
+    }
+}
@@ -0,0 +1,78 @@
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use fugit::HertzU32;
+
+use sensor_kit_core::peripherals::{DutyCycle, PeripheralError, Pwm, PwmSettings};
+
+/// Emulated PWM output, logging every change of its state.
+pub struct QemuPwm {
+    name: &'static str,
+    duty_cycle_pct: u8,
+    frequency: Option<HertzU32>,
+    enabled: bool,
+}
+
+impl QemuPwm {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            duty_cycle_pct: 0,
+            frequency: None,
+            enabled: false,
+        }
+    }
+
+    /// The duty cycle is applied exactly, in steps of 1%.
+    fn duty_cycle(&self) -> DutyCycle {
+        DutyCycle {
+            high: self.duty_cycle_pct as u32,
+            steps: 100,
+        }
+    }
+}
+
+#[async_trait]
+impl Pwm for QemuPwm {
+    async fn set_duty_cycle_percent(&mut self, percent: u8) -> Result<DutyCycle, PeripheralError> {
+        if percent > 100 {
+            return Err(PeripheralError::Pwm);
+        }
+        if percent != self.duty_cycle_pct {
+            defmt::info!("{}: duty cycle {}%", self.name, percent);
+            self.duty_cycle_pct = percent;
+        }
+        Ok(self.duty_cycle())
+    }
+
+    async fn set_frequency(&mut self, freq: HertzU32) -> Result<HertzU32, PeripheralError> {
+        if Some(freq) != self.frequency {
+            defmt::info!("{}: frequency {}Hz", self.name, freq.to_Hz());
+            self.frequency = Some(freq);
+        }
+        Ok(freq)
+    }
+
+    async fn enable(&mut self) -> Result<(), PeripheralError> {
+        if !self.enabled {
+            defmt::info!("{}: enabled", self.name);
+            self.enabled = true;
+        }
+        Ok(())
+    }
+
+    async fn disable(&mut self) -> Result<(), PeripheralError> {
+        if self.enabled {
+            defmt::info!("{}: disabled", self.name);
+            self.enabled = false;
+        }
+        Ok(())
+    }
+
+    async fn settings(&mut self) -> Result<PwmSettings, PeripheralError> {
+        Ok(PwmSettings {
+            frequency: self.frequency,
+            duty_cycle: self.duty_cycle(),
+            enabled: self.enabled,
+        })
+    }
+}
@@ -0,0 +1,101 @@
+//! Time driver based on the SysTick timer, as the emulated board has no HAL providing one.
+
+use core::cell::RefCell;
+use core::task::Waker;
+use cortex_m::peripheral::syst::SystClkSource;
+use cortex_m::peripheral::SYST;
+use cortex_m_rt::exception;
+use cortex_m_semihosting::debug;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_time::Instant;
+use embassy_time_driver::{Driver, TICK_HZ};
+use embassy_time_queue_utils::Queue;
+
+/// Core clock of the emulated LM3S6965 after reset.
+const SYSCLK_HZ: u32 = 12_000_000;
+/// Frequency of SysTick interrupts.
+const SYSTICK_HZ: u32 = 1_000;
+/// Time driver ticks elapsing between two SysTick interrupts.
+const TICKS_PER_SYSTICK: u64 = TICK_HZ / SYSTICK_HZ as u64;
+
+struct State {
+    /// Current time in ticks.
+    now: u64,
+    /// Time at which the next timer expires.
+    alarm: u64,
+    /// Time at which the emulator is stopped, if any.
+    end: Option<u64>,
+    queue: Queue,
+}
+
+struct SysTickDriver {
+    state: Mutex<CriticalSectionRawMutex, RefCell<State>>,
+}
+
+embassy_time_driver::time_driver_impl!(static DRIVER: SysTickDriver = SysTickDriver {
+    state: Mutex::new(RefCell::new(State {
+        now: 0,
+        alarm: u64::MAX,
+        end: None,
+        queue: Queue::new(),
+    })),
+});
+
+impl SysTickDriver {
+    fn on_systick(&self) {
+        let ended = self.state.lock(|state| {
+            let mut state = state.borrow_mut();
+            state.now += TICKS_PER_SYSTICK;
+
+            if state.now >= state.alarm {
+                let now = state.now;
+                state.alarm = state.queue.next_expiration(now);
+            }
+
+            state.end.is_some_and(|end| state.now >= end)
+        });
+
+        if ended {
+            defmt::info!("End of script reached");
+            debug::exit(debug::EXIT_SUCCESS);
+        }
+    }
+}
+
+impl Driver for SysTickDriver {
+    fn now(&self) -> u64 {
+        self.state.lock(|state| state.borrow().now)
+    }
+
+    fn schedule_wake(&self, at: u64, waker: &Waker) {
+        self.state.lock(|state| {
+            let mut state = state.borrow_mut();
+            if state.queue.schedule_wake(at, waker) {
+                let now = state.now;
+                state.alarm = state.queue.next_expiration(now);
+            }
+        });
+    }
+}
+
+/// Start the SysTick timer driving the time driver.
+pub fn init(mut syst: SYST) {
+    syst.set_clock_source(SystClkSource::Core);
+    syst.set_reload(SYSCLK_HZ / SYSTICK_HZ - 1);
+    syst.clear_current();
+    syst.enable_interrupt();
+    syst.enable_counter();
+}
+
+/// Exit the emulator once `end` has been reached.
+pub fn exit_at(end: Instant) {
+    DRIVER
+        .state
+        .lock(|state| state.borrow_mut().end = Some(end.as_ticks()));
+}
+
+#[exception]
+fn SysTick() {
+    DRIVER.on_systick();
+}
@@ -0,0 +1,44 @@
+use embassy_rp::adc::{Adc, Async, Channel};
+use embassy_rp::dma;
+use fugit::HertzU32;
+use sensor_kit_core::peripherals::AdcReference;
+
+use crate::platform::{ScanBuffer, SCAN_CHANNELS};
+
+/// Clock of the ADC.
+const ADC_CLOCK_HZ: u32 = 48_000_000;
+/// Scans read per DMA transfer.
+const SCANS_PER_TRANSFER: usize = 64;
+
+/// Rate at which each input is sampled.
+pub const SAMPLE_RATE: HertzU32 = HertzU32::kHz(8);
+
+/// Reference of the ADC, given the voltage applied to its ADC_VREF pin in millivolts.
+pub const fn reference(vref_mv: u16) -> AdcReference {
+    AdcReference::new(4096, vref_mv)
+}
+
+/// Continuously sample `channels` into `buffer` at [`SAMPLE_RATE`].
+///
+/// The ADC converts the channels round-robin, paced by its clock divider, while DMA moves the
+/// results to memory. Restarting the transfer after each block leaves only a short gap between
+/// blocks.
+pub async fn scan<D: dma::Channel>(
+    mut adc: Adc<'static, Async>,
+    mut channels: [Channel<'static>; SCAN_CHANNELS],
+    mut dma: D,
+    buffer: &'static ScanBuffer,
+) {
+    // A conversion is started every `div + 1` ADC clock cycles
+    let div = (ADC_CLOCK_HZ / (SAMPLE_RATE.to_Hz() * SCAN_CHANNELS as u32) - 1) as u16;
+    let mut samples = [0u16; SCANS_PER_TRANSFER * SCAN_CHANNELS];
+    loop {
+        if adc
+            .read_many_multichannel(&mut channels, &mut samples, div, &mut dma)
+            .await
+            .is_ok()
+        {
+            buffer.push_interleaved(&samples);
+        }
+    }
+}
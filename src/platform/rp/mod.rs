@@ -0,0 +1,36 @@
+//! Peripheral implementations shared by the RP2040 and RP235x platforms.
+
+pub mod adc;
+pub mod pwm;
+
+use super::DynSafeWait;
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use embassy_rp::gpio::Input;
+use embedded_hal_async::digital::Wait;
+
+#[async_trait]
+impl DynSafeWait for Input<'_> {
+    type Error = core::convert::Infallible;
+
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_high(&mut self).await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_low(&mut self).await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_rising_edge(&mut self).await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_falling_edge(&mut self).await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_any_edge(&mut self).await
+    }
+}
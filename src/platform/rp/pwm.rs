@@ -0,0 +1,143 @@
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use embassy_rp::pwm::{self, Pwm, SetDutyCycle};
+use embassy_time::Timer;
+use fixed::{types::extra::U4, FixedU16};
+use fugit::HertzU32;
+
+use sensor_kit_core::peripherals::{DutyCycle, PeripheralError, Pwm as PwmTrait, PwmSettings};
+
+/// Smallest clock divider, in sixteenths.
+const DIVIDER_MIN: u32 = 1 << 4;
+/// Largest clock divider (255 + 15/16), in sixteenths.
+const DIVIDER_MAX: u32 = (1 << 12) - 1;
+/// Largest number of counter steps per period, i.e. TOP + 1.
+const STEPS_MAX: u32 = 1 << 16;
+
+pub struct PwmPin<'a> {
+    slice: Pwm<'a>,
+    config: pwm::Config,
+    /// Duty cycle in percent, applied while the output is enabled and reapplied whenever the
+    /// frequency changes.
+    duty_cycle_pct: u8,
+    /// Achieved frequency, if set.
+    frequency: Option<HertzU32>,
+}
+
+impl<'a> PwmPin<'a> {
+    pub fn new(mut slice: Pwm<'a>, config: pwm::Config) -> Self {
+        slice.set_config(&config);
+        Self {
+            slice,
+            config,
+            duty_cycle_pct: 0,
+            frequency: None,
+        }
+    }
+
+    /// Apply the duty cycle to the slice, unless the output is disabled. A disabled slice holds
+    /// its output low only as long as the compare value stays 0.
+    fn apply_duty_cycle(&mut self) -> Result<(), PeripheralError> {
+        if !self.config.enable {
+            return Ok(());
+        }
+        self.slice
+            .set_duty_cycle_percent(self.duty_cycle_pct)
+            .map_err(|_| PeripheralError::Pwm)
+    }
+
+    /// Duty cycle as applied by `SetDutyCycle::set_duty_cycle_percent`.
+    fn duty_cycle(&self) -> DutyCycle {
+        let steps = self.slice.max_duty_cycle() as u32;
+        DutyCycle {
+            high: steps * self.duty_cycle_pct as u32 / 100,
+            steps,
+        }
+    }
+}
+
+/// Select clock divider (in sixteenths) and TOP value producing `freq_hz` from `clock_hz`, and
+/// return them along with the frequency they actually achieve.
+///
+/// The smallest divider that lets the 16 bit counter cover a whole period is used, which yields
+/// the finest duty cycle resolution. Its fractional part keeps the frequency close to the requested
+/// one. Returns `None` if the frequency is out of reach, or too high to leave room for more than
+/// one counter step.
+fn divider_and_top(clock_hz: u32, freq_hz: u32) -> Option<(u16, u16, HertzU32)> {
+    if freq_hz == 0 {
+        return None;
+    }
+
+    // Period length in sixteenths of a clock cycle, i.e. divider * (TOP + 1)
+    let period = ((clock_hz as u64) << 4) / freq_hz as u64;
+
+    let divider = period.div_ceil(STEPS_MAX as u64).max(DIVIDER_MIN as u64);
+    if divider > DIVIDER_MAX as u64 {
+        return None;
+    }
+
+    let steps = (period + divider / 2) / divider;
+    if steps < 2 {
+        return None;
+    }
+
+    let achieved_hz = (((clock_hz as u64) << 4) + divider * steps / 2) / (divider * steps);
+    Some((
+        divider as u16,
+        (steps - 1) as u16,
+        HertzU32::Hz(achieved_hz as u32),
+    ))
+}
+
+#[async_trait]
+impl<'a> PwmTrait for PwmPin<'a> {
+    async fn set_duty_cycle_percent(&mut self, percent: u8) -> Result<DutyCycle, PeripheralError> {
+        if percent > 100 {
+            return Err(PeripheralError::Pwm);
+        }
+        self.duty_cycle_pct = percent;
+        self.apply_duty_cycle()?;
+        Ok(self.duty_cycle())
+    }
+
+    async fn enable(&mut self) -> Result<(), PeripheralError> {
+        self.config.enable = true;
+        self.slice.set_config(&self.config);
+        // Restore the duty cycle cleared by `disable`
+        self.apply_duty_cycle()
+    }
+
+    async fn disable(&mut self) -> Result<(), PeripheralError> {
+        self.slice
+            .set_duty_cycle(0)
+            .map_err(|_| PeripheralError::Pwm)?;
+        Timer::after_millis(10).await; // Ensure the pin actually goes low
+        self.config.enable = false;
+        self.slice.set_config(&self.config);
+        Ok(())
+    }
+
+    async fn set_frequency(&mut self, freq: HertzU32) -> Result<HertzU32, PeripheralError> {
+        let clock_freq_hz = embassy_rp::clocks::clk_sys_freq();
+        let (divider, top, achieved) =
+            divider_and_top(clock_freq_hz, freq.to_Hz()).ok_or(PeripheralError::Pwm)?;
+
+        self.config.top = top;
+        self.config.divider = FixedU16::<U4>::from_bits(divider);
+
+        self.slice.set_config(&self.config);
+        // The compare value depends on TOP
+        self.apply_duty_cycle()?;
+
+        self.frequency = Some(achieved);
+        Ok(achieved)
+    }
+
+    async fn settings(&mut self) -> Result<PwmSettings, PeripheralError> {
+        Ok(PwmSettings {
+            frequency: self.frequency,
+            duty_cycle: self.duty_cycle(),
+            enabled: self.config.enable,
+        })
+    }
+}
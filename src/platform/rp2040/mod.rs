@@ -0,0 +1,75 @@
+//! Platform for the original Raspberry Pi Pico (RP2040).
+//!
+//! The Pico is pin compatible with the Pico2, so the sensor kit is wired up the same way:
+//!
+//! | Pico | Sensor Kit | Peripheral            |
+//! | ---- | ---------- | --------------------- |
+//! | GP0  | SDA        | I2C0                  |
+//! | GP1  | SCL        | I2C0                  |
+//! | GP2  | D4         | GPIO                  |
+//! | GP3  | D5         | PWM slice 1, output B |
+//! | GP4  | D6         | PWM slice 2, output A |
+//! | GP26 | A0         | ADC channel 0         |
+//! | GP27 | A2         | ADC channel 1         |
+//! | GP28 | A3         | ADC channel 2         |
+
+use super::rp::adc::{self as rp_adc, scan, SAMPLE_RATE};
+use super::rp::pwm::PwmPin;
+use super::{scan_inputs, Platform, ScanInput};
+
+use alloc::boxed::Box;
+use embassy_rp::{
+    adc::{self as hal_adc, Adc as HalAdc},
+    bind_interrupts,
+    gpio::{self, Input},
+    i2c::{self, I2c as HalI2c},
+    peripherals::{self, I2C0},
+    pwm::Pwm,
+};
+
+pub type I2c<'a> = HalI2c<'a, I2C0, i2c::Async>;
+pub type PinError = core::convert::Infallible;
+
+/// Voltage at ADC_VREF in millivolts. The board connects it to the 3.3V supply through a filter;
+/// adjust this when feeding it from a precision reference instead.
+const ADC_VREF_MV: u16 = 3300;
+
+bind_interrupts!(struct Irqs {
+    I2C0_IRQ => i2c::InterruptHandler<peripherals::I2C0>;
+    ADC_IRQ_FIFO => hal_adc::InterruptHandler;
+});
+
+pub fn platform<'a>() -> Platform<I2c<'a>, ScanInput, PwmPin<'a>, Input<'a>> {
+    let p = embassy_rp::init(Default::default());
+
+    let mut i2c_config: i2c::Config = Default::default();
+    i2c_config.frequency = 400000;
+    let i2c0 = I2c::new_async(p.I2C0, p.PIN_1, p.PIN_0, Irqs, i2c_config);
+
+    let adc = HalAdc::new(p.ADC, Irqs, Default::default());
+
+    // ADC inputs 0 to 2 are on GP26 to GP28
+    let a0_channel = hal_adc::Channel::new_pin(p.PIN_26, gpio::Pull::Up);
+    let a2_channel = hal_adc::Channel::new_pin(p.PIN_27, gpio::Pull::Down);
+    let a3_channel = hal_adc::Channel::new_pin(p.PIN_28, gpio::Pull::Down);
+
+    let (scan_buffer, a0, a2, a3) = scan_inputs(SAMPLE_RATE);
+    scan_buffer.set_reference(rp_adc::reference(ADC_VREF_MV));
+    let sampler = Box::pin(scan(
+        adc,
+        [a0_channel, a2_channel, a3_channel],
+        p.DMA_CH0,
+        scan_buffer,
+    ));
+
+    // GPn is connected to output A (even n) or B (odd n) of slice n / 2 mod 8
+    let pwm_slice1 = Pwm::new_output_b(p.PWM_SLICE1, p.PIN_3, Default::default());
+    let d5 = PwmPin::new(pwm_slice1, Default::default());
+
+    let pwm_slice2 = Pwm::new_output_a(p.PWM_SLICE2, p.PIN_4, Default::default());
+    let d6 = PwmPin::new(pwm_slice2, Default::default());
+
+    let d4 = Input::new(p.PIN_2, gpio::Pull::Down);
+
+    Platform::new(i2c0, a0, a2, a3, d4, d5, d6, sampler)
+}
@@ -1,12 +1,8 @@
-mod adc;
-mod pwm;
+use super::rp::adc::{self as rp_adc, scan, SAMPLE_RATE};
+use super::rp::pwm::PwmPin;
+use super::{scan_inputs, Platform, ScanInput};
 
-use super::{DynSafeWait, Platform};
-
-use adc::Adc;
 use alloc::boxed::Box;
-use alloc::sync::Arc;
-use async_trait::async_trait;
 use embassy_rp::{
     adc::{self as hal_adc, Adc as HalAdc},
     bind_interrupts,
@@ -15,20 +11,20 @@ use embassy_rp::{
     peripherals::{self, I2C0},
     pwm::Pwm,
 };
-use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex};
-use embedded_hal_async::digital::Wait;
-use pwm::PwmPin;
 
 pub type I2c<'a> = HalI2c<'a, I2C0, i2c::Async>;
 pub type PinError = core::convert::Infallible;
 
+/// Voltage at ADC_VREF in millivolts. The board connects it to the 3.3V supply through a filter;
+/// adjust this when feeding it from a precision reference instead.
+const ADC_VREF_MV: u16 = 3300;
+
 bind_interrupts!(struct Irqs {
     I2C0_IRQ => i2c::InterruptHandler<peripherals::I2C0>;
     ADC_IRQ_FIFO => hal_adc::InterruptHandler;
 });
 
-pub fn platform<'a>() -> Platform<I2c<'a>, Adc<'a, CriticalSectionRawMutex>, PwmPin<'a>, Input<'a>>
-{
+pub fn platform<'a>() -> Platform<I2c<'a>, ScanInput, PwmPin<'a>, Input<'a>> {
     let p = embassy_rp::init(Default::default());
 
     let mut i2c_config: i2c::Config = Default::default();
@@ -36,16 +32,20 @@ pub fn platform<'a>() -> Platform<I2c<'a>, Adc<'a, CriticalSectionRawMutex>, Pwm
     let i2c0 = I2c::new_async(p.I2C0, p.PIN_1, p.PIN_0, Irqs, i2c_config);
 
     let adc = HalAdc::new(p.ADC, Irqs, Default::default());
-    let adc: Arc<Mutex<CriticalSectionRawMutex, _>> = Arc::new(Mutex::new(adc));
 
     let a0_channel = hal_adc::Channel::new_pin(p.PIN_26, gpio::Pull::Up);
 
     let a2_channel = hal_adc::Channel::new_pin(p.PIN_27, gpio::Pull::Down);
     let a3_channel = hal_adc::Channel::new_pin(p.PIN_28, gpio::Pull::Down);
 
-    let a0 = Adc::new(adc.clone(), a0_channel, None);
-    let a2 = Adc::new(adc.clone(), a2_channel, Some(2800));
-    let a3 = Adc::new(adc.clone(), a3_channel, Some(1500));
+    let (scan_buffer, a0, a2, a3) = scan_inputs(SAMPLE_RATE);
+    scan_buffer.set_reference(rp_adc::reference(ADC_VREF_MV));
+    let sampler = Box::pin(scan(
+        adc,
+        [a0_channel, a2_channel, a3_channel],
+        p.DMA_CH0,
+        scan_buffer,
+    ));
 
     let pwm_slice1 = Pwm::new_output_b(p.PWM_SLICE1, p.PIN_3, Default::default());
     let d5 = PwmPin::new(pwm_slice1, Default::default());
@@ -55,30 +55,5 @@ pub fn platform<'a>() -> Platform<I2c<'a>, Adc<'a, CriticalSectionRawMutex>, Pwm
 
     let d4 = Input::new(p.PIN_2, gpio::Pull::Down);
 
-    Platform::new(i2c0, a0, a2, a3, d4, d5, d6)
-}
-
-#[async_trait]
-impl DynSafeWait for Input<'_> {
-    type Error = core::convert::Infallible;
-
-    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
-        Wait::wait_for_high(&mut self).await
-    }
-
-    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
-        Wait::wait_for_low(&mut self).await
-    }
-
-    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
-        Wait::wait_for_rising_edge(&mut self).await
-    }
-
-    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
-        Wait::wait_for_falling_edge(&mut self).await
-    }
-
-    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
-        Wait::wait_for_any_edge(&mut self).await
-    }
+    Platform::new(i2c0, a0, a2, a3, d4, d5, d6, sampler)
 }
@@ -0,0 +1,170 @@
+//! Headless simulator running the firmware on the host.
+//!
+//! All peripherals of the sensor kit are simulated: analog inputs, the button and the I2C devices
+//! are provided by [`emulated`](super::emulated) and follow a [`Script`], and PWM outputs log their
+//! state. Frames shown on the display can be written to a directory as PNG or PBM images.
+//!
+//! Usage: `sensor-kit [--script <file>] [--frames <directory>] [--format png|pbm]`
+
+mod pwm;
+
+use super::emulated::adc;
+use super::emulated::i2c::{Bmp280, Dht20, Framebuffer, Lis3dh, SimI2c, Ssd1315};
+use super::emulated::script::{Channel, Script};
+use super::emulated::SimPin;
+use super::{scan_inputs, Platform, ScanInput};
+use pwm::SimPwm;
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use fugit::HertzU32;
+use std::path::PathBuf;
+use std::{env, fs, process, thread};
+
+pub type I2c = SimI2c;
+pub type PinError = core::convert::Infallible;
+
+/// Rate at which the analog inputs are sampled.
+const SAMPLE_RATE: HertzU32 = HertzU32::kHz(1);
+
+pub fn platform() -> Platform<SimI2c, ScanInput, SimPwm, SimPin> {
+    let options = Options::from_args();
+
+    let script = match &options.script {
+        Some(path) => {
+            let source = fs::read_to_string(path)
+                .unwrap_or_else(|e| exit_with_error(&format!("{}: {e}", path.display())));
+            Script::parse(&source)
+                .unwrap_or_else(|e| exit_with_error(&format!("{}: {e}", path.display())))
+        }
+        None => Script::empty(),
+    };
+    let script = Arc::new(script);
+
+    if let Some(end) = script.end() {
+        thread::spawn(move || {
+            thread::sleep(std::time::Duration::from_millis(end.as_millis()));
+            println!("End of script reached");
+            process::exit(0);
+        });
+    }
+
+    let mut frames = FrameWriter::new(options.frames, options.format);
+    let display = Ssd1315::new(move |frame| frames.write(frame));
+
+    let i2c = SimI2c::new()
+        .attach(0x3c, display)
+        .attach(0x38, Dht20::new(script.clone()))
+        .attach(0x77, Bmp280::new(script.clone()))
+        .attach(0x19, Lis3dh::new(script.clone()));
+
+    let (scan_buffer, a0, a2, a3) = scan_inputs(SAMPLE_RATE);
+    let sampler = Box::pin(adc::scan(script.clone(), scan_buffer));
+
+    let d4 = SimPin::new(script.clone(), Channel::D4);
+
+    let d5 = SimPwm::new("D5");
+    let d6 = SimPwm::new("D6");
+
+    Platform::new(i2c, a0, a2, a3, d4, d5, d6, sampler)
+}
+
+/// Image format used for dumped frames.
+#[derive(Clone, Copy)]
+enum ImageFormat {
+    Png,
+    Pbm,
+}
+
+/// Command line options of the simulator.
+struct Options {
+    /// Script driving the inputs.
+    script: Option<PathBuf>,
+    /// Directory frames are written to.
+    frames: Option<PathBuf>,
+    /// Format of written frames.
+    format: ImageFormat,
+}
+
+impl Options {
+    fn from_args() -> Self {
+        let mut options = Self {
+            script: None,
+            frames: None,
+            format: ImageFormat::Png,
+        };
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .unwrap_or_else(|| exit_with_error(&format!("missing value for {arg}")))
+            };
+            match arg.as_str() {
+                "--script" => options.script = Some(value().into()),
+                "--frames" => options.frames = Some(value().into()),
+                "--format" => {
+                    options.format = match value().as_str() {
+                        "png" => ImageFormat::Png,
+                        "pbm" => ImageFormat::Pbm,
+                        _ => exit_with_error("unknown format, expected png or pbm"),
+                    }
+                }
+                _ => exit_with_error(&format!("unknown argument {arg}")),
+            }
+        }
+
+        options
+    }
+}
+
+/// Writes each distinct frame shown on the display into a directory.
+struct FrameWriter {
+    directory: Option<PathBuf>,
+    format: ImageFormat,
+    count: usize,
+    last_frame: Option<Framebuffer>,
+}
+
+impl FrameWriter {
+    fn new(directory: Option<PathBuf>, format: ImageFormat) -> Self {
+        if let Some(directory) = &directory {
+            fs::create_dir_all(directory)
+                .unwrap_or_else(|e| exit_with_error(&format!("{}: {e}", directory.display())));
+        }
+
+        Self {
+            directory,
+            format,
+            count: 0,
+            last_frame: None,
+        }
+    }
+
+    fn write(&mut self, frame: &Framebuffer) {
+        if self.last_frame.as_ref() == Some(frame) {
+            return;
+        }
+        self.last_frame = Some(frame.clone());
+
+        let Some(directory) = &self.directory else {
+            return;
+        };
+
+        let (data, extension) = match self.format {
+            ImageFormat::Png => (frame.to_png(), "png"),
+            ImageFormat::Pbm => (frame.to_pbm(), "pbm"),
+        };
+        let path = directory.join(format!("frame_{:05}.{extension}", self.count));
+        if let Err(e) = fs::write(&path, data) {
+            eprintln!("Failed to write {}: {e}", path.display());
+        }
+        self.count += 1;
+    }
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("error: {message}");
+    eprintln!("usage: sensor-kit [--script <file>] [--frames <directory>] [--format png|pbm]");
+    process::exit(2);
+}
@@ -0,0 +1,48 @@
+//! Peripheral implementations shared by the STM32 platforms.
+
+pub mod pwm;
+
+use super::DynSafeWait;
+
+use alloc::boxed::Box;
+use async_trait::async_trait;
+use embassy_stm32::exti::ExtiInput;
+use embedded_hal_async::digital::Wait;
+use sensor_kit_core::peripherals::AdcReference;
+
+/// Reference of a 12 bit ADC converting relative to VDDA, derived from a reading `vrefint` of the
+/// internal reference VREFINT.
+///
+/// Each device stores a factory reading of VREFINT, VREFINT_CAL, at `cal_address`, taken at a VDDA
+/// of `cal_mv`. Comparing the two readings yields the actual VDDA, correcting for a supply voltage
+/// other than the nominal 3.3V.
+pub fn vrefint_reference(cal_address: usize, cal_mv: u16, vrefint: u16) -> Option<AdcReference> {
+    // SAFETY: VREFINT_CAL is stored in system memory, which is always mapped and read only.
+    let vrefint_cal = unsafe { core::ptr::read_volatile(cal_address as *const u16) };
+    AdcReference::from_vrefint(4095, cal_mv, vrefint_cal, vrefint)
+}
+
+#[async_trait]
+impl<'a> DynSafeWait for ExtiInput<'a> {
+    type Error = <ExtiInput<'a> as embedded_hal::digital::ErrorType>::Error;
+
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_high(self).await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_low(self).await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_rising_edge(self).await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_falling_edge(self).await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        Wait::wait_for_any_edge(self).await
+    }
+}
@@ -0,0 +1,113 @@
+use sensor_kit_core::peripherals::{DutyCycle, PeripheralError, Pwm, PwmSettings};
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use async_trait::async_trait;
+use embassy_stm32::time::Hertz;
+use embassy_stm32::timer::{simple_pwm::SimplePwm, Channel, GeneralInstance4Channel};
+use embassy_sync::{blocking_mutex::raw::RawMutex, mutex::Mutex};
+
+/// A single channel of a PWM timer, which may be shared with other channels.
+///
+/// All channels of a timer run at the same frequency. The frequency reported by
+/// [`Pwm::settings`] is the one last set through this channel.
+pub struct SharedPwm<'a, M, I>
+where
+    M: RawMutex,
+    I: GeneralInstance4Channel,
+{
+    pwm: Arc<Mutex<M, SimplePwm<'a, I>>>,
+    channel: Channel,
+    /// Clock the timer counts, used for computing the achieved frequency.
+    timer_clock: Hertz,
+    /// Achieved frequency, if set through this channel.
+    frequency: Option<fugit::HertzU32>,
+    /// Duty cycle last set through this channel, reapplied when the frequency changes.
+    duty_cycle_pct: u8,
+}
+
+impl<'a, M, I> SharedPwm<'a, M, I>
+where
+    M: RawMutex,
+    I: GeneralInstance4Channel,
+{
+    pub fn new(pwm: Arc<Mutex<M, SimplePwm<'a, I>>>, channel: Channel, timer_clock: Hertz) -> Self {
+        Self {
+            pwm,
+            channel,
+            timer_clock,
+            frequency: None,
+            duty_cycle_pct: 0,
+        }
+    }
+}
+
+#[async_trait]
+impl<M, I> Pwm for SharedPwm<'_, M, I>
+where
+    M: RawMutex + Send + Sync,
+    I: GeneralInstance4Channel + Send,
+{
+    async fn set_duty_cycle_percent(&mut self, percent: u8) -> Result<DutyCycle, PeripheralError> {
+        let mut pwm = self.pwm.lock().await;
+        let mut channel = pwm.channel(self.channel);
+        channel.set_duty_cycle_percent(percent);
+        self.duty_cycle_pct = percent;
+        Ok(DutyCycle {
+            high: channel.current_duty_cycle() as u32,
+            steps: channel.max_duty_cycle() as u32,
+        })
+    }
+
+    async fn set_frequency(
+        &mut self,
+        freq: fugit::HertzU32,
+    ) -> Result<fugit::HertzU32, PeripheralError> {
+        // A period needs at least two timer ticks to have a duty cycle.
+        let ticks = self.timer_clock.0.checked_div(freq.to_Hz()).unwrap_or(0);
+        if ticks < 2 {
+            return Err(PeripheralError::Pwm);
+        }
+
+        let mut pwm = self.pwm.lock().await;
+        pwm.set_frequency(Hertz::hz(freq.to_Hz()));
+        // The compare value depends on the number of steps per period
+        pwm.channel(self.channel)
+            .set_duty_cycle_percent(self.duty_cycle_pct);
+
+        // The timer driver picks the smallest prescaler that fits the period into the 16 bit
+        // counter, and the number of counter steps per period is what remains after prescaling.
+        let prescaler = (ticks - 1) / (1 << 16) + 1;
+        let steps = pwm.max_duty_cycle() as u32;
+        let achieved = fugit::HertzU32::Hz(self.timer_clock.0 / (prescaler * steps));
+        self.frequency = Some(achieved);
+        Ok(achieved)
+    }
+
+    async fn enable(&mut self) -> Result<(), PeripheralError> {
+        let mut pwm = self.pwm.lock().await;
+        pwm.channel(self.channel).enable();
+        Ok(())
+    }
+
+    async fn disable(&mut self) -> Result<(), PeripheralError> {
+        let mut pwm = self.pwm.lock().await;
+        pwm.channel(self.channel).disable();
+        Ok(())
+    }
+
+    async fn settings(&mut self) -> Result<PwmSettings, PeripheralError> {
+        let mut pwm = self.pwm.lock().await;
+        let channel = pwm.channel(self.channel);
+        Ok(PwmSettings {
+            frequency: self.frequency,
+            // Read back from the timer, as the compare value is only rounded to its steps. The
+            // timer keeps it while the channel is disabled.
+            duty_cycle: DutyCycle {
+                high: channel.current_duty_cycle() as u32,
+                steps: channel.max_duty_cycle() as u32,
+            },
+            enabled: channel.is_enabled(),
+        })
+    }
+}
@@ -1,9 +0,0 @@
-mod filled_circle;
-mod horizontal_bar;
-mod title_frame;
-mod vector_box;
-
-pub use filled_circle::FilledCircle;
-pub use horizontal_bar::HorizontalBar;
-pub use title_frame::TitleFrame;
-pub use vector_box::VectorBox;